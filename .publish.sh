@@ -2,8 +2,15 @@
 
 set -Eeuo pipefail
 
+cargo publish -p document-converter
 cargo publish -p cli-pdf-to-text
+cargo publish -p cli-html-to-text
 cargo publish -p cli-epub-to-text
+cargo publish -p cli-docx-to-text
+cargo publish -p cli-odt-to-text
+cargo publish -p cli-fb2-to-text
+cargo publish -p cli-mobi-to-text
+cargo publish -p cli-rtf-to-text
 cargo publish -p cli-justify
 cargo publish -p cli-text-reader
 cargo publish -p redirect-stderr
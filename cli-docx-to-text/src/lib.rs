@@ -0,0 +1,949 @@
+//! # CLI DOCX to Text Converter
+//!
+//! A Rust library for converting Word documents to plain text without
+//! pandoc.
+//!
+//! ## Features
+//! - Extract the paragraphs of `word/document.xml` in document order
+//! - Split the text into sections at headings
+//! - Number and indent list items the way Word shows them
+//! - Flatten tables to one line per row
+//! - Collect footnotes and endnotes into a section after the text
+//! - Keep the structure as blocks: headings, lists, tables with their header
+//!   row, quotes, code and pictures
+//! - Read the document properties: title, author, subject and language
+//!
+//! ## Usage
+//! ```rust
+//! use cli_docx_to_text::docx_to_text;
+//!
+//! match docx_to_text("path/to/report.docx") {
+//!     Ok(text) => println!("Extracted text: {}", text),
+//!     Err(e) => eprintln!("Error: {}", e),
+//! }
+//! ```
+
+use document_converter::{Block, Span};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{Read, Seek};
+use std::path::Path;
+use xml::attribute::OwnedAttribute;
+use xml::reader::{EventReader, ParserConfig, XmlEvent};
+use zip::ZipArchive;
+use zip::result::ZipError;
+
+/// Version of this crate
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Headings of the first two levels start a new section, deeper ones stay in
+/// the text of their section
+const SECTION_LEVELS: usize = 2;
+
+/// Paragraph styles of preformatted code, as written by Word and pandoc
+const CODE_STYLES: &[&str] = &["SourceCode", "HTMLPreformatted", "Code"];
+
+/// Paragraph styles of quotations, `BlockText` is what pandoc writes
+const QUOTE_STYLES: &[&str] = &["Quote", "IntenseQuote", "BlockText"];
+
+/// Custom error type for DOCX processing errors
+#[derive(Debug)]
+pub enum DocxError {
+  FileNotFound(String),
+  InvalidDocx(String),
+}
+
+impl fmt::Display for DocxError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      DocxError::FileNotFound(path) => {
+        write!(f, "DOCX file not found: {path}")
+      }
+      DocxError::InvalidDocx(msg) => write!(f, "Invalid DOCX format: {msg}"),
+    }
+  }
+}
+
+impl Error for DocxError {}
+
+/// A heading and the text following it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Section {
+  /// The heading, `None` for text before the first heading
+  pub title: Option<String>,
+  /// Nesting depth, 0 for top level sections
+  pub level: usize,
+  /// Plain text starting with the heading, with paragraphs, list items and
+  /// table rows separated by blank lines
+  pub text: String,
+  /// The heading, paragraphs, list items, tables, quotes, code and pictures
+  /// the text is made of
+  pub blocks: Vec<Block>,
+}
+
+/// Document properties from `docProps/core.xml`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DocxMetadata {
+  pub title: Option<String>,
+  pub creator: Option<String>,
+  pub subject: Option<String>,
+  pub description: Option<String>,
+  pub language: Option<String>,
+  pub keywords: Option<String>,
+  /// Creation date as written in the document, e.g. `2024-01-01T09:00:00Z`
+  pub created: Option<String>,
+}
+
+/// Read the document properties of a DOCX file without converting its
+/// content
+///
+/// # Examples
+/// ```rust
+/// use cli_docx_to_text::docx_metadata;
+///
+/// if let Ok(metadata) = docx_metadata("path/to/report.docx") {
+///     println!("{}", metadata.title.unwrap_or_default());
+/// }
+/// ```
+pub fn docx_metadata(file_path: &str) -> Result<DocxMetadata, DocxError> {
+  let mut archive = open_archive(file_path)?;
+  let Some(core) = read_part(&mut archive, "docProps/core.xml")? else {
+    return Ok(DocxMetadata::default());
+  };
+
+  let mut metadata = DocxMetadata::default();
+  let mut field: Option<String> = None;
+  let mut value = String::new();
+  for event in xml_reader(&core) {
+    let Ok(event) = event else { break };
+    match event {
+      XmlEvent::StartElement { name, .. } => {
+        field = Some(name.local_name);
+        value.clear();
+      }
+      XmlEvent::Characters(text) | XmlEvent::CData(text) => {
+        value.push_str(&text);
+      }
+      XmlEvent::EndElement { .. } => {
+        let Some(name) = field.take() else { continue };
+        let value = Some(collapse_whitespace(&value)).filter(|v| !v.is_empty());
+        match name.as_str() {
+          "title" => metadata.title = value,
+          "creator" => metadata.creator = value,
+          "subject" => metadata.subject = value,
+          "description" => metadata.description = value,
+          "language" => metadata.language = value,
+          "keywords" => metadata.keywords = value,
+          "created" => metadata.created = value,
+          _ => {}
+        }
+      }
+      _ => {}
+    }
+  }
+  Ok(metadata)
+}
+
+/// Convert a DOCX file to plain text
+///
+/// # Error Cases
+/// * `DocxError::FileNotFound` - The specified file doesn't exist
+/// * `DocxError::InvalidDocx` - The file is not a Word document or has no
+///   text
+///
+/// # Examples
+/// ```rust
+/// use cli_docx_to_text::docx_to_text;
+///
+/// match docx_to_text("path/to/report.docx") {
+///     Ok(text) => println!("Extracted text: {}", text),
+///     Err(e) => eprintln!("Error: {}", e),
+/// }
+/// ```
+pub fn docx_to_text(file_path: &str) -> Result<String, DocxError> {
+  let sections = docx_sections(file_path)?;
+  Ok(
+    sections
+      .into_iter()
+      .map(|section| section.text)
+      .collect::<Vec<_>>()
+      .join("\n\n"),
+  )
+}
+
+/// Convert a DOCX file to plain text sections
+///
+/// A section starts at each heading of the first two levels, as given by
+/// the paragraph style or outline level. Footnotes and endnotes are marked
+/// `[1]`, `[2]`, ... in the text and collected in a final "Notes" section.
+/// Joining the section texts with blank lines gives the same text as
+/// `docx_to_text`.
+///
+/// # Examples
+/// ```rust
+/// use cli_docx_to_text::docx_sections;
+///
+/// if let Ok(sections) = docx_sections("path/to/report.docx") {
+///     for section in sections {
+///         println!("{}", section.title.unwrap_or_default());
+///     }
+/// }
+/// ```
+pub fn docx_sections(file_path: &str) -> Result<Vec<Section>, DocxError> {
+  let mut archive = open_archive(file_path)?;
+
+  let document_path = relationships(&mut archive, "_rels/.rels")?
+    .remove("officeDocument")
+    .unwrap_or_else(|| "word/document.xml".to_string());
+  let document = read_part(&mut archive, &document_path)?.ok_or_else(|| {
+    DocxError::InvalidDocx(format!("Missing main document '{document_path}'"))
+  })?;
+
+  // Styles, numbering and notes are found through the relationships of the
+  // main document
+  let (dir, file) = document_path.rsplit_once('/').unwrap_or(("", ""));
+  let rels_path = format!("{dir}/_rels/{file}.rels");
+  let parts = relationships(&mut archive, &rels_path)?;
+  let mut read_related = |kind: &str, default: &str| {
+    let target = parts.get(kind).map_or(default, String::as_str);
+    let path = match target.strip_prefix('/') {
+      Some(absolute) => absolute.to_string(),
+      None if dir.is_empty() => target.to_string(),
+      None => format!("{dir}/{target}"),
+    };
+    read_part(&mut archive, &path)
+  };
+  let styles = read_related("styles", "styles.xml")?
+    .map(|xml| heading_levels(&xml))
+    .unwrap_or_default();
+  let numbering = read_related("numbering", "numbering.xml")?
+    .map(|xml| Numbering::parse(&xml))
+    .unwrap_or_default();
+  let footnotes = read_related("footnotes", "footnotes.xml")?
+    .map(|xml| read_notes(&xml, "footnote"))
+    .unwrap_or_default();
+  let endnotes = read_related("endnotes", "endnotes.xml")?
+    .map(|xml| read_notes(&xml, "endnote"))
+    .unwrap_or_default();
+
+  let mut body = Body { styles, numbering, ..Default::default() };
+  body.read(&document);
+  let mut sections = split_sections(body.blocks);
+
+  let notes: Vec<Block> = body
+    .notes
+    .iter()
+    .enumerate()
+    .filter_map(|(index, (kind, id))| {
+      let notes = if *kind == "footnote" { &footnotes } else { &endnotes };
+      notes.get(id).map(|text| Block::ListItem {
+        depth: 0,
+        marker: format!("[{}]", index + 1),
+        spans: vec![Span::plain(text)],
+      })
+    })
+    .collect();
+  if !notes.is_empty() {
+    let title = "Notes".to_string();
+    let heading = Block::Heading { level: 0, spans: vec![Span::plain(&title)] };
+    let blocks: Vec<Block> = std::iter::once(heading).chain(notes).collect();
+    sections.push(Section {
+      title: Some(title),
+      level: 0,
+      text: blocks_text(&blocks),
+      blocks,
+    });
+  }
+
+  if sections.is_empty() {
+    Err(DocxError::InvalidDocx("No readable content found in DOCX".to_string()))
+  } else {
+    Ok(sections)
+  }
+}
+
+fn open_archive(file_path: &str) -> Result<ZipArchive<File>, DocxError> {
+  if !Path::new(file_path).exists() {
+    return Err(DocxError::FileNotFound(file_path.to_string()));
+  }
+  let file = File::open(file_path)
+    .map_err(|e| DocxError::InvalidDocx(format!("Failed to open DOCX: {e}")))?;
+  ZipArchive::new(file)
+    .map_err(|e| DocxError::InvalidDocx(format!("Failed to open DOCX: {e}")))
+}
+
+// Read a part of the package, `None` if the package doesn't have it
+fn read_part<R: Read + Seek>(
+  archive: &mut ZipArchive<R>,
+  name: &str,
+) -> Result<Option<Vec<u8>>, DocxError> {
+  let mut entry = match archive.by_name(name) {
+    Ok(entry) => entry,
+    Err(ZipError::FileNotFound) => return Ok(None),
+    Err(e) => {
+      return Err(DocxError::InvalidDocx(format!(
+        "Failed to read '{name}': {e}"
+      )));
+    }
+  };
+  let mut bytes = Vec::new();
+  entry.read_to_end(&mut bytes).map_err(|e| {
+    DocxError::InvalidDocx(format!("Failed to read '{name}': {e}"))
+  })?;
+  Ok(Some(bytes))
+}
+
+// Map the last segment of each relationship type, e.g. `styles`, to its
+// target
+fn relationships<R: Read + Seek>(
+  archive: &mut ZipArchive<R>,
+  rels_path: &str,
+) -> Result<HashMap<String, String>, DocxError> {
+  let mut targets = HashMap::new();
+  let Some(rels) = read_part(archive, rels_path)? else {
+    return Ok(targets);
+  };
+  for event in xml_reader(&rels) {
+    let Ok(event) = event else { break };
+    if let XmlEvent::StartElement { name, attributes, .. } = event
+      && name.local_name == "Relationship"
+      && let (Some(kind), Some(target)) =
+        (attribute(&attributes, "Type"), attribute(&attributes, "Target"))
+    {
+      let kind = kind.rsplit('/').next().unwrap_or_default().to_string();
+      targets.entry(kind).or_insert(target);
+    }
+  }
+  Ok(targets)
+}
+
+// Map paragraph style ids to the 0-based outline level of the headings they
+// format
+fn heading_levels(xml: &[u8]) -> HashMap<String, usize> {
+  struct Style {
+    name: Option<String>,
+    based_on: Option<String>,
+    outline_level: Option<usize>,
+  }
+
+  let mut styles: HashMap<String, Style> = HashMap::new();
+  let mut current: Option<String> = None;
+  for event in xml_reader(xml) {
+    let Ok(event) = event else { break };
+    match event {
+      XmlEvent::StartElement { name, attributes, .. } => {
+        let value = attribute(&attributes, "val");
+        match name.local_name.as_str() {
+          "style" => {
+            current = attribute(&attributes, "styleId");
+            if let Some(id) = &current {
+              let style =
+                Style { name: None, based_on: None, outline_level: None };
+              styles.insert(id.clone(), style);
+            }
+          }
+          "name" | "basedOn" | "outlineLvl" => {
+            let Some(style) =
+              current.as_ref().and_then(|id| styles.get_mut(id))
+            else {
+              continue;
+            };
+            match name.local_name.as_str() {
+              "name" => style.name = value,
+              "basedOn" => style.based_on = value,
+              _ => style.outline_level = value.and_then(|v| v.parse().ok()),
+            }
+          }
+          _ => {}
+        }
+      }
+      XmlEvent::EndElement { name } if name.local_name == "style" => {
+        current = None;
+      }
+      _ => {}
+    }
+  }
+
+  let mut levels = HashMap::new();
+  for id in styles.keys() {
+    // Follow `basedOn` a few steps, style chains are short and may loop
+    let mut style_id = Some(id);
+    for _ in 0..10 {
+      let Some(style) = style_id.and_then(|id| styles.get(id)) else { break };
+      let level = style
+        .outline_level
+        .or_else(|| style.name.as_deref().and_then(heading_level_from_name));
+      if let Some(level) = level.filter(|&level| level < 9) {
+        levels.insert(id.clone(), level);
+        break;
+      }
+      style_id = style.based_on.as_ref();
+    }
+  }
+  levels
+}
+
+// Outline level of the built-in "heading 1" to "heading 9" and "Title"
+// styles, which also works on the style ids "Heading1" and "Title"
+fn heading_level_from_name(name: &str) -> Option<usize> {
+  let name = name.to_lowercase();
+  if name == "title" {
+    return Some(0);
+  }
+  let number = name.strip_prefix("heading")?.trim();
+  number.parse::<usize>().ok().filter(|&n| n >= 1).map(|n| n - 1)
+}
+
+/// One level of a list definition in `numbering.xml`
+#[derive(Debug, Clone)]
+struct ListLevel {
+  /// `decimal`, `bullet`, `lowerLetter`, `upperRoman`, ...
+  format: String,
+  /// Marker pattern, e.g. `%1.` or `%1.%2`
+  text: String,
+  start: u32,
+}
+
+#[derive(Debug, Default)]
+struct Numbering {
+  // numId to abstractNumId
+  abstract_ids: HashMap<String, String>,
+  // (abstractNumId, ilvl) to the level definition
+  levels: HashMap<(String, u32), ListLevel>,
+  // Number of the last item of each (numId, ilvl) list
+  counters: HashMap<(String, u32), u32>,
+}
+
+impl Numbering {
+  fn parse(xml: &[u8]) -> Self {
+    let mut numbering = Self::default();
+    let mut abstract_id: Option<String> = None;
+    let mut num_id: Option<String> = None;
+    let mut level: Option<(u32, ListLevel)> = None;
+
+    for event in xml_reader(xml) {
+      let Ok(event) = event else { break };
+      match event {
+        XmlEvent::StartElement { name, attributes, .. } => {
+          let value = attribute(&attributes, "val");
+          match name.local_name.as_str() {
+            "abstractNum" => {
+              abstract_id = attribute(&attributes, "abstractNumId");
+            }
+            "num" => num_id = attribute(&attributes, "numId"),
+            "abstractNumId" => {
+              if let (Some(num_id), Some(value)) = (&num_id, value) {
+                numbering.abstract_ids.insert(num_id.clone(), value);
+              }
+            }
+            "lvl" if abstract_id.is_some() => {
+              let ilvl = attribute(&attributes, "ilvl")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0);
+              let definition = ListLevel {
+                format: "decimal".to_string(),
+                text: format!("%{}.", ilvl + 1),
+                start: 1,
+              };
+              level = Some((ilvl, definition));
+            }
+            "numFmt" | "lvlText" | "start" => {
+              let (Some((_, definition)), Some(value)) =
+                (level.as_mut(), value)
+              else {
+                continue;
+              };
+              match name.local_name.as_str() {
+                "numFmt" => definition.format = value,
+                "lvlText" => definition.text = value,
+                _ => definition.start = value.parse().unwrap_or(1),
+              }
+            }
+            _ => {}
+          }
+        }
+        XmlEvent::EndElement { name } => match name.local_name.as_str() {
+          "lvl" => {
+            if let (Some(id), Some((ilvl, definition))) =
+              (&abstract_id, level.take())
+            {
+              numbering.levels.insert((id.clone(), ilvl), definition);
+            }
+          }
+          "abstractNum" => abstract_id = None,
+          "num" => num_id = None,
+          _ => {}
+        },
+        _ => {}
+      }
+    }
+    numbering
+  }
+
+  // Marker of the next item of list `num_id` at level `ilvl`, e.g. `3.` or
+  // `•`, counting the item
+  fn next_marker(&mut self, num_id: &str, ilvl: u32) -> String {
+    let Some(abstract_id) = self.abstract_ids.get(num_id).cloned() else {
+      return String::new();
+    };
+    let definition = |ilvl: u32| self.levels.get(&(abstract_id.clone(), ilvl));
+    let start = definition(ilvl).map_or(1, |level| level.start);
+
+    let key = (num_id.to_string(), ilvl);
+    let count = self.counters.get(&key).map_or(start, |count| count + 1);
+    self.counters.insert(key, count);
+    // A new item restarts the numbering of the levels below it
+    self.counters.retain(|(id, level), _| id != num_id || *level <= ilvl);
+
+    let Some(level) = definition(ilvl) else {
+      return count.to_string();
+    };
+    match level.format.as_str() {
+      "bullet" => "•".to_string(),
+      "none" => String::new(),
+      _ => {
+        let mut marker = level.text.clone();
+        for outer in 0..=ilvl {
+          let outer_level = definition(outer);
+          let number = self
+            .counters
+            .get(&(num_id.to_string(), outer))
+            .copied()
+            .unwrap_or_else(|| outer_level.map_or(1, |level| level.start));
+          let format = outer_level.map_or("decimal", |level| &level.format);
+          marker = marker.replace(
+            &format!("%{}", outer + 1),
+            &format_number(number, format),
+          );
+        }
+        marker
+      }
+    }
+  }
+}
+
+fn format_number(number: u32, format: &str) -> String {
+  match format {
+    "lowerLetter" => letters(number),
+    "upperLetter" => letters(number).to_uppercase(),
+    "lowerRoman" => roman(number).to_lowercase(),
+    "upperRoman" => roman(number),
+    _ => number.to_string(),
+  }
+}
+
+// a, b, ..., z, aa, bb, ... like Word letters lists
+fn letters(number: u32) -> String {
+  let number = number.max(1) - 1;
+  let letter = char::from(b'a' + (number % 26) as u8);
+  letter.to_string().repeat(number as usize / 26 + 1)
+}
+
+fn roman(mut number: u32) -> String {
+  const NUMERALS: [(u32, &str); 13] = [
+    (1000, "M"),
+    (900, "CM"),
+    (500, "D"),
+    (400, "CD"),
+    (100, "C"),
+    (90, "XC"),
+    (50, "L"),
+    (40, "XL"),
+    (10, "X"),
+    (9, "IX"),
+    (5, "V"),
+    (4, "IV"),
+    (1, "I"),
+  ];
+  let mut roman = String::new();
+  for (value, numeral) in NUMERALS {
+    while number >= value {
+      roman.push_str(numeral);
+      number -= value;
+    }
+  }
+  roman
+}
+
+// Text of each footnote or endnote by id, with its paragraphs on one line
+fn read_notes(xml: &[u8], element: &str) -> HashMap<String, String> {
+  let mut notes = HashMap::new();
+  let mut current: Option<(String, String)> = None;
+  let mut in_text = false;
+  for event in xml_reader(xml) {
+    let Ok(event) = event else { break };
+    match event {
+      XmlEvent::StartElement { name, attributes, .. } => {
+        match name.local_name.as_str() {
+          local if local == element => {
+            current =
+              attribute(&attributes, "id").map(|id| (id, String::new()));
+          }
+          "t" => in_text = true,
+          "p" | "tab" | "br" => {
+            if let Some((_, text)) = current.as_mut() {
+              text.push(' ');
+            }
+          }
+          _ => {}
+        }
+      }
+      XmlEvent::Characters(chunk)
+      | XmlEvent::Whitespace(chunk)
+      | XmlEvent::CData(chunk) => {
+        if in_text && let Some((_, text)) = current.as_mut() {
+          text.push_str(&chunk);
+        }
+      }
+      XmlEvent::EndElement { name } => {
+        if name.local_name == "t" {
+          in_text = false;
+        } else if name.local_name == element
+          && let Some((id, text)) = current.take()
+        {
+          let text = collapse_whitespace(&text);
+          if !text.is_empty() {
+            notes.insert(id, text);
+          }
+        }
+      }
+      _ => {}
+    }
+  }
+  notes
+}
+
+#[derive(Default)]
+struct Paragraph {
+  text: String,
+  style: Option<String>,
+  outline_level: Option<usize>,
+  num_id: Option<String>,
+  ilvl: u32,
+  /// Descriptions of the pictures in the paragraph
+  images: Vec<String>,
+}
+
+#[derive(Default)]
+struct Table {
+  header: bool,
+  rows: Vec<Vec<String>>,
+  cells: Vec<String>,
+  cell: Vec<String>,
+}
+
+// The body of the main document, read into blocks
+#[derive(Default)]
+struct Body {
+  styles: HashMap<String, usize>,
+  numbering: Numbering,
+  blocks: Vec<Block>,
+  // Footnote and endnote references in the order they appear, numbered
+  // from 1 in the text
+  notes: Vec<(&'static str, String)>,
+}
+
+impl Body {
+  fn read(&mut self, xml: &[u8]) {
+    // Paragraphs nest when a text box sits inside a paragraph
+    let mut paragraphs: Vec<Paragraph> = Vec::new();
+    let mut tables: Vec<Table> = Vec::new();
+    let mut in_text = false;
+    // Depth inside elements whose text isn't shown: deleted text, field
+    // codes and the fallback copies of drawings
+    let mut skip = 0;
+
+    for event in xml_reader(xml) {
+      // Keep what was read before a malformed part of the document
+      let Ok(event) = event else { break };
+      match event {
+        XmlEvent::StartElement { name, attributes, .. } => {
+          if skip > 0 {
+            skip += 1;
+            continue;
+          }
+          let value = attribute(&attributes, "val");
+          let paragraph = paragraphs.last_mut();
+          match (name.local_name.as_str(), paragraph) {
+            (
+              "del" | "moveFrom" | "instrText" | "delInstrText" | "Fallback",
+              _,
+            ) => {
+              skip = 1;
+            }
+            ("p", _) => paragraphs.push(Paragraph::default()),
+            ("tbl", _) => tables.push(Table::default()),
+            // Header rows repeat at the top of each page the table is on
+            ("tblHeader", _) => {
+              if let Some(table) = tables.last_mut()
+                && table.rows.is_empty()
+              {
+                table.header =
+                  !matches!(value.as_deref(), Some("0" | "false" | "off"));
+              }
+            }
+            ("pStyle", Some(paragraph)) => paragraph.style = value,
+            ("outlineLvl", Some(paragraph)) => {
+              paragraph.outline_level = value.and_then(|v| v.parse().ok());
+            }
+            ("numId", Some(paragraph)) => paragraph.num_id = value,
+            ("ilvl", Some(paragraph)) => {
+              paragraph.ilvl = value.and_then(|v| v.parse().ok()).unwrap_or(0);
+            }
+            ("docPr", Some(paragraph)) => {
+              let description = attribute(&attributes, "descr")
+                .or_else(|| attribute(&attributes, "title"))
+                .unwrap_or_default();
+              paragraph.images.push(collapse_whitespace(&description));
+            }
+            ("t", _) => in_text = true,
+            // Tab stops in the paragraph properties have a `val`, tab
+            // characters in runs don't
+            ("tab", Some(paragraph)) if value.is_none() => {
+              paragraph.text.push('\t');
+            }
+            ("br" | "cr", Some(paragraph)) => paragraph.text.push('\n'),
+            ("noBreakHyphen", Some(paragraph)) => paragraph.text.push('-'),
+            (
+              kind @ ("footnoteReference" | "endnoteReference"),
+              Some(paragraph),
+            ) => {
+              if let Some(id) = attribute(&attributes, "id") {
+                let kind = if kind == "footnoteReference" {
+                  "footnote"
+                } else {
+                  "endnote"
+                };
+                self.notes.push((kind, id));
+                paragraph.text.push_str(&format!("[{}]", self.notes.len()));
+              }
+            }
+            _ => {}
+          }
+        }
+        XmlEvent::Characters(text)
+        | XmlEvent::Whitespace(text)
+        | XmlEvent::CData(text) => {
+          if in_text
+            && skip == 0
+            && let Some(paragraph) = paragraphs.last_mut()
+          {
+            paragraph.text.push_str(&text);
+          }
+        }
+        XmlEvent::EndElement { name } => {
+          if skip > 0 {
+            skip -= 1;
+            continue;
+          }
+          match name.local_name.as_str() {
+            "t" => in_text = false,
+            "p" => {
+              let Some(paragraph) = paragraphs.pop() else { continue };
+              if let Some(table) = tables.last_mut() {
+                let text = collapse_whitespace(&paragraph.text);
+                if !text.is_empty() {
+                  table.cell.push(text);
+                }
+              } else {
+                self.push_paragraph(paragraph);
+              }
+            }
+            "tc" => {
+              if let Some(table) = tables.last_mut() {
+                let cell = table.cell.join(" ");
+                table.cells.push(cell);
+                table.cell.clear();
+              }
+            }
+            "tr" => {
+              if let Some(table) = tables.last_mut() {
+                let cells = std::mem::take(&mut table.cells);
+                if cells.iter().any(|cell| !cell.is_empty()) {
+                  table.rows.push(cells);
+                } else if table.rows.is_empty() {
+                  table.header = false;
+                }
+              }
+            }
+            "tbl" => {
+              let Some(table) = tables.pop() else { continue };
+              match tables.last_mut() {
+                // A nested table becomes part of the cell it sits in
+                Some(outer) => outer
+                  .cell
+                  .extend(table.rows.iter().map(|row| row.join(" | "))),
+                None if !table.rows.is_empty() => {
+                  self.blocks.push(Block::Table {
+                    header: table.header,
+                    rows: table
+                      .rows
+                      .iter()
+                      .map(|row| {
+                        row.iter().map(|cell| vec![Span::plain(cell)]).collect()
+                      })
+                      .collect(),
+                  });
+                }
+                None => {}
+              }
+            }
+            _ => {}
+          }
+        }
+        _ => {}
+      }
+    }
+  }
+
+  // Add a paragraph as a heading, list item, quote, code or paragraph,
+  // followed by its pictures
+  fn push_paragraph(&mut self, paragraph: Paragraph) {
+    let images =
+      paragraph.images.iter().map(|alt| Block::Image { alt: alt.clone() });
+    let text =
+      paragraph.text.lines().map(str::trim_end).collect::<Vec<_>>().join("\n");
+    // Keep the indentation of code and preformatted lines
+    let text = text.trim_matches('\n');
+    if text.trim().is_empty() {
+      self.blocks.extend(images);
+      return;
+    }
+
+    let style = paragraph.style.as_deref().unwrap_or_default();
+    let heading_level =
+      paragraph.outline_level.filter(|&level| level < 9).or_else(|| {
+        paragraph
+          .style
+          .as_ref()
+          .and_then(|style| self.styles.get(style))
+          .copied()
+      });
+    let block = if let Some(level) = heading_level {
+      Block::Heading {
+        level,
+        spans: vec![Span::plain(&collapse_whitespace(text))],
+      }
+    } else if CODE_STYLES.contains(&style) {
+      Block::CodeBlock(text.to_string())
+    } else if QUOTE_STYLES.contains(&style) {
+      Block::Quote(vec![Span::plain(text)])
+    } else {
+      match paragraph.num_id.as_deref() {
+        // numId 0 removes the numbering a style would add
+        Some(num_id) if num_id != "0" => Block::ListItem {
+          depth: paragraph.ilvl as usize,
+          marker: self.numbering.next_marker(num_id, paragraph.ilvl),
+          spans: vec![Span::plain(text)],
+        },
+        _ => Block::Paragraph(vec![Span::plain(text)]),
+      }
+    };
+    self.blocks.push(block);
+    self.blocks.extend(images);
+  }
+}
+
+// Group blocks into sections starting at the top level headings
+fn split_sections(blocks: Vec<Block>) -> Vec<Section> {
+  let mut sections = Vec::new();
+  let mut current = Section::default();
+  for block in blocks {
+    if let Block::Heading { level, spans } = &block
+      && *level < SECTION_LEVELS
+    {
+      let title = spans.iter().map(|span| span.text.as_str()).collect();
+      let next =
+        Section { title: Some(title), level: *level, ..Default::default() };
+      let previous = std::mem::replace(&mut current, next);
+      if !previous.blocks.is_empty() {
+        sections.push(previous);
+      }
+    }
+    current.blocks.push(block);
+  }
+  if !current.blocks.is_empty() {
+    sections.push(current);
+  }
+
+  // Documents that start at "Heading 2" still have top level sections
+  let top = sections
+    .iter()
+    .filter(|section| section.title.is_some())
+    .map(|section| section.level)
+    .min()
+    .unwrap_or(0);
+  for section in &mut sections {
+    section.level = section.level.saturating_sub(top);
+    for block in &mut section.blocks {
+      if let Block::Heading { level, .. } = block {
+        *level = level.saturating_sub(top);
+      }
+    }
+    section.text = blocks_text(&section.blocks);
+  }
+  sections
+}
+
+// Plain text of blocks, separated by blank lines
+fn blocks_text(blocks: &[Block]) -> String {
+  blocks.iter().map(Block::text).collect::<Vec<_>>().join("\n\n")
+}
+
+fn xml_reader(bytes: &[u8]) -> EventReader<&[u8]> {
+  ParserConfig::new().create_reader(bytes)
+}
+
+fn attribute(attributes: &[OwnedAttribute], name: &str) -> Option<String> {
+  attributes
+    .iter()
+    .find(|attr| attr.name.local_name == name)
+    .map(|attr| attr.value.clone())
+}
+
+fn collapse_whitespace(text: &str) -> String {
+  text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_docx_error_display() {
+    let file_error = DocxError::FileNotFound("test.docx".to_string());
+    assert_eq!(format!("{file_error}"), "DOCX file not found: test.docx");
+
+    let invalid_error = DocxError::InvalidDocx("Bad format".to_string());
+    assert_eq!(format!("{invalid_error}"), "Invalid DOCX format: Bad format");
+  }
+
+  #[test]
+  fn test_heading_level_from_name() {
+    assert_eq!(heading_level_from_name("heading 1"), Some(0));
+    assert_eq!(heading_level_from_name("Heading3"), Some(2));
+    assert_eq!(heading_level_from_name("Title"), Some(0));
+    assert_eq!(heading_level_from_name("Body Text"), None);
+  }
+
+  #[test]
+  fn test_list_markers() {
+    assert_eq!(format_number(3, "lowerLetter"), "c");
+    assert_eq!(format_number(28, "upperLetter"), "BB");
+    assert_eq!(format_number(14, "lowerRoman"), "xiv");
+    assert_eq!(format_number(7, "decimal"), "7");
+  }
+
+  #[test]
+  fn test_file_not_found() {
+    let result = docx_to_text("definitely_nonexistent_file.docx");
+    match result.unwrap_err() {
+      DocxError::FileNotFound(path) => {
+        assert_eq!(path, "definitely_nonexistent_file.docx");
+      }
+      _ => panic!("Expected FileNotFound error"),
+    }
+  }
+}
@@ -0,0 +1,182 @@
+use cli_docx_to_text::{docx_metadata, docx_sections, docx_to_text};
+use document_converter::{Block, Span};
+use std::io::Write;
+use std::path::PathBuf;
+use zip::write::SimpleFileOptions;
+
+const FIXTURE: &str = "../test-data/docx/test-standard.docx";
+
+const W: &str =
+  r#"xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main""#;
+
+// Write a DOCX with the given body, styles and numbering to the temp dir
+fn write_docx(
+  name: &str,
+  body: &str,
+  styles: &str,
+  numbering: &str,
+) -> PathBuf {
+  let path = std::env::temp_dir()
+    .join(format!("cli-docx-to-text-{}-{name}.docx", std::process::id()));
+  let mut zip = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+  let options = SimpleFileOptions::default()
+    .compression_method(zip::CompressionMethod::Stored);
+
+  let parts = [
+    (
+      "_rels/.rels",
+      r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties" Target="docProps/core.xml"/></Relationships>"#.to_string(),
+    ),
+    (
+      "docProps/core.xml",
+      r#"<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Quarterly Report</dc:title><dc:creator>Jane Doe</dc:creator><dc:language>de-DE</dc:language></cp:coreProperties>"#.to_string(),
+    ),
+    (
+      "word/document.xml",
+      format!(r#"<w:document {W}><w:body>{body}</w:body></w:document>"#),
+    ),
+    ("word/styles.xml", format!(r#"<w:styles {W}>{styles}</w:styles>"#)),
+    (
+      "word/numbering.xml",
+      format!(r#"<w:numbering {W}>{numbering}</w:numbering>"#),
+    ),
+  ];
+  for (name, content) in parts {
+    zip.start_file(name, options).unwrap();
+    zip.write_all(content.as_bytes()).unwrap();
+  }
+  zip.finish().unwrap();
+  path
+}
+
+fn paragraph(style: &str, text: &str) -> String {
+  format!(
+    r#"<w:p><w:pPr><w:pStyle w:val="{style}"/></w:pPr><w:r><w:t>{text}</w:t></w:r></w:p>"#
+  )
+}
+
+#[test]
+fn test_fixture_sections() {
+  let sections = docx_sections(FIXTURE).unwrap();
+  let titles: Vec<&str> =
+    sections.iter().filter_map(|s| s.title.as_deref()).collect();
+  assert_eq!(titles[0], "Hygg Test DOCX Document");
+  assert!(titles.contains(&"Introduction"));
+  assert!(titles.contains(&"Tables"));
+  assert_eq!(titles.last(), Some(&"Notes"));
+  assert_eq!(sections[0].level, 0);
+  assert_eq!(sections[1].level, 1);
+
+  let text = docx_to_text(FIXTURE).unwrap();
+  let joined: Vec<String> = sections.into_iter().map(|s| s.text).collect();
+  assert_eq!(text, joined.join("\n\n"));
+}
+
+#[test]
+fn test_fixture_lists_tables_and_footnotes() {
+  let text = docx_to_text(FIXTURE).unwrap();
+  assert!(text.contains("\n\n• First level item\n\n"));
+  assert!(text.contains("\n\n  • Second level item\n\n"));
+  assert!(text.contains("\n\n2. Second item\n\n  1. Sub-item 2.1\n\n"));
+  assert!(text.contains("\n\n3. Third item\n\n  1. Sub-item 3.1\n\n"));
+  assert!(text.contains("\n\nFeature | Status | Notes\n\n"));
+  assert!(text.contains("This text has a footnote[1]."));
+  assert!(text.ends_with(
+    "[1] This is the first footnote.\n\n[2] This is the second footnote with more content."
+  ));
+  assert!(
+    text
+      .contains("def hello_world():\n    \"\"\"A simple Python function\"\"\"")
+  );
+}
+
+#[test]
+fn test_localized_styles_lists_and_revisions() {
+  let styles = r#"<w:style w:type="paragraph" w:styleId="berschrift1"><w:name w:val="heading 1"/></w:style><w:style w:type="paragraph" w:styleId="Kapitel"><w:name w:val="Kapitel"/><w:basedOn w:val="berschrift1"/></w:style>"#;
+  let numbering = r#"<w:abstractNum w:abstractNumId="7"><w:lvl w:ilvl="0"><w:start w:val="1"/><w:numFmt w:val="lowerLetter"/><w:lvlText w:val="%1)"/></w:lvl><w:lvl w:ilvl="1"><w:start w:val="1"/><w:numFmt w:val="lowerRoman"/><w:lvlText w:val="%1.%2"/></w:lvl></w:abstractNum><w:num w:numId="3"><w:abstractNumId w:val="7"/></w:num>"#;
+  let item = |ilvl: u32, text: &str| {
+    format!(
+      r#"<w:p><w:pPr><w:numPr><w:ilvl w:val="{ilvl}"/><w:numId w:val="3"/></w:numPr></w:pPr><w:r><w:t>{text}</w:t></w:r></w:p>"#
+    )
+  };
+  let body = [
+    paragraph("Normal", "Preface text."),
+    paragraph("berschrift1", "Einleitung"),
+    r#"<w:p><w:r><w:t xml:space="preserve">Kept </w:t></w:r><w:del><w:r><w:delText>removed </w:delText></w:r></w:del><w:ins><w:r><w:t>inserted</w:t></w:r></w:ins><w:r><w:instrText> PAGE </w:instrText></w:r></w:p>"#.to_string(),
+    item(0, "Apples"),
+    item(0, "Pears"),
+    item(1, "Conference"),
+    item(1, "Williams"),
+    item(0, "Plums"),
+    item(1, "Damson"),
+    paragraph("Kapitel", "Zahlen"),
+    r#"<w:tbl><w:tr><w:tc><w:p><w:r><w:t>Name</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>Value</w:t></w:r></w:p></w:tc></w:tr><w:tr><w:tc><w:p><w:r><w:t>Inner</w:t></w:r></w:p></w:tc><w:tc><w:tbl><w:tr><w:tc><w:p><w:r><w:t>a</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>b</w:t></w:r></w:p></w:tc></w:tr></w:tbl></w:tc></w:tr></w:tbl>"#.to_string(),
+  ]
+  .concat();
+  let path = write_docx("localized", &body, styles, numbering);
+  let file = path.to_str().unwrap();
+
+  let sections = docx_sections(file).unwrap();
+  let titles: Vec<Option<&str>> =
+    sections.iter().map(|s| s.title.as_deref()).collect();
+  assert_eq!(titles, [None, Some("Einleitung"), Some("Zahlen")]);
+  assert_eq!(sections[0].text, "Preface text.");
+  assert_eq!(
+    sections[1].text,
+    "Einleitung\n\nKept inserted\n\na) Apples\n\nb) Pears\n\n  b.i Conference\n\n  b.ii Williams\n\nc) Plums\n\n  c.i Damson"
+  );
+  assert_eq!(sections[2].text, "Zahlen\n\nName | Value\n\nInner | a | b");
+
+  let metadata = docx_metadata(file).unwrap();
+  assert_eq!(metadata.title.as_deref(), Some("Quarterly Report"));
+  assert_eq!(metadata.creator.as_deref(), Some("Jane Doe"));
+  assert_eq!(metadata.language.as_deref(), Some("de-DE"));
+
+  std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_blocks_keep_the_structure() {
+  let body = [
+    paragraph("Heading1", "Findings"),
+    paragraph("Quote", "Measure twice."),
+    r#"<w:p><w:pPr><w:pStyle w:val="SourceCode"/></w:pPr><w:r><w:t xml:space="preserve">if ok:</w:t></w:r><w:r><w:br/></w:r><w:r><w:t xml:space="preserve">    go()</w:t></w:r></w:p>"#.to_string(),
+    r#"<w:tbl><w:tr><w:trPr><w:tblHeader/></w:trPr><w:tc><w:p><w:r><w:t>Part</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>Count</w:t></w:r></w:p></w:tc></w:tr><w:tr><w:tc><w:p><w:r><w:t>Bolt</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>12</w:t></w:r></w:p></w:tc></w:tr></w:tbl>"#.to_string(),
+    r#"<w:p><w:r><w:drawing><wp:inline xmlns:wp="http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing"><wp:docPr id="1" name="Picture 1" descr="Floor plan"/></wp:inline></w:drawing></w:r></w:p>"#.to_string(),
+  ]
+  .concat();
+  let styles = r#"<w:style w:type="paragraph" w:styleId="Heading1"><w:name w:val="heading 1"/></w:style>"#;
+  let path = write_docx("blocks", &body, styles, "");
+  let sections = docx_sections(path.to_str().unwrap()).unwrap();
+  let cell = |text: &str| vec![Span::plain(text)];
+
+  assert_eq!(
+    sections[0].blocks,
+    [
+      Block::Heading { level: 0, spans: cell("Findings") },
+      Block::Quote(cell("Measure twice.")),
+      Block::CodeBlock("if ok:\n    go()".to_string()),
+      Block::Table {
+        header: true,
+        rows: vec![
+          vec![cell("Part"), cell("Count")],
+          vec![cell("Bolt"), cell("12")],
+        ],
+      },
+      Block::Image { alt: "Floor plan".to_string() },
+    ]
+  );
+  assert_eq!(
+    sections[0].text,
+    "Findings\n\nMeasure twice.\n\nif ok:\n    go()\n\nPart | Count\n\n\
+     Bolt | 12\n\n[Floor plan]"
+  );
+
+  std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_not_a_docx() {
+  let result = docx_to_text("../test-data/sample.txt");
+  assert!(matches!(result, Err(cli_docx_to_text::DocxError::InvalidDocx(_))));
+}
@@ -6,7 +6,11 @@
 //! ## Features
 //! - Extract text content from EPUB files
 //! - Preserve reading order as defined in the EPUB spine
+//! - Split the text into chapters titled from the nav document or NCX
+//! - Read the book metadata: title, creators, language, ISBN and cover
 //! - Convert HTML content to plain text
+//! - Keep the structure as blocks: headings, lists, tables, quotes, code
+//!   and pictures
 //! - Comprehensive error handling with custom error types
 //! - Unicode support for international content
 //!
@@ -20,10 +24,18 @@
 //! }
 //! ```
 
-use epub::doc::EpubDoc;
+use document_converter::Block;
+use epub::doc::{EpubDoc, NavPoint};
+use serde::Serialize;
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
-use std::path::Path;
+use std::io::{Read, Seek};
+use std::path::{Component, Path, PathBuf};
+use xml::reader::{EventReader, ParserConfig, XmlEvent};
+
+/// Version of this crate
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// Custom error type for EPUB processing errors
 #[derive(Debug)]
@@ -53,6 +65,112 @@ impl fmt::Display for EpubError {
 
 impl Error for EpubError {}
 
+/// One spine item of an EPUB
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chapter {
+  /// Title from the table of contents, or the first line of the text for
+  /// spine items the table of contents doesn't list
+  pub title: String,
+  /// The `idref` of the spine item
+  pub spine_id: String,
+  /// Plain text with paragraphs, list items and table rows separated by
+  /// blank lines
+  pub text: String,
+  /// The headings, paragraphs, list items, tables, quotes, code and
+  /// pictures of the spine item
+  pub blocks: Vec<Block>,
+}
+
+/// Bibliographic data from the EPUB package document
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct EpubMetadata {
+  pub title: Option<String>,
+  /// Authors and other contributors listed as `dc:creator`
+  pub creators: Vec<String>,
+  pub language: Option<String>,
+  pub publisher: Option<String>,
+  pub date: Option<String>,
+  pub description: Option<String>,
+  pub subjects: Vec<String>,
+  /// All `dc:identifier` values, e.g. a UUID and `urn:isbn:...`
+  pub identifiers: Vec<String>,
+  pub cover: Option<Cover>,
+}
+
+/// The cover image of an EPUB
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Cover {
+  pub media_type: String,
+  #[serde(skip)]
+  pub data: Vec<u8>,
+}
+
+impl EpubMetadata {
+  /// The first identifier that is an ISBN, without any `urn:isbn:` prefix
+  pub fn isbn(&self) -> Option<String> {
+    self.identifiers.iter().find_map(|identifier| {
+      let lower = identifier.to_lowercase();
+      let isbn = ["urn:isbn:", "isbn:", "isbn"]
+        .iter()
+        .find_map(|prefix| lower.strip_prefix(prefix))
+        .unwrap_or(&lower)
+        .trim();
+      let digits = isbn.chars().filter(|c| !matches!(c, '-' | ' ')).count();
+      let valid = isbn
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, '-' | ' ' | 'x'));
+      (valid && (digits == 10 || digits == 13)).then(|| isbn.to_uppercase())
+    })
+  }
+}
+
+/// Read the metadata of an EPUB file without converting its content
+///
+/// # Examples
+/// ```rust
+/// use cli_epub_to_text::epub_metadata;
+///
+/// if let Ok(metadata) = epub_metadata("path/to/book.epub") {
+///     println!("{}", metadata.title.unwrap_or_default());
+/// }
+/// ```
+pub fn epub_metadata(file_path: &str) -> Result<EpubMetadata, EpubError> {
+  if !Path::new(file_path).exists() {
+    return Err(EpubError::FileNotFound(file_path.to_string()));
+  }
+  let mut epub = EpubDoc::new(file_path)
+    .map_err(|e| EpubError::InvalidEpub(format!("Failed to open EPUB: {e}")))?;
+
+  let values = |name: &str| -> Vec<String> {
+    epub
+      .metadata
+      .get(name)
+      .into_iter()
+      .flatten()
+      .map(|value| value.split_whitespace().collect::<Vec<_>>().join(" "))
+      .filter(|value| !value.is_empty())
+      .collect()
+  };
+  let first = |name: &str| values(name).into_iter().next();
+
+  let mut metadata = EpubMetadata {
+    title: first("title"),
+    creators: values("creator"),
+    language: first("language"),
+    publisher: first("publisher"),
+    date: first("date"),
+    description: first("description"),
+    subjects: values("subject"),
+    identifiers: values("identifier"),
+    cover: None,
+  };
+
+  metadata.cover =
+    epub.get_cover().map(|(data, media_type)| Cover { media_type, data });
+
+  Ok(metadata)
+}
+
 /// Convert an EPUB file to plain text
 ///
 /// This function extracts text content from all chapters in an EPUB file,
@@ -70,7 +188,6 @@ impl Error for EpubError {}
 /// * `EpubError::FileNotFound` - The specified file doesn't exist
 /// * `EpubError::InvalidEpub` - The file is not a valid EPUB or cannot be
 ///   parsed
-/// * `EpubError::HtmlConversion` - Failed to convert HTML content to plain text
 ///
 /// # Examples
 /// ```rust
@@ -83,6 +200,35 @@ impl Error for EpubError {}
 /// }
 /// ```
 pub fn epub_to_text(file_path: &str) -> Result<String, EpubError> {
+  let chapters = epub_chapters(file_path)?;
+  Ok(
+    chapters
+      .into_iter()
+      .map(|chapter| chapter.text)
+      .collect::<Vec<_>>()
+      .join("\n\n"),
+  )
+}
+
+/// Convert an EPUB file to plain text chapters
+///
+/// Returns one chapter per spine item with readable content, in reading
+/// order. Joining the chapter texts with blank lines gives the same text as
+/// `epub_to_text`.
+///
+/// Titles come from the EPUB 3 nav document, falling back to the EPUB 2 NCX.
+///
+/// # Examples
+/// ```rust
+/// use cli_epub_to_text::epub_chapters;
+///
+/// if let Ok(chapters) = epub_chapters("path/to/book.epub") {
+///     for chapter in chapters {
+///         println!("{}", chapter.title);
+///     }
+/// }
+/// ```
+pub fn epub_chapters(file_path: &str) -> Result<Vec<Chapter>, EpubError> {
   // Check if file exists before attempting to parse
   if !Path::new(file_path).exists() {
     return Err(EpubError::FileNotFound(file_path.to_string()));
@@ -90,23 +236,35 @@ pub fn epub_to_text(file_path: &str) -> Result<String, EpubError> {
   let mut epub = EpubDoc::new(file_path)
     .map_err(|e| EpubError::InvalidEpub(format!("Failed to open EPUB: {e}")))?;
 
-  let mut text_parts = Vec::new();
+  let titles = toc_titles(&mut epub);
+  let mut chapters = Vec::new();
 
   for spine_item in epub.spine.clone() {
     match epub.get_resource(&spine_item.idref) {
       Some((xhtml_bytes, _media_type)) => {
+        let xhtml = String::from_utf8_lossy(&xhtml_bytes);
+        let blocks = cli_html_to_text::html_blocks(&xhtml);
         let text =
-          html2text::from_read(xhtml_bytes.as_slice(), 110).map_err(|e| {
-            EpubError::HtmlConversion(format!(
-              "Failed to convert HTML to text for resource '{}': {}",
-              spine_item.idref, e
-            ))
-          })?;
+          blocks.iter().map(Block::text).collect::<Vec<_>>().join("\n\n");
 
         let trimmed_text = text.trim();
-        if !trimmed_text.is_empty() {
-          text_parts.push(trimmed_text.to_string());
+        if trimmed_text.is_empty() {
+          continue;
         }
+
+        let title = epub
+          .resources
+          .get(&spine_item.idref)
+          .and_then(|(path, _)| titles.get(&normalize_path(path)))
+          .cloned()
+          .unwrap_or_else(|| title_from_text(trimmed_text, &spine_item.idref));
+
+        chapters.push(Chapter {
+          title,
+          spine_id: spine_item.idref,
+          text: trimmed_text.to_string(),
+          blocks,
+        });
       }
       None => {
         // Resource not found, skip silently as some EPUBs may reference
@@ -116,10 +274,185 @@ pub fn epub_to_text(file_path: &str) -> Result<String, EpubError> {
     }
   }
 
-  if text_parts.is_empty() {
+  if chapters.is_empty() {
     Err(EpubError::InvalidEpub("No readable content found in EPUB".to_string()))
   } else {
-    Ok(text_parts.join("\n\n"))
+    Ok(chapters)
+  }
+}
+
+// Map archive paths of content documents to their first table of contents
+// title
+fn toc_titles<R: Read + Seek>(
+  epub: &mut EpubDoc<R>,
+) -> HashMap<PathBuf, String> {
+  let mut entries = nav_entries(epub);
+  if entries.is_empty() {
+    flatten_navpoints(&epub.toc, &mut entries);
+  }
+
+  let mut titles = HashMap::new();
+  for (path, title) in entries {
+    titles.entry(path).or_insert(title);
+  }
+  titles
+}
+
+fn flatten_navpoints(
+  navpoints: &[NavPoint],
+  entries: &mut Vec<(PathBuf, String)>,
+) {
+  for navpoint in navpoints {
+    let content = navpoint.content.to_string_lossy();
+    let path = content.split('#').next().unwrap_or_default();
+    entries.push((normalize_path(Path::new(path)), navpoint.label.clone()));
+    flatten_navpoints(&navpoint.children, entries);
+  }
+}
+
+// Read the `<nav epub:type="toc">` links of the EPUB 3 navigation document
+fn nav_entries<R: Read + Seek>(
+  epub: &mut EpubDoc<R>,
+) -> Vec<(PathBuf, String)> {
+  let root_file = epub.root_file.clone();
+  let Some(opf) = epub.get_resource_by_path(&root_file) else {
+    return Vec::new();
+  };
+  let Some(nav_href) = nav_href(&opf) else {
+    return Vec::new();
+  };
+
+  let nav_path = epub.root_base.join(percent_decode(&nav_href));
+  let Some(nav) = epub.get_resource_by_path(&nav_path) else {
+    return Vec::new();
+  };
+  let nav_dir = nav_path.parent().map(Path::to_path_buf).unwrap_or_default();
+
+  let mut entries = Vec::new();
+  let mut toc_depth = None;
+  let mut depth = 0;
+  let mut link: Option<(String, String)> = None;
+
+  for event in xml_reader(&nav) {
+    // Keep what was read before a malformed part of the document
+    let Ok(event) = event else { break };
+    match event {
+      XmlEvent::StartElement { name, attributes, .. } => {
+        depth += 1;
+        if name.local_name == "nav"
+          && toc_depth.is_none()
+          && attributes.iter().any(|attr| {
+            attr.name.local_name == "type"
+              && attr.value.split_whitespace().any(|t| t == "toc")
+          })
+        {
+          toc_depth = Some(depth);
+        } else if name.local_name == "a" && toc_depth.is_some() {
+          let href = attributes
+            .iter()
+            .find(|attr| attr.name.local_name == "href")
+            .map(|attr| attr.value.clone());
+          link = href.map(|href| (href, String::new()));
+        }
+      }
+      XmlEvent::Characters(text) | XmlEvent::CData(text) => {
+        if let Some((_, label)) = link.as_mut() {
+          label.push_str(&text);
+        }
+      }
+      XmlEvent::Whitespace(text) => {
+        if let Some((_, label)) = link.as_mut() {
+          label.push_str(&text);
+        }
+      }
+      XmlEvent::EndElement { name } => {
+        if name.local_name == "a"
+          && let Some((href, label)) = link.take()
+        {
+          let label = label.split_whitespace().collect::<Vec<_>>().join(" ");
+          let path = href.split('#').next().unwrap_or_default();
+          if !label.is_empty() && !path.is_empty() {
+            let path = nav_dir.join(percent_decode(path));
+            entries.push((normalize_path(&path), label));
+          }
+        }
+        if toc_depth == Some(depth) {
+          break;
+        }
+        depth -= 1;
+      }
+      _ => {}
+    }
+  }
+
+  entries
+}
+
+// Find the manifest item with the `nav` property in the package document
+fn nav_href(opf: &[u8]) -> Option<String> {
+  for event in xml_reader(opf) {
+    if let XmlEvent::StartElement { name, attributes, .. } = event.ok()?
+      && name.local_name == "item"
+      && attributes.iter().any(|attr| {
+        attr.name.local_name == "properties"
+          && attr.value.split_whitespace().any(|p| p == "nav")
+      })
+    {
+      return attributes
+        .into_iter()
+        .find(|attr| attr.name.local_name == "href")
+        .map(|attr| attr.value);
+    }
+  }
+  None
+}
+
+fn xml_reader(bytes: &[u8]) -> EventReader<&[u8]> {
+  ParserConfig::new().add_entity("nbsp", "\u{a0}").create_reader(bytes)
+}
+
+// Resolve `.` and `..` so paths from different documents compare equal
+fn normalize_path(path: &Path) -> PathBuf {
+  let mut normalized = PathBuf::new();
+  for component in path.components() {
+    match component {
+      Component::CurDir => {}
+      Component::ParentDir => {
+        normalized.pop();
+      }
+      component => normalized.push(component),
+    }
+  }
+  normalized
+}
+
+fn percent_decode(s: &str) -> String {
+  let bytes = s.as_bytes();
+  let mut decoded = Vec::with_capacity(bytes.len());
+  let mut i = 0;
+  while i < bytes.len() {
+    if bytes[i] == b'%'
+      && let Some(byte) =
+        s.get(i + 1..i + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok())
+    {
+      decoded.push(byte);
+      i += 3;
+    } else {
+      decoded.push(bytes[i]);
+      i += 1;
+    }
+  }
+  String::from_utf8_lossy(&decoded).into_owned()
+}
+
+// Use the first line of a chapter, usually its heading, as its title
+fn title_from_text(text: &str, fallback: &str) -> String {
+  let line = text.lines().next().unwrap_or_default();
+  let title = line.trim();
+  if title.is_empty() {
+    fallback.to_string()
+  } else {
+    title.chars().take(80).collect()
   }
 }
 
@@ -152,6 +485,33 @@ mod tests {
     assert!(error.source().is_none());
   }
 
+  #[test]
+  fn test_isbn_from_identifiers() {
+    let metadata = EpubMetadata {
+      identifiers: vec![
+        "hygg-test-epub-001".to_string(),
+        "urn:isbn:978-0-306-40615-7".to_string(),
+      ],
+      ..Default::default()
+    };
+    assert_eq!(metadata.isbn().as_deref(), Some("978-0-306-40615-7"));
+
+    let metadata = EpubMetadata {
+      identifiers: vec!["urn:uuid:1234".to_string()],
+      ..Default::default()
+    };
+    assert_eq!(metadata.isbn(), None);
+  }
+
+  #[test]
+  fn test_normalize_path() {
+    assert_eq!(
+      normalize_path(Path::new("OEBPS/text/../chapter1.xhtml")),
+      PathBuf::from("OEBPS/chapter1.xhtml")
+    );
+    assert_eq!(percent_decode("my%20chapter.xhtml"), "my chapter.xhtml");
+  }
+
   #[test]
   fn test_file_not_found() {
     let result = epub_to_text("definitely_nonexistent_file.epub");
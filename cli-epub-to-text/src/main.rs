@@ -1,13 +1,65 @@
+use std::env;
+
+use cli_epub_to_text::EpubMetadata;
+use getopts::Options;
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-  let file_path = std::env::args().nth(1).ok_or_else(|| {
-    eprintln!(
-      "Usage: {} <epub_file>",
-      std::env::args().next().unwrap_or_else(|| "cli-epub-to-text".to_string())
-    );
+  let args: Vec<String> = env::args().collect();
+  let mut opts = Options::new();
+
+  opts.optflag("m", "metadata", "print the book's metadata");
+  opts.optflag("j", "json", "print the book's metadata as JSON");
+  opts.optflag("h", "help", "print this help menu");
+
+  let matches = opts.parse(&args[1..])?;
+  let brief = format!("Usage: {} FILE [options]", args[0]);
+
+  if matches.opt_present("h") {
+    print!("{}", opts.usage(&brief));
+    return Ok(());
+  }
+
+  let Some(file_path) = matches.free.first() else {
+    eprint!("{}", opts.usage(&brief));
     std::process::exit(1);
-  })?;
+  };
+  let metadata = matches.opt_present("m");
+  let json = matches.opt_present("j");
 
-  println!("{}", cli_epub_to_text::epub_to_text(&file_path)?);
+  if json {
+    let metadata = cli_epub_to_text::epub_metadata(file_path)?;
+    println!("{}", serde_json::to_string_pretty(&metadata)?);
+  } else if metadata {
+    print_metadata(&cli_epub_to_text::epub_metadata(file_path)?);
+  } else {
+    println!("{}", cli_epub_to_text::epub_to_text(file_path)?);
+  }
 
   Ok(())
 }
+
+fn print_metadata(metadata: &EpubMetadata) {
+  let fields = [
+    ("Title", metadata.title.clone()),
+    ("Creators", Some(metadata.creators.join(", "))),
+    ("Language", metadata.language.clone()),
+    ("Publisher", metadata.publisher.clone()),
+    ("Date", metadata.date.clone()),
+    ("ISBN", metadata.isbn()),
+    ("Identifiers", Some(metadata.identifiers.join(", "))),
+    ("Subjects", Some(metadata.subjects.join(", "))),
+    ("Description", metadata.description.clone()),
+    (
+      "Cover",
+      metadata.cover.as_ref().map(|cover| {
+        format!("{} ({} bytes)", cover.media_type, cover.data.len())
+      }),
+    ),
+  ];
+
+  for (name, value) in fields {
+    if let Some(value) = value.filter(|value| !value.is_empty()) {
+      println!("{name}: {value}");
+    }
+  }
+}
@@ -2,7 +2,7 @@ use cli_epub_to_text::{EpubError, epub_to_text};
 use std::io::Write;
 use tempfile::TempDir;
 
-/// Test EPUB with malformed XHTML that html2text should handle gracefully
+/// Test EPUB with malformed XHTML that should be handled gracefully
 #[test]
 fn test_malformed_xhtml() {
   let temp_dir = TempDir::new().unwrap();
@@ -65,7 +65,7 @@ fn test_malformed_xhtml() {
   zip.finish().unwrap();
 
   let result = epub_to_text(&epub_path.to_string_lossy());
-  // html2text should handle malformed HTML gracefully
+  // Malformed HTML should be handled gracefully
   assert!(
     result.is_ok(),
     "Should handle malformed XHTML gracefully: {:?}",
@@ -1,4 +1,4 @@
-use cli_epub_to_text::{EpubError, epub_to_text};
+use cli_epub_to_text::{EpubError, epub_chapters, epub_to_text};
 use std::fs;
 use std::io::Write;
 use std::path::Path;
@@ -162,6 +162,92 @@ fn test_epub_to_text_with_multiple_chapters() {
   );
 }
 
+#[test]
+fn test_epub_chapters_from_ncx() {
+  let temp_dir = TempDir::new().unwrap();
+  let epub_path = temp_dir.path().join("ncx.epub");
+
+  let zip_file = std::fs::File::create(&epub_path).unwrap();
+  let mut zip = zip::ZipWriter::new(zip_file);
+
+  let options = zip::write::SimpleFileOptions::default()
+    .compression_method(zip::CompressionMethod::Stored);
+  zip.start_file("mimetype", options).unwrap();
+  zip.write_all(b"application/epub+zip").unwrap();
+
+  let options = zip::write::SimpleFileOptions::default();
+  zip.start_file("META-INF/container.xml", options).unwrap();
+  zip.write_all(br#"<?xml version="1.0"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>"#).unwrap();
+
+  zip.start_file("OEBPS/content.opf", options).unwrap();
+  zip.write_all(br#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" unique-identifier="BookId" version="2.0">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>NCX Book</dc:title>
+    <dc:identifier id="BookId">ncx-book</dc:identifier>
+  </metadata>
+  <manifest>
+    <item id="ncx" href="toc.ncx" media-type="application/x-dtbncx+xml"/>
+    <item id="intro" href="text/intro.xhtml" media-type="application/xhtml+xml"/>
+    <item id="body" href="text/body.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine toc="ncx">
+    <itemref idref="intro"/>
+    <itemref idref="body"/>
+  </spine>
+</package>"#).unwrap();
+
+  zip.start_file("OEBPS/toc.ncx", options).unwrap();
+  zip
+    .write_all(
+      br#"<?xml version="1.0" encoding="UTF-8"?>
+<ncx xmlns="http://www.daisy.org/z3986/2005/ncx/" version="2005-1">
+  <docTitle><text>NCX Book</text></docTitle>
+  <navMap>
+    <navPoint id="p1" playOrder="1">
+      <navLabel><text>Opening Words</text></navLabel>
+      <content src="text/intro.xhtml"/>
+    </navPoint>
+    <navPoint id="p2" playOrder="2">
+      <navLabel><text>The Main Part</text></navLabel>
+      <content src="text/body.xhtml#start"/>
+    </navPoint>
+  </navMap>
+</ncx>"#,
+    )
+    .unwrap();
+
+  for (name, heading) in [("intro", "Intro"), ("body", "Body")] {
+    zip.start_file(format!("OEBPS/text/{name}.xhtml"), options).unwrap();
+    zip
+      .write_all(
+        format!(
+          r#"<?xml version="1.0" encoding="UTF-8"?>
+<html xmlns="http://www.w3.org/1999/xhtml">
+<head><title>{heading}</title></head>
+<body><h1 id="start">{heading}</h1><p>Text of {name}.</p></body>
+</html>"#
+        )
+        .as_bytes(),
+      )
+      .unwrap();
+  }
+
+  zip.finish().unwrap();
+
+  let chapters = epub_chapters(&epub_path.to_string_lossy()).unwrap();
+  assert_eq!(chapters.len(), 2);
+  assert_eq!(chapters[0].title, "Opening Words");
+  assert_eq!(chapters[0].spine_id, "intro");
+  assert_eq!(chapters[1].title, "The Main Part");
+  assert!(chapters[1].text.contains("Text of body."));
+}
+
 #[test]
 fn test_epub_to_text_file_not_found() {
   let result = epub_to_text("nonexistent_file.epub");
@@ -282,7 +368,7 @@ fn test_epub_to_text_html_formatting() {
   );
 
   let text = result.unwrap();
-  // html2text should convert HTML to plain text
+  // The HTML should be converted to plain text
   assert!(text.contains("bold"), "Should contain text from strong tags");
   assert!(text.contains("italic"), "Should contain text from em tags");
   assert!(text.contains("Item 1"), "Should contain list items");
@@ -1,4 +1,5 @@
-use cli_epub_to_text::{EpubError, epub_to_text};
+use cli_epub_to_text::{EpubError, epub_chapters, epub_metadata, epub_to_text};
+use document_converter::Block;
 use std::path::Path;
 
 #[test]
@@ -63,6 +64,50 @@ fn test_standard_epub_file() {
   assert!(content.contains("Supported"), "Should extract table cells");
 }
 
+#[test]
+fn test_standard_epub_chapters() {
+  let test_file = Path::new(env!("CARGO_MANIFEST_DIR"))
+    .parent()
+    .unwrap()
+    .join("test-data/epub/test-standard.epub");
+
+  if !test_file.exists() {
+    eprintln!("Skipping test: test-standard.epub not found");
+    return;
+  }
+  let test_file = test_file.to_str().unwrap();
+
+  let chapters = epub_chapters(test_file).unwrap();
+  let titles: Vec<_> = chapters.iter().map(|c| c.title.as_str()).collect();
+
+  // The cover and chapters are titled from nav.xhtml, the nav document
+  // itself isn't listed there and falls back to its heading
+  assert_eq!(
+    titles,
+    vec![
+      "Cover",
+      "Table of Contents",
+      "Chapter 1: Introduction",
+      "Chapter 2: Formatting Tests",
+      "Chapter 3: Unicode and Special Characters",
+    ]
+  );
+  assert_eq!(chapters[2].spine_id, "chapter1");
+
+  // The chapters keep their lists, quotes and tables as blocks
+  let blocks: Vec<&Block> =
+    chapters[2..].iter().flat_map(|chapter| &chapter.blocks).collect();
+  assert!(blocks.iter().any(|block| {
+    matches!(block, Block::ListItem { marker, .. } if marker == "1.")
+  }));
+  assert!(blocks.iter().any(|block| matches!(block, Block::Quote(_))));
+  assert!(blocks.iter().any(|block| matches!(block, Block::Table { .. })));
+
+  let joined =
+    chapters.iter().map(|c| c.text.as_str()).collect::<Vec<_>>().join("\n\n");
+  assert_eq!(joined, epub_to_text(test_file).unwrap());
+}
+
 #[test]
 fn test_empty_epub() {
   // This should fail as the file doesn't exist
@@ -114,3 +159,27 @@ fn test_all_epub_files_in_test_data() {
   assert!(tested_files > 0, "No EPUB files found to test");
   println!("Successfully tested {} EPUB files", tested_files);
 }
+
+#[test]
+fn test_standard_epub_metadata() {
+  let test_file = Path::new(env!("CARGO_MANIFEST_DIR"))
+    .parent()
+    .unwrap()
+    .join("test-data/epub/test-standard.epub");
+
+  if !test_file.exists() {
+    eprintln!("Skipping test: test-standard.epub not found");
+    return;
+  }
+
+  let metadata = epub_metadata(test_file.to_str().unwrap()).unwrap();
+  assert_eq!(metadata.title.as_deref(), Some("Hygg Test EPUB"));
+  assert_eq!(metadata.creators, vec!["Hygg Test Suite"]);
+  assert_eq!(metadata.language.as_deref(), Some("en"));
+  assert_eq!(metadata.identifiers, vec!["hygg-test-epub-001"]);
+  assert_eq!(metadata.isbn(), None);
+  assert!(matches!(
+    epub_metadata("missing.epub"),
+    Err(EpubError::FileNotFound(_))
+  ));
+}
@@ -0,0 +1,847 @@
+//! # CLI FB2 to Text Converter
+//!
+//! A Rust library for converting FictionBook 2 e-books, `.fb2` files and
+//! zipped `.fb2.zip` files, to plain text.
+//!
+//! ## Features
+//! - Split the text into chapters at the sections of the book
+//! - Keep poems line by line and flatten tables to one line per row
+//! - Keep the structure as blocks: titles, epigraphs and quotes, tables
+//!   and pictures
+//! - Collect the footnotes of the notes body into a final chapter
+//! - Read the book description: title, authors, genres, series and cover
+//! - Read books in legacy encodings such as windows-1251
+//!
+//! ## Usage
+//! ```rust
+//! use cli_fb2_to_text::fb2_to_text;
+//!
+//! match fb2_to_text("path/to/book.fb2") {
+//!     Ok(text) => println!("Extracted text: {}", text),
+//!     Err(e) => eprintln!("Error: {}", e),
+//! }
+//! ```
+
+use document_converter::{Block, Span};
+use encoding_rs::{Encoding, UTF_8};
+use serde::Serialize;
+use std::error::Error;
+use std::fmt;
+use std::io::{Cursor, Read};
+use std::path::Path;
+use xml::attribute::OwnedAttribute;
+use xml::reader::{EventReader, ParserConfig, XmlEvent};
+use zip::ZipArchive;
+
+/// Version of this crate
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Sections nested this deep or less start a chapter, deeper ones stay in
+/// the text of their chapter
+const CHAPTER_LEVELS: usize = 2;
+
+/// An XML declaration past this many bytes is not looked for
+const DECLARATION_LEN: usize = 256;
+
+/// Custom error type for FB2 processing errors
+#[derive(Debug)]
+pub enum Fb2Error {
+  FileNotFound(String),
+  InvalidFb2(String),
+}
+
+impl fmt::Display for Fb2Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Fb2Error::FileNotFound(path) => write!(f, "FB2 file not found: {path}"),
+      Fb2Error::InvalidFb2(msg) => write!(f, "Invalid FB2 format: {msg}"),
+    }
+  }
+}
+
+impl Error for Fb2Error {}
+
+/// A section of an FB2 book
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Chapter {
+  /// Title of the section, or the first line of the text for sections
+  /// without one
+  pub title: String,
+  /// Nesting depth, 0 for the top level sections of the body
+  pub level: usize,
+  /// Plain text starting with the title, with paragraphs and stanzas
+  /// separated by blank lines
+  pub text: String,
+  /// The title and the paragraphs, stanzas, quotes, tables and pictures
+  /// after it
+  pub blocks: Vec<Block>,
+}
+
+/// Bibliographic data from the `<description>` of an FB2 book
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Fb2Metadata {
+  pub title: Option<String>,
+  pub authors: Vec<String>,
+  pub language: Option<String>,
+  pub publisher: Option<String>,
+  pub date: Option<String>,
+  /// The annotation of the book
+  pub description: Option<String>,
+  /// Genre codes such as `sf_fantasy`
+  pub genres: Vec<String>,
+  /// Name of the series the book is part of
+  pub series: Option<String>,
+  /// Number of the book in its series
+  pub series_number: Option<String>,
+  pub isbn: Option<String>,
+  /// The `<id>` of the document, often a UUID
+  pub id: Option<String>,
+  pub cover: Option<Cover>,
+}
+
+/// The cover image of an FB2 book
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Cover {
+  pub media_type: String,
+  #[serde(skip)]
+  pub data: Vec<u8>,
+}
+
+/// Read the description of an FB2 book without converting its content
+///
+/// # Examples
+/// ```rust
+/// use cli_fb2_to_text::fb2_metadata;
+///
+/// if let Ok(metadata) = fb2_metadata("path/to/book.fb2") {
+///     println!("{}", metadata.title.unwrap_or_default());
+/// }
+/// ```
+pub fn fb2_metadata(file_path: &str) -> Result<Fb2Metadata, Fb2Error> {
+  let xml = read_book(file_path)?;
+  let mut metadata = Fb2Metadata::default();
+  let mut path: Vec<String> = Vec::new();
+  // Text of each open element, handed to its parent when it closes
+  let mut texts: Vec<String> = Vec::new();
+  let mut author: Vec<String> = Vec::new();
+  let mut nickname = None;
+  let mut cover_id = None;
+  let mut cover_type = None;
+  let mut cover = false;
+  let mut date_value = None;
+  let mut year = None;
+
+  for event in book_events(&xml)? {
+    // Keep what was read before a malformed part of the book
+    let Ok(event) = event else { break };
+    match event {
+      XmlEvent::StartElement { name, attributes, .. } => {
+        path.push(name.local_name);
+        texts.push(String::new());
+        if ends_with(&path, &["title-info", "sequence"]) {
+          metadata.series = attribute(&attributes, "name");
+          metadata.series_number = attribute(&attributes, "number");
+        } else if ends_with(&path, &["title-info", "coverpage", "image"]) {
+          cover_id = attribute(&attributes, "href")
+            .map(|href| href.trim_start_matches('#').to_string());
+        } else if ends_with(&path, &["title-info", "date"]) {
+          date_value = attribute(&attributes, "value");
+        } else if ends_with(&path, &["FictionBook", "binary"]) {
+          // Binaries other than the cover aren't kept
+          cover =
+            cover_id.is_some() && attribute(&attributes, "id") == cover_id;
+          cover_type = attribute(&attributes, "content-type");
+        }
+      }
+      // Only the text of the description and the cover is needed
+      XmlEvent::Characters(text) | XmlEvent::CData(text)
+        if cover || path.iter().any(|name| name == "description") =>
+      {
+        if let Some(current) = texts.last_mut() {
+          current.push_str(&text);
+        }
+      }
+      XmlEvent::EndElement { .. } => {
+        let text = texts.pop().unwrap_or_default();
+        let value = Some(collapse_whitespace(&text)).filter(|v| !v.is_empty());
+        if let Some(parent) = texts.last_mut()
+          && !text.is_empty()
+        {
+          parent.push(' ');
+          parent.push_str(&text);
+        }
+
+        let is = |names: &[&str]| ends_with(&path, names);
+        if is(&["title-info", "book-title"]) {
+          metadata.title = value;
+        } else if is(&["title-info", "author", "nickname"]) {
+          nickname = value;
+        } else if is(&["title-info", "author", "first-name"])
+          || is(&["title-info", "author", "middle-name"])
+          || is(&["title-info", "author", "last-name"])
+        {
+          author.extend(value);
+        } else if is(&["title-info", "author"]) {
+          let name = if author.is_empty() {
+            nickname.take()
+          } else {
+            Some(author.join(" "))
+          };
+          metadata.authors.extend(name);
+          author.clear();
+          nickname = None;
+        } else if is(&["title-info", "genre"]) {
+          metadata.genres.extend(value);
+        } else if is(&["title-info", "lang"]) {
+          metadata.language = value;
+        } else if is(&["title-info", "date"]) {
+          metadata.date = value.or_else(|| date_value.take());
+        } else if is(&["title-info", "annotation"]) {
+          metadata.description = value;
+        } else if is(&["publish-info", "publisher"]) {
+          metadata.publisher = value;
+        } else if is(&["publish-info", "isbn"]) {
+          metadata.isbn = value;
+        } else if is(&["publish-info", "year"]) {
+          year = value;
+        } else if is(&["document-info", "id"]) {
+          metadata.id = value;
+        } else if is(&["FictionBook", "description"]) && cover_id.is_none() {
+          break;
+        } else if is(&["FictionBook", "binary"]) && cover {
+          metadata.cover = Some(Cover {
+            media_type: cover_type.take().unwrap_or_default(),
+            data: decode_base64(text.as_bytes()),
+          });
+          break;
+        }
+        path.pop();
+      }
+      _ => {}
+    }
+  }
+  metadata.date = metadata.date.or(year);
+  Ok(metadata)
+}
+
+/// Convert an FB2 book to plain text
+///
+/// # Error Cases
+/// * `Fb2Error::FileNotFound` - The specified file doesn't exist
+/// * `Fb2Error::InvalidFb2` - The file is not a FictionBook, a zip without
+///   one, or has no text
+///
+/// # Examples
+/// ```rust
+/// use cli_fb2_to_text::fb2_to_text;
+///
+/// match fb2_to_text("path/to/book.fb2.zip") {
+///     Ok(text) => println!("Extracted text: {}", text),
+///     Err(e) => eprintln!("Error: {}", e),
+/// }
+/// ```
+pub fn fb2_to_text(file_path: &str) -> Result<String, Fb2Error> {
+  let chapters = fb2_chapters(file_path)?;
+  Ok(
+    chapters
+      .into_iter()
+      .map(|chapter| chapter.text)
+      .collect::<Vec<_>>()
+      .join("\n\n"),
+  )
+}
+
+/// Convert an FB2 book to plain text chapters
+///
+/// A chapter starts at each section of the first two levels, and the text
+/// before the first section, such as the title and epigraph of the book, is
+/// a chapter of its own. Note links are marked `[1]`, `[2]`, ... as the
+/// book labels them, and the notes body becomes a final chapter. Joining
+/// the chapter texts with blank lines gives the same text as `fb2_to_text`.
+///
+/// # Examples
+/// ```rust
+/// use cli_fb2_to_text::fb2_chapters;
+///
+/// if let Ok(chapters) = fb2_chapters("path/to/book.fb2") {
+///     for chapter in chapters {
+///         println!("{}", chapter.title);
+///     }
+/// }
+/// ```
+pub fn fb2_chapters(file_path: &str) -> Result<Vec<Chapter>, Fb2Error> {
+  let xml = read_book(file_path)?;
+  let mut reader = Reader::default();
+  reader.read(book_events(&xml)?);
+
+  let mut chapters = reader.chapters;
+  for chapter in &mut chapters {
+    if chapter.title.is_empty() {
+      chapter.title = title_from_text(&chapter.text);
+    }
+  }
+  if chapters.is_empty() {
+    Err(Fb2Error::InvalidFb2("No readable content found in FB2".to_string()))
+  } else {
+    Ok(chapters)
+  }
+}
+
+// Read a book as UTF-8 XML, unzipping `.fb2.zip` files
+fn read_book(file_path: &str) -> Result<String, Fb2Error> {
+  let path = Path::new(file_path);
+  if !path.exists() {
+    return Err(Fb2Error::FileNotFound(file_path.to_string()));
+  }
+  let bytes = std::fs::read(path)
+    .map_err(|e| Fb2Error::InvalidFb2(format!("Failed to read FB2: {e}")))?;
+  let bytes =
+    if bytes.starts_with(b"PK\x03\x04") { unzip_book(bytes)? } else { bytes };
+  Ok(decode_xml(&bytes))
+}
+
+// The first `.fb2` file of a zip archive
+fn unzip_book(bytes: Vec<u8>) -> Result<Vec<u8>, Fb2Error> {
+  let zip_error = |e: zip::result::ZipError| {
+    Fb2Error::InvalidFb2(format!("Failed to unzip FB2: {e}"))
+  };
+  let mut archive = ZipArchive::new(Cursor::new(bytes)).map_err(zip_error)?;
+  for index in 0..archive.len() {
+    let mut entry = archive.by_index(index).map_err(zip_error)?;
+    if !entry.name().to_lowercase().ends_with(".fb2") {
+      continue;
+    }
+    let mut book = Vec::new();
+    entry
+      .read_to_end(&mut book)
+      .map_err(|e| Fb2Error::InvalidFb2(format!("Failed to unzip FB2: {e}")))?;
+    return Ok(book);
+  }
+  Err(Fb2Error::InvalidFb2("No .fb2 file found in zip".to_string()))
+}
+
+// Decode a book in the encoding of its byte order mark or XML declaration,
+// and drop the declaration, which would name the old encoding
+fn decode_xml(bytes: &[u8]) -> String {
+  let head = &bytes[..bytes.len().min(DECLARATION_LEN)];
+  let head = String::from_utf8_lossy(head);
+  let encoding = head
+    .split_once("encoding=")
+    .and_then(|(_, rest)| {
+      let quote = rest.chars().next()?;
+      rest[1..].split(quote).next()
+    })
+    .and_then(|label| Encoding::for_label(label.trim().as_bytes()))
+    .unwrap_or(UTF_8);
+  let text = encoding.decode(bytes).0;
+
+  let start = text.trim_start();
+  match start.strip_prefix("<?xml").and_then(|rest| rest.split_once("?>")) {
+    Some((_, rest)) => rest.to_string(),
+    None => start.to_string(),
+  }
+}
+
+// Events of a book, after checking that it's a FictionBook
+fn book_events(
+  xml: &str,
+) -> Result<impl Iterator<Item = xml::reader::Result<XmlEvent>>, Fb2Error> {
+  let mut events = xml_reader(xml.as_bytes()).into_iter().peekable();
+  loop {
+    match events.peek() {
+      Some(Ok(XmlEvent::StartElement { name, .. })) => {
+        if name.local_name == "FictionBook" {
+          return Ok(events);
+        }
+        return Err(Fb2Error::InvalidFb2(format!(
+          "Expected a FictionBook, found <{}>",
+          name.local_name
+        )));
+      }
+      Some(Err(e)) => {
+        return Err(Fb2Error::InvalidFb2(format!("Failed to parse FB2: {e}")));
+      }
+      Some(Ok(_)) => {
+        events.next();
+      }
+      None => {
+        return Err(Fb2Error::InvalidFb2("Empty document".to_string()));
+      }
+    }
+  }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum BodyKind {
+  Main,
+  /// The `notes` and `comments` bodies that note links point into
+  Notes,
+}
+
+// The bodies of a book, read into chapters
+struct Reader {
+  chapters: Vec<Chapter>,
+  chapter: Option<Chapter>,
+  body: Option<BodyKind>,
+  // Depth inside sections of the current body
+  depth: usize,
+  // Depth inside the description and binaries, which aren't text
+  skip: usize,
+  // Lines of the title being read
+  title: Option<Vec<String>>,
+  // Text of the paragraph, verse or table cell being read
+  text: String,
+  // Whether the text ends in whitespace that the next whitespace collapses
+  // into, true at the start where whitespace is dropped
+  space: bool,
+  stanza: Vec<String>,
+  // Depth inside epigraphs and citations
+  quotes: usize,
+  // Whether a paragraph is being read, whose pictures are inline
+  in_paragraph: bool,
+  cells: Vec<String>,
+  rows: Vec<Vec<String>>,
+  // Whether the first row of the table is a header, and whether the row
+  // being read has only `th` cells so far
+  header: bool,
+  header_row: bool,
+  // Start of the text of each open link in the text, `None` for links
+  // that aren't notes
+  links: Vec<Option<usize>>,
+  notes_title: Option<String>,
+  note_label: Option<String>,
+  note_text: Vec<String>,
+  // Labels and text of the notes of the current notes body
+  notes: Vec<(Option<String>, String)>,
+}
+
+impl Default for Reader {
+  fn default() -> Self {
+    Self {
+      chapters: Vec::new(),
+      chapter: None,
+      body: None,
+      depth: 0,
+      skip: 0,
+      title: None,
+      text: String::new(),
+      space: true,
+      stanza: Vec::new(),
+      quotes: 0,
+      in_paragraph: false,
+      cells: Vec::new(),
+      rows: Vec::new(),
+      header: false,
+      header_row: false,
+      links: Vec::new(),
+      notes_title: None,
+      note_label: None,
+      note_text: Vec::new(),
+      notes: Vec::new(),
+    }
+  }
+}
+
+impl Reader {
+  fn read(
+    &mut self,
+    events: impl Iterator<Item = xml::reader::Result<XmlEvent>>,
+  ) {
+    for event in events {
+      // Keep what was read before a malformed part of the book
+      let Ok(event) = event else { break };
+      match event {
+        XmlEvent::StartElement { name, attributes, .. } => {
+          if self.skip > 0 {
+            self.skip += 1;
+            continue;
+          }
+          self.start(&name.local_name, &attributes);
+        }
+        XmlEvent::Characters(text) | XmlEvent::CData(text)
+          if self.skip == 0 && self.body.is_some() =>
+        {
+          self.push_text(&text);
+        }
+        XmlEvent::EndElement { name } => {
+          if self.skip > 0 {
+            self.skip -= 1;
+            continue;
+          }
+          self.end(&name.local_name);
+        }
+        _ => {}
+      }
+    }
+    self.finish_chapter();
+  }
+
+  fn start(&mut self, name: &str, attributes: &[OwnedAttribute]) {
+    match name {
+      "description" | "binary" | "stylesheet" => self.skip = 1,
+      "body" => {
+        let notes = matches!(
+          attribute(attributes, "name").as_deref(),
+          Some("notes" | "comments" | "footnotes")
+        );
+        self.body = Some(if notes { BodyKind::Notes } else { BodyKind::Main });
+        self.depth = 0;
+        if !notes {
+          self.start_chapter(0);
+        }
+      }
+      "section" => {
+        self.depth += 1;
+        if self.body == Some(BodyKind::Main) && self.depth <= CHAPTER_LEVELS {
+          self.start_chapter(self.depth - 1);
+        }
+      }
+      "title" => self.title = Some(Vec::new()),
+      "p" | "v" | "subtitle" | "text-author" | "td" | "th" => {
+        self.text.clear();
+        self.space = true;
+        self.in_paragraph = true;
+      }
+      "epigraph" | "cite" => self.quotes += 1,
+      "table" => self.rows.clear(),
+      "tr" => self.header_row = true,
+      "image" if !self.in_paragraph && self.title.is_none() => {
+        let alt = attribute(attributes, "alt")
+          .or_else(|| attribute(attributes, "title"))
+          .map(|alt| collapse_whitespace(&alt))
+          .unwrap_or_default();
+        self.push_block(Block::Image { alt });
+      }
+      "a" => {
+        let note = attribute(attributes, "type").as_deref() == Some("note");
+        self.links.push(note.then_some(self.text.len()));
+      }
+      _ => {}
+    }
+  }
+
+  fn end(&mut self, name: &str) {
+    if matches!(name, "p" | "v" | "subtitle" | "text-author" | "td" | "th") {
+      self.in_paragraph = false;
+    }
+    match name {
+      "p" | "text-author" => {
+        let text = self.take_text();
+        self.paragraph(text);
+      }
+      "subtitle" => {
+        let text = self.take_text();
+        if !text.is_empty() {
+          let spans = vec![Span::plain(&text)];
+          self.push_block(Block::Heading { level: self.depth, spans });
+        }
+      }
+      "epigraph" | "cite" => self.quotes = self.quotes.saturating_sub(1),
+      "v" => {
+        let text = self.take_text();
+        if !text.is_empty() {
+          self.stanza.push(text);
+        }
+      }
+      "stanza" => {
+        let stanza = std::mem::take(&mut self.stanza);
+        self.paragraph(stanza.join("\n"));
+      }
+      "td" | "th" => {
+        let text = self.take_text();
+        self.cells.push(text);
+        self.header_row &= name == "th";
+      }
+      "tr" => {
+        let cells = std::mem::take(&mut self.cells);
+        if cells.iter().any(|cell| !cell.is_empty()) {
+          if self.rows.is_empty() {
+            self.header = self.header_row;
+          }
+          self.rows.push(cells);
+        }
+      }
+      "table" => {
+        let rows = std::mem::take(&mut self.rows);
+        if !rows.is_empty() {
+          let rows = rows
+            .iter()
+            .map(|row| row.iter().map(|cell| vec![Span::plain(cell)]).collect())
+            .collect();
+          self.push_block(Block::Table { header: self.header, rows });
+        }
+      }
+      "a" => {
+        // Note links show as `[1]` whether or not the book brackets them
+        if let Some(Some(start)) = self.links.pop()
+          && let Some(label) = self.text.get(start..)
+        {
+          let label = label.trim().to_string();
+          if !label.is_empty() && !label.starts_with('[') {
+            self.text.truncate(start);
+            self.text.push_str(&format!("[{label}]"));
+            self.space = false;
+          }
+        }
+      }
+      "title" => {
+        let lines = self.title.take().unwrap_or_default();
+        self.title(join_title(&lines));
+      }
+      "section" => {
+        if self.body == Some(BodyKind::Notes) && self.depth == 1 {
+          let text = std::mem::take(&mut self.note_text).join(" ");
+          if !text.is_empty() {
+            self.notes.push((self.note_label.take(), text));
+          }
+          self.note_label = None;
+        }
+        self.depth = self.depth.saturating_sub(1);
+      }
+      "body" => {
+        match self.body {
+          Some(BodyKind::Main) => self.finish_chapter(),
+          Some(BodyKind::Notes) => self.finish_notes(),
+          None => {}
+        }
+        self.body = None;
+      }
+      _ => {}
+    }
+  }
+
+  fn title(&mut self, title: String) {
+    if title.is_empty() {
+      return;
+    }
+    match self.body {
+      Some(BodyKind::Main) => {
+        if let Some(chapter) = &mut self.chapter
+          && chapter.title.is_empty()
+          && chapter.blocks.is_empty()
+        {
+          chapter.title = title.clone();
+        }
+        let level = self.depth.saturating_sub(1);
+        self.push_block(Block::Heading {
+          level,
+          spans: vec![Span::plain(&title)],
+        });
+      }
+      Some(BodyKind::Notes) => match self.depth {
+        0 => self.notes_title = Some(title),
+        1 => self.note_label = Some(title),
+        _ => self.note_text.push(title),
+      },
+      None => {}
+    }
+  }
+
+  // Add a paragraph, a quote in epigraphs and citations
+  fn paragraph(&mut self, text: String) {
+    if text.is_empty() {
+      return;
+    }
+    let spans = vec![Span::plain(&text)];
+    let block = if self.quotes > 0 {
+      Block::Quote(spans)
+    } else {
+      Block::Paragraph(spans)
+    };
+    self.push_block(block);
+  }
+
+  // Add a block to the chapter being read, or its text to the title or
+  // note being read
+  fn push_block(&mut self, block: Block) {
+    if let Some(title) = &mut self.title {
+      title.push(block.text());
+      return;
+    }
+    match self.body {
+      Some(BodyKind::Main) => {
+        let chapter = self.chapter.get_or_insert_with(Chapter::default);
+        chapter.blocks.push(block);
+      }
+      Some(BodyKind::Notes) if self.depth > 0 => {
+        self.note_text.push(block.text());
+      }
+      _ => {}
+    }
+  }
+
+  fn start_chapter(&mut self, level: usize) {
+    self.finish_chapter();
+    self.chapter = Some(Chapter { level, ..Default::default() });
+  }
+
+  fn finish_chapter(&mut self) {
+    if let Some(mut chapter) = self.chapter.take()
+      && !chapter.blocks.is_empty()
+    {
+      chapter.text = blocks_text(&chapter.blocks);
+      self.chapters.push(chapter);
+    }
+  }
+
+  fn finish_notes(&mut self) {
+    let notes = std::mem::take(&mut self.notes);
+    let title = self.notes_title.take().unwrap_or_else(|| "Notes".to_string());
+    if notes.is_empty() {
+      return;
+    }
+    let heading = Block::Heading { level: 0, spans: vec![Span::plain(&title)] };
+    let notes = notes.into_iter().map(|(label, text)| {
+      let spans = vec![Span::plain(&text)];
+      match label {
+        Some(label) => Block::ListItem {
+          depth: 0,
+          marker: format!("[{}]", label.trim_matches(['[', ']'])),
+          spans,
+        },
+        None => Block::Paragraph(spans),
+      }
+    });
+    let blocks: Vec<Block> = std::iter::once(heading).chain(notes).collect();
+    self.chapters.push(Chapter {
+      text: blocks_text(&blocks),
+      title,
+      level: 0,
+      blocks,
+    });
+  }
+
+  // Add text from the XML, where runs of whitespace show as one space
+  fn push_text(&mut self, text: &str) {
+    for c in text.chars() {
+      if c.is_whitespace() {
+        if !self.space {
+          self.text.push(' ');
+          self.space = true;
+        }
+      } else {
+        self.text.push(c);
+        self.space = false;
+      }
+    }
+  }
+
+  fn take_text(&mut self) -> String {
+    self.space = true;
+    std::mem::take(&mut self.text).trim().to_string()
+  }
+}
+
+// Join the lines of a title like "Chapter 1" and "The Beginning" into
+// "Chapter 1. The Beginning"
+fn join_title(lines: &[String]) -> String {
+  let mut title = String::new();
+  for line in lines {
+    if !title.is_empty() {
+      if !title.ends_with(['.', '!', '?', ':', ';', '…']) {
+        title.push('.');
+      }
+      title.push(' ');
+    }
+    title.push_str(line);
+  }
+  title
+}
+
+// Plain text of blocks, separated by blank lines
+fn blocks_text(blocks: &[Block]) -> String {
+  blocks.iter().map(Block::text).collect::<Vec<_>>().join("\n\n")
+}
+
+// Use the first line of a chapter as its title
+fn title_from_text(text: &str) -> String {
+  let line = text.lines().next().unwrap_or_default().trim();
+  line.chars().take(80).collect()
+}
+
+fn decode_base64(bytes: &[u8]) -> Vec<u8> {
+  let mut decoded = Vec::with_capacity(bytes.len() * 3 / 4);
+  let mut buffer = 0u32;
+  let mut bits = 0;
+  for &byte in bytes {
+    let value = match byte {
+      b'A'..=b'Z' => byte - b'A',
+      b'a'..=b'z' => byte - b'a' + 26,
+      b'0'..=b'9' => byte - b'0' + 52,
+      b'+' => 62,
+      b'/' => 63,
+      b'=' => break,
+      _ => continue,
+    };
+    buffer = (buffer << 6) | u32::from(value);
+    bits += 6;
+    if bits >= 8 {
+      bits -= 8;
+      decoded.push((buffer >> bits) as u8);
+    }
+  }
+  decoded
+}
+
+fn ends_with(path: &[String], names: &[&str]) -> bool {
+  path.len() >= names.len()
+    && path[path.len() - names.len()..]
+      .iter()
+      .zip(names)
+      .all(|(name, expected)| name == expected)
+}
+
+fn xml_reader(bytes: &[u8]) -> EventReader<&[u8]> {
+  ParserConfig::new().add_entity("nbsp", "\u{a0}").create_reader(bytes)
+}
+
+fn attribute(attributes: &[OwnedAttribute], name: &str) -> Option<String> {
+  attributes
+    .iter()
+    .find(|attr| attr.name.local_name == name)
+    .map(|attr| attr.value.clone())
+}
+
+fn collapse_whitespace(text: &str) -> String {
+  text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_fb2_error_display() {
+    let file_error = Fb2Error::FileNotFound("test.fb2".to_string());
+    assert_eq!(format!("{file_error}"), "FB2 file not found: test.fb2");
+
+    let invalid_error = Fb2Error::InvalidFb2("Bad format".to_string());
+    assert_eq!(format!("{invalid_error}"), "Invalid FB2 format: Bad format");
+  }
+
+  #[test]
+  fn test_legacy_encoding() {
+    let xml = b"<?xml version=\"1.0\" encoding=\"windows-1251\"?>\n<FictionBook>\xcf\xf0\xe8\xe2\xe5\xf2</FictionBook>";
+    assert_eq!(decode_xml(xml), "\n<FictionBook>Привет</FictionBook>");
+    assert_eq!(decode_xml(b"<FictionBook/>"), "<FictionBook/>");
+  }
+
+  #[test]
+  fn test_title_lines() {
+    let lines = ["Chapter 1".to_string(), "The Beginning".to_string()];
+    assert_eq!(join_title(&lines), "Chapter 1. The Beginning");
+    let lines = ["Part One:".to_string(), "Arrival".to_string()];
+    assert_eq!(join_title(&lines), "Part One: Arrival");
+  }
+
+  #[test]
+  fn test_file_not_found() {
+    let result = fb2_to_text("definitely_nonexistent_file.fb2");
+    match result.unwrap_err() {
+      Fb2Error::FileNotFound(path) => {
+        assert_eq!(path, "definitely_nonexistent_file.fb2");
+      }
+      _ => panic!("Expected FileNotFound error"),
+    }
+  }
+}
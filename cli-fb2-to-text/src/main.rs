@@ -0,0 +1,58 @@
+use cli_fb2_to_text::Fb2Metadata;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+  let program =
+    std::env::args().next().unwrap_or_else(|| "cli-fb2-to-text".to_string());
+  let args: Vec<String> = std::env::args().skip(1).collect();
+
+  let metadata = args.iter().any(|arg| arg == "--metadata");
+  let json = args.iter().any(|arg| arg == "--json");
+  let file_path =
+    args.iter().find(|arg| !arg.starts_with("--")).ok_or_else(|| {
+      eprintln!("Usage: {program} [--metadata] [--json] <fb2_file>");
+      std::process::exit(1);
+    })?;
+
+  if json {
+    let metadata = cli_fb2_to_text::fb2_metadata(file_path)?;
+    println!("{}", serde_json::to_string_pretty(&metadata)?);
+  } else if metadata {
+    print_metadata(&cli_fb2_to_text::fb2_metadata(file_path)?);
+  } else {
+    println!("{}", cli_fb2_to_text::fb2_to_text(file_path)?);
+  }
+
+  Ok(())
+}
+
+fn print_metadata(metadata: &Fb2Metadata) {
+  let series =
+    metadata.series.as_ref().map(|series| match &metadata.series_number {
+      Some(number) => format!("{series} #{number}"),
+      None => series.clone(),
+    });
+  let fields = [
+    ("Title", metadata.title.clone()),
+    ("Authors", Some(metadata.authors.join(", "))),
+    ("Language", metadata.language.clone()),
+    ("Series", series),
+    ("Publisher", metadata.publisher.clone()),
+    ("Date", metadata.date.clone()),
+    ("ISBN", metadata.isbn.clone()),
+    ("Id", metadata.id.clone()),
+    ("Genres", Some(metadata.genres.join(", "))),
+    ("Description", metadata.description.clone()),
+    (
+      "Cover",
+      metadata.cover.as_ref().map(|cover| {
+        format!("{} ({} bytes)", cover.media_type, cover.data.len())
+      }),
+    ),
+  ];
+
+  for (name, value) in fields {
+    if let Some(value) = value.filter(|value| !value.is_empty()) {
+      println!("{name}: {value}");
+    }
+  }
+}
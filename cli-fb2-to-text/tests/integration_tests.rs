@@ -0,0 +1,110 @@
+use cli_fb2_to_text::{Fb2Error, fb2_chapters, fb2_metadata, fb2_to_text};
+use document_converter::{Block, Span};
+use std::io::Write;
+use zip::write::SimpleFileOptions;
+
+const FIXTURE: &str = "../test-data/fb2/test.fb2";
+
+#[test]
+fn test_fixture_chapters() {
+  let chapters = fb2_chapters(FIXTURE).unwrap();
+  let titles: Vec<(&str, usize)> = chapters
+    .iter()
+    .map(|chapter| (chapter.title.as_str(), chapter.level))
+    .collect();
+  assert_eq!(
+    titles,
+    [
+      ("Jane Doe. The Lighthouse", 0),
+      ("Part One. The Keeper", 0),
+      ("Chapter 1", 1),
+      ("Chapter 2", 1),
+      ("Part Two", 0),
+      ("Notes", 0),
+    ]
+  );
+  assert_eq!(
+    chapters[0].text,
+    "Jane Doe. The Lighthouse\n\nThe sea remembers.\n\nOld saying"
+  );
+  assert_eq!(
+    chapters[2].text,
+    "Chapter 1\n\nThe keeper climbed the stairs[1] every night.\n\nHe sang as he went:\n\nLight the lamp,\nmind the sea."
+  );
+  assert_eq!(
+    chapters[3].text,
+    "Chapter 2\n\nThe storm came[2].\n\nNight | Wind\n\nFirst | Strong"
+  );
+  assert_eq!(
+    chapters[5].text,
+    "Notes\n\n[1] One hundred and twelve steps.\n\n[2] In the autumn of that year."
+  );
+
+  // The epigraph is a quote and the table keeps its header row
+  assert_eq!(
+    chapters[0].blocks,
+    [
+      Block::Heading {
+        level: 0,
+        spans: vec![Span::plain("Jane Doe. The Lighthouse")]
+      },
+      Block::Quote(vec![Span::plain("The sea remembers.")]),
+      Block::Quote(vec![Span::plain("Old saying")]),
+    ]
+  );
+  assert!(matches!(
+    chapters[3].blocks.last(),
+    Some(Block::Table { header: true, rows }) if rows.len() == 2
+  ));
+}
+
+#[test]
+fn test_fixture_metadata() {
+  let metadata = fb2_metadata(FIXTURE).unwrap();
+  assert_eq!(metadata.title.as_deref(), Some("The Lighthouse"));
+  assert_eq!(metadata.authors, ["Jane Doe", "Roe"]);
+  assert_eq!(metadata.genres, ["sf_fantasy", "adventure"]);
+  assert_eq!(metadata.language.as_deref(), Some("en"));
+  assert_eq!(metadata.date.as_deref(), Some("2021"));
+  assert_eq!(
+    metadata.description.as_deref(),
+    Some("A keeper and a storm. Told in two parts.")
+  );
+  assert_eq!(metadata.series.as_deref(), Some("Coast Tales"));
+  assert_eq!(metadata.series_number.as_deref(), Some("2"));
+  assert_eq!(metadata.publisher.as_deref(), Some("Hygg Press"));
+  assert_eq!(metadata.isbn.as_deref(), Some("978-0-306-40615-7"));
+  assert_eq!(metadata.id.as_deref(), Some("5f0c7f1e-hygg-test-fb2"));
+
+  let cover = metadata.cover.unwrap();
+  assert_eq!(cover.media_type, "image/png");
+  assert!(cover.data.starts_with(b"\x89PNG"));
+}
+
+#[test]
+fn test_zipped_book() {
+  let path = std::env::temp_dir()
+    .join(format!("cli-fb2-to-text-{}.fb2.zip", std::process::id()));
+  let mut zip = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+  zip.start_file("The Lighthouse.fb2", SimpleFileOptions::default()).unwrap();
+  zip.write_all(&std::fs::read(FIXTURE).unwrap()).unwrap();
+  zip.finish().unwrap();
+
+  let file = path.to_str().unwrap();
+  assert_eq!(fb2_to_text(file).unwrap(), fb2_to_text(FIXTURE).unwrap());
+  assert_eq!(
+    fb2_metadata(file).unwrap().title.as_deref(),
+    Some("The Lighthouse")
+  );
+
+  std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_not_an_fb2() {
+  let result = fb2_to_text("../test-data/sample.txt");
+  assert!(matches!(result, Err(Fb2Error::InvalidFb2(_))));
+
+  let result = fb2_to_text("../test-data/odf/test.odt");
+  assert!(matches!(result, Err(Fb2Error::InvalidFb2(_))));
+}
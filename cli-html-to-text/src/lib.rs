@@ -0,0 +1,1014 @@
+//! # CLI HTML to Text Converter
+//!
+//! A Rust library for converting saved web pages, `.html` files and `.mhtml`
+//! archives, to plain text for offline reading without pandoc.
+//!
+//! ## Features
+//! - Keep the article of a page and leave out menus, sidebars, footers,
+//!   comments and scripts
+//! - Split the text into sections at headings
+//! - Number and indent list items
+//! - Number links `[1]`, `[2]`, ... and list their addresses at the end
+//! - Flatten tables to one line per row
+//! - Keep the structure as blocks: headings, lists, tables, quotes, code
+//!   and pictures
+//! - Read the page properties: title, author, description and language
+//!
+//! ## Usage
+//! ```rust
+//! use cli_html_to_text::html_to_text;
+//!
+//! match html_to_text("path/to/article.html") {
+//!     Ok(text) => println!("Extracted text: {}", text),
+//!     Err(e) => eprintln!("Error: {}", e),
+//! }
+//! ```
+
+use document_converter::{Block, Span};
+use encoding_rs::{Encoding, UTF_8};
+use html5ever::QualName;
+use html5ever::tendril::TendrilSink;
+use markup5ever_rcdom::{Handle, Node, NodeData, RcDom};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+use std::rc::Rc;
+
+mod mhtml;
+mod readability;
+
+/// Version of this crate
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Headings of the first two levels start a new section, deeper ones stay in
+/// the text of their section
+const SECTION_LEVELS: usize = 2;
+
+/// A `<meta charset>` past this many bytes is not looked for
+const CHARSET_SNIFF_LEN: usize = 4096;
+
+/// Elements that start and end a paragraph
+const BLOCK_ELEMENTS: &[&str] = &[
+  "address",
+  "article",
+  "aside",
+  "blockquote",
+  "body",
+  "center",
+  "details",
+  "dialog",
+  "div",
+  "dl",
+  "dt",
+  "fieldset",
+  "figcaption",
+  "figure",
+  "footer",
+  "form",
+  "header",
+  "hgroup",
+  "html",
+  "legend",
+  "main",
+  "nav",
+  "p",
+  "section",
+  "summary",
+];
+
+/// Custom error type for HTML processing errors
+#[derive(Debug)]
+pub enum HtmlError {
+  FileNotFound(String),
+  InvalidHtml(String),
+}
+
+impl fmt::Display for HtmlError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      HtmlError::FileNotFound(path) => {
+        write!(f, "HTML file not found: {path}")
+      }
+      HtmlError::InvalidHtml(msg) => write!(f, "Invalid HTML format: {msg}"),
+    }
+  }
+}
+
+impl Error for HtmlError {}
+
+/// A heading and the text following it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Section {
+  /// The heading, `None` for text before the first heading
+  pub title: Option<String>,
+  /// Nesting depth, 0 for top level sections
+  pub level: usize,
+  /// Plain text starting with the heading, with paragraphs, list items and
+  /// table rows separated by blank lines
+  pub text: String,
+  /// The heading and the paragraphs, list items, tables, quotes, code and
+  /// pictures after it
+  pub blocks: Vec<Block>,
+}
+
+/// Page properties from `<title>`, `<html lang>` and `<meta>` tags
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HtmlMetadata {
+  /// The `og:title` of the page, or its `<title>`
+  pub title: Option<String>,
+  pub author: Option<String>,
+  pub description: Option<String>,
+  pub language: Option<String>,
+  pub keywords: Option<String>,
+  /// Publication date as written in the page, e.g. `2024-01-01T09:00:00Z`
+  pub published: Option<String>,
+}
+
+/// Read the properties of a web page without converting its content
+///
+/// # Examples
+/// ```rust
+/// use cli_html_to_text::html_metadata;
+///
+/// if let Ok(metadata) = html_metadata("path/to/article.html") {
+///     println!("{}", metadata.title.unwrap_or_default());
+/// }
+/// ```
+pub fn html_metadata(file_path: &str) -> Result<HtmlMetadata, HtmlError> {
+  Ok(read_page(file_path)?.metadata())
+}
+
+/// Convert a web page to plain text
+///
+/// # Error Cases
+/// * `HtmlError::FileNotFound` - The specified file doesn't exist
+/// * `HtmlError::InvalidHtml` - The file is not text, is an MHTML archive
+///   without a page, or the page has no text
+///
+/// # Examples
+/// ```rust
+/// use cli_html_to_text::html_to_text;
+///
+/// match html_to_text("path/to/article.html") {
+///     Ok(text) => println!("Extracted text: {}", text),
+///     Err(e) => eprintln!("Error: {}", e),
+/// }
+/// ```
+pub fn html_to_text(file_path: &str) -> Result<String, HtmlError> {
+  let sections = html_sections(file_path)?;
+  Ok(
+    sections
+      .into_iter()
+      .map(|section| section.text)
+      .collect::<Vec<_>>()
+      .join("\n\n"),
+  )
+}
+
+/// Convert a web page to plain text sections
+///
+/// Only the article of the page is kept, see the crate docs. A section
+/// starts at each `h1` and `h2`. Links are marked `[1]`, `[2]`, ... in the
+/// text, with a link used twice keeping its number, and their addresses are
+/// collected in a final "Links" section. Joining the section texts with
+/// blank lines gives the same text as `html_to_text`.
+///
+/// # Examples
+/// ```rust
+/// use cli_html_to_text::html_sections;
+///
+/// if let Ok(sections) = html_sections("path/to/article.html") {
+///     for section in sections {
+///         println!("{}", section.title.unwrap_or_default());
+///     }
+/// }
+/// ```
+pub fn html_sections(file_path: &str) -> Result<Vec<Section>, HtmlError> {
+  let page = read_page(file_path)?;
+  let root = readability::article(&page.dom.document);
+
+  let mut body = Body { base: page.base.clone(), ..Default::default() };
+  // An article without its own heading is titled by the page
+  let is_body = find_element(&page.dom.document, "body")
+    .is_some_and(|body| Rc::ptr_eq(&body, &root));
+  if !is_body
+    && find_element(&root, "h1").is_none()
+    && let Some(title) = page.metadata().title
+  {
+    body
+      .blocks
+      .push(Block::Heading { level: 0, spans: vec![Span::plain(&title)] });
+  }
+  body.walk(&root);
+  body.flush();
+  let mut sections = split_sections(body.blocks);
+
+  if !body.links.is_empty() {
+    let title = "Links".to_string();
+    let heading = Block::Heading { level: 0, spans: vec![Span::plain(&title)] };
+    let links =
+      body.links.iter().enumerate().map(|(index, url)| Block::ListItem {
+        depth: 0,
+        marker: format!("[{}]", index + 1),
+        spans: vec![Span::plain(url)],
+      });
+    let blocks: Vec<Block> = std::iter::once(heading).chain(links).collect();
+    sections.push(Section {
+      title: Some(title),
+      level: 0,
+      text: blocks_text(&blocks),
+      blocks,
+    });
+  }
+
+  if sections.is_empty() {
+    Err(HtmlError::InvalidHtml("No readable content found in HTML".to_string()))
+  } else {
+    Ok(sections)
+  }
+}
+
+/// Read the blocks of an HTML or XHTML document, such as a chapter of an
+/// e-book
+///
+/// Unlike `html_sections`, the whole body is read without looking for the
+/// article, and links aren't numbered.
+///
+/// # Examples
+/// ```rust
+/// use cli_html_to_text::html_blocks;
+///
+/// let blocks = html_blocks("<h1>Birds</h1><p>Seen early.</p>");
+/// assert_eq!(blocks.len(), 2);
+/// ```
+pub fn html_blocks(html: &str) -> Vec<Block> {
+  let dom =
+    html5ever::parse_document(RcDom::default(), Default::default()).one(html);
+  let root =
+    find_element(&dom.document, "body").unwrap_or_else(|| dom.document.clone());
+  let mut body = Body { article: false, ..Default::default() };
+  body.walk(&root);
+  body.flush();
+  body.blocks
+}
+
+struct Page {
+  dom: RcDom,
+  /// Address that relative links are resolved against
+  base: Option<String>,
+}
+
+impl Page {
+  fn metadata(&self) -> HtmlMetadata {
+    let document = &self.dom.document;
+    let mut metadata = HtmlMetadata {
+      language: find_element(document, "html")
+        .and_then(|html| attribute(&html, "lang"))
+        .map(|lang| lang.trim().to_string())
+        .filter(|lang| !lang.is_empty()),
+      ..Default::default()
+    };
+    let mut og_title = None;
+
+    let mut metas = Vec::new();
+    find_elements(document, "meta", &mut metas);
+    for meta in metas {
+      let Some(name) = attribute(&meta, "name")
+        .or_else(|| attribute(&meta, "property"))
+        .or_else(|| attribute(&meta, "http-equiv"))
+      else {
+        continue;
+      };
+      let value = attribute(&meta, "content")
+        .map(|content| collapse_whitespace(&content))
+        .filter(|content| !content.is_empty());
+      let field = match name.trim().to_ascii_lowercase().as_str() {
+        "og:title" => &mut og_title,
+        "author" | "article:author" | "dc.creator" => &mut metadata.author,
+        "description" | "og:description" => &mut metadata.description,
+        "keywords" => &mut metadata.keywords,
+        "article:published_time" | "date" | "dc.date" => {
+          &mut metadata.published
+        }
+        "content-language" => &mut metadata.language,
+        _ => continue,
+      };
+      if field.is_none() {
+        *field = value;
+      }
+    }
+
+    let title = find_element(document, "title")
+      .map(|title| collapse_whitespace(&text_content(&title)))
+      .filter(|title| !title.is_empty());
+    metadata.title = og_title.or(title);
+    metadata
+  }
+}
+
+fn read_page(file_path: &str) -> Result<Page, HtmlError> {
+  let path = Path::new(file_path);
+  if !path.exists() {
+    return Err(HtmlError::FileNotFound(file_path.to_string()));
+  }
+  let bytes = std::fs::read(path)
+    .map_err(|e| HtmlError::InvalidHtml(format!("Failed to read HTML: {e}")))?;
+
+  let archive = path.extension().is_some_and(|ext| {
+    ext.eq_ignore_ascii_case("mhtml") || ext.eq_ignore_ascii_case("mht")
+  }) || mhtml::is_mhtml(&bytes);
+  let (html, charset, location) = if archive {
+    let page = mhtml::html_page(&bytes).ok_or_else(|| {
+      HtmlError::InvalidHtml("No HTML page found in MHTML archive".to_string())
+    })?;
+    (page.html, page.charset, page.location)
+  } else {
+    (bytes, None, None)
+  };
+
+  let text = decode(&html, charset.as_deref());
+  if text.contains('\0') {
+    return Err(HtmlError::InvalidHtml("File is not text".to_string()));
+  }
+  let dom =
+    html5ever::parse_document(RcDom::default(), Default::default()).one(text);
+
+  let base = find_element(&dom.document, "base")
+    .and_then(|base| attribute(&base, "href"))
+    .map(|href| resolve_url(location.as_deref(), &href))
+    .or(location);
+  Ok(Page { dom, base })
+}
+
+// Decode a page in the encoding of its byte order mark, of the MHTML part,
+// or of its `<meta charset>`, in that order, and UTF-8 otherwise
+fn decode(bytes: &[u8], charset: Option<&str>) -> String {
+  let encoding = charset
+    .and_then(|charset| Encoding::for_label(charset.trim().as_bytes()))
+    .or_else(|| meta_charset(bytes))
+    .unwrap_or(UTF_8);
+  // A byte order mark wins over the other two
+  encoding.decode(bytes).0.into_owned()
+}
+
+fn meta_charset(bytes: &[u8]) -> Option<&'static Encoding> {
+  let head = &bytes[..bytes.len().min(CHARSET_SNIFF_LEN)];
+  let head = String::from_utf8_lossy(head).to_ascii_lowercase();
+  let start = head.find("charset=")? + "charset=".len();
+  let label: String = head[start..]
+    .trim_start_matches(['"', '\'', ' '])
+    .chars()
+    .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':'))
+    .collect();
+  Encoding::for_label(label.as_bytes())
+}
+
+// Resolve a link against the address of the page, leaving it as it is when
+// the page has no address
+fn resolve_url(base: Option<&str>, href: &str) -> String {
+  let href = href.trim();
+  let Some(base) = base else { return href.to_string() };
+  let has_scheme = href.split_once(':').is_some_and(|(scheme, _)| {
+    scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+      && scheme.chars().all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+  });
+  let Some((scheme, rest)) = base.split_once("://") else {
+    return href.to_string();
+  };
+  if has_scheme {
+    return href.to_string();
+  }
+  if let Some(rest) = href.strip_prefix("//") {
+    return format!("{scheme}://{rest}");
+  }
+
+  let host_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+  let origin = &base[..scheme.len() + 3 + host_end];
+  let base_path = base[origin.len()..].split(['?', '#']).next().unwrap_or("");
+  let joined = if href.starts_with('/') {
+    href.to_string()
+  } else if href.starts_with(['?', '#']) {
+    format!("{base_path}{href}")
+  } else {
+    let dir = base_path.rfind('/').map_or("/", |at| &base_path[..=at]);
+    format!("{dir}{href}")
+  };
+
+  // Drop the "." and ".." of the path, not of the query
+  let split = joined.find(['?', '#']).unwrap_or(joined.len());
+  let (path, suffix) = joined.split_at(split);
+  let mut segments: Vec<&str> = Vec::new();
+  for segment in path.split('/') {
+    match segment {
+      "." => {}
+      ".." => {
+        if segments.len() > 1 {
+          segments.pop();
+        }
+      }
+      _ => segments.push(segment),
+    }
+  }
+  format!("{origin}{}{suffix}", segments.join("/"))
+}
+
+struct List {
+  ordered: bool,
+  /// `type` of an `ol`: `1`, `a`, `A`, `i` or `I`
+  format: String,
+  /// Number of the next item
+  next: u32,
+  /// Marker of the current item, until its first paragraph takes it
+  pending: Option<String>,
+}
+
+#[derive(Default)]
+struct Table {
+  caption: Option<String>,
+  /// Whether the first row is a header, of `th` cells or in a `thead`
+  header: bool,
+  rows: Vec<Vec<String>>,
+  cells: Vec<String>,
+  cell: Vec<String>,
+  // Whether the row being read has only `th` cells so far
+  header_row: bool,
+  in_head: bool,
+}
+
+// The article of a page, read into blocks
+struct Body {
+  // Whether this is the article of a web page, which leaves out page
+  // furniture and numbers links, rather than a whole document
+  article: bool,
+  base: Option<String>,
+  blocks: Vec<Block>,
+  // Text of the paragraph being read
+  text: String,
+  // Whether the text ends in whitespace that the next whitespace collapses
+  // into, true at the start of a paragraph where whitespace is dropped
+  space: bool,
+  heading: Option<usize>,
+  preformatted: usize,
+  // Depth inside `blockquote` elements
+  quotes: usize,
+  lists: Vec<List>,
+  // Depth inside `dd` elements, which are indented like list items
+  definitions: usize,
+  tables: Vec<Table>,
+  // Addresses of the links in the order they're first used, numbered from
+  // 1 in the text
+  links: Vec<String>,
+  link_numbers: HashMap<String, usize>,
+}
+
+impl Default for Body {
+  fn default() -> Self {
+    Self {
+      article: true,
+      base: None,
+      blocks: Vec::new(),
+      text: String::new(),
+      space: true,
+      heading: None,
+      preformatted: 0,
+      quotes: 0,
+      lists: Vec::new(),
+      definitions: 0,
+      tables: Vec::new(),
+      links: Vec::new(),
+      link_numbers: HashMap::new(),
+    }
+  }
+}
+
+impl Body {
+  fn walk(&mut self, node: &Node) {
+    for child in node.children.borrow().iter() {
+      match &child.data {
+        NodeData::Text { contents } => {
+          let contents = contents.borrow();
+          if self.preformatted > 0 {
+            self.push_literal(&contents);
+          } else {
+            self.push_text(&contents);
+          }
+        }
+        NodeData::Element { name, .. } if !self.is_skipped(child, name) => {
+          self.element(child, &name.local);
+        }
+        _ => {}
+      }
+    }
+  }
+
+  fn is_skipped(&self, node: &Node, name: &QualName) -> bool {
+    if self.article {
+      readability::is_boilerplate(node)
+    } else {
+      matches!(&*name.local, "script" | "style" | "template" | "noscript")
+    }
+  }
+
+  fn element(&mut self, node: &Node, name: &str) {
+    match name {
+      "br" => self.push_literal("\n"),
+      "hr" => self.flush(),
+      "img" => {
+        let alt = attribute(node, "alt")
+          .map(|alt| collapse_whitespace(&alt))
+          .unwrap_or_default();
+        // A picture on its own is a block, one in a line of text stays in it
+        let inline = !self.text.trim().is_empty()
+          || self.heading.is_some()
+          || !self.tables.is_empty()
+          || self.lists.len() + self.definitions > 0;
+        if inline {
+          if !alt.is_empty() {
+            self.push_text(&format!("[{alt}]"));
+          }
+        } else if !alt.is_empty() || !self.article {
+          // Pictures without a description are mostly decoration on web
+          // pages, but the figures of a book
+          self.flush();
+          self.blocks.push(Block::Image { alt });
+        }
+      }
+      "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+        self.flush();
+        self.heading = Some(usize::from(name.as_bytes()[1] - b'1'));
+        self.walk(node);
+        self.flush();
+        self.heading = None;
+      }
+      "pre" | "listing" | "xmp" | "plaintext" => {
+        self.flush();
+        self.preformatted += 1;
+        self.walk(node);
+        self.flush();
+        self.preformatted -= 1;
+      }
+      "ul" | "ol" | "menu" => {
+        self.flush();
+        let start =
+          attribute(node, "start").and_then(|v| v.trim().parse().ok());
+        self.lists.push(List {
+          ordered: name == "ol",
+          format: attribute(node, "type").unwrap_or_default(),
+          next: start.unwrap_or(1),
+          pending: None,
+        });
+        self.walk(node);
+        self.flush();
+        self.lists.pop();
+      }
+      "li" => {
+        self.flush();
+        let value =
+          attribute(node, "value").and_then(|v| v.trim().parse().ok());
+        if let Some(list) = self.lists.last_mut() {
+          list.pending = Some(if list.ordered {
+            let number = value.unwrap_or(list.next);
+            list.next = number + 1;
+            format!("{}.", format_number(number, &list.format))
+          } else {
+            "•".to_string()
+          });
+        }
+        self.walk(node);
+        self.flush();
+      }
+      "blockquote" => {
+        self.flush();
+        self.quotes += 1;
+        self.walk(node);
+        self.flush();
+        self.quotes -= 1;
+      }
+      "dd" => {
+        self.flush();
+        self.definitions += 1;
+        self.walk(node);
+        self.flush();
+        self.definitions -= 1;
+      }
+      "table" => {
+        self.flush();
+        self.tables.push(Table::default());
+        self.walk(node);
+        self.flush();
+        let Some(table) = self.tables.pop() else { return };
+        match self.tables.last_mut() {
+          // A nested table becomes part of the cell it sits in
+          Some(outer) => {
+            outer.cell.extend(table.caption);
+            outer.cell.extend(table.rows.iter().map(|row| row.join(" | ")));
+          }
+          None => {
+            if let Some(caption) = table.caption {
+              self.blocks.push(Block::Paragraph(vec![Span::plain(&caption)]));
+            }
+            if !table.rows.is_empty() {
+              self.blocks.push(Block::Table {
+                header: table.header,
+                rows: table
+                  .rows
+                  .iter()
+                  .map(|row| {
+                    row.iter().map(|cell| vec![Span::plain(cell)]).collect()
+                  })
+                  .collect(),
+              });
+            }
+          }
+        }
+      }
+      "thead" => {
+        if let Some(table) = self.tables.last_mut() {
+          table.in_head = true;
+        }
+        self.walk(node);
+        if let Some(table) = self.tables.last_mut() {
+          table.in_head = false;
+        }
+      }
+      "caption" => {
+        self.flush();
+        self.walk(node);
+        self.flush();
+        if let Some(table) = self.tables.last_mut() {
+          let caption = std::mem::take(&mut table.cell).join(" ");
+          if !caption.is_empty() {
+            table.caption = Some(caption);
+          }
+        }
+      }
+      "tr" => {
+        self.flush();
+        if let Some(table) = self.tables.last_mut() {
+          table.header_row = true;
+        }
+        self.walk(node);
+        self.flush();
+        if let Some(table) = self.tables.last_mut() {
+          let cells = std::mem::take(&mut table.cells);
+          if cells.iter().any(|cell| !cell.is_empty()) {
+            if table.rows.is_empty() {
+              table.header = table.header_row || table.in_head;
+            }
+            table.rows.push(cells);
+          }
+        }
+      }
+      "td" | "th" => {
+        self.flush();
+        self.walk(node);
+        self.flush();
+        if let Some(table) = self.tables.last_mut() {
+          let cell = table.cell.join(" ");
+          table.cells.push(cell);
+          table.cell.clear();
+          table.header_row &= name == "th";
+        }
+      }
+      "a" => {
+        let start = self.text.len();
+        self.walk(node);
+        if let Some(href) = attribute(node, "href") {
+          self.link(start, &href);
+        }
+      }
+      _ if BLOCK_ELEMENTS.contains(&name) => {
+        self.flush();
+        self.walk(node);
+        self.flush();
+      }
+      _ => self.walk(node),
+    }
+  }
+
+  // Number the link whose text starts at `start` of the paragraph
+  fn link(&mut self, start: usize, href: &str) {
+    let href = href.trim();
+    let lowercase = href.to_ascii_lowercase();
+    if !self.article
+      || href.is_empty()
+      || href.starts_with('#')
+      || lowercase.starts_with("javascript:")
+      || lowercase.starts_with("data:")
+      || self.heading.is_some()
+      || self.preformatted > 0
+    {
+      return;
+    }
+    let url = resolve_url(self.base.as_deref(), href);
+    // Links spelled out in the text don't need a number
+    let label = self.text.get(start..).unwrap_or_default().trim();
+    if label.is_empty() || label == href || label == url {
+      return;
+    }
+
+    let number = *self.link_numbers.entry(url.clone()).or_insert_with(|| {
+      self.links.push(url);
+      self.links.len()
+    });
+    // The number goes right after the text, before the space ending it
+    let space = self.text.ends_with(' ');
+    if space {
+      self.text.pop();
+    }
+    self.text.push_str(&format!("[{number}]"));
+    if space {
+      self.text.push(' ');
+    }
+    self.space = space;
+  }
+
+  // Add text from the markup, where runs of whitespace show as one space
+  fn push_text(&mut self, text: &str) {
+    for c in text.chars() {
+      if matches!(c, ' ' | '\t' | '\r' | '\n' | '\x0c') {
+        if !self.space {
+          self.text.push(' ');
+          self.space = true;
+        }
+      } else {
+        self.text.push(c);
+        self.space = false;
+      }
+    }
+  }
+
+  // Add preformatted text and line breaks as they are
+  fn push_literal(&mut self, text: &str) {
+    self.text.push_str(text);
+    self.space = text.ends_with(char::is_whitespace);
+  }
+
+  // Turn the text read so far into a heading, a paragraph, a list item,
+  // a quote, code or the text of a table cell
+  fn flush(&mut self) {
+    let text = std::mem::take(&mut self.text);
+    self.space = true;
+    let Some(text) = paragraph_text(&text) else { return };
+
+    let in_list = self.lists.len() + self.definitions > 0;
+    if let Some(table) = self.tables.last_mut() {
+      table.cell.push(collapse_whitespace(&text));
+    } else if let Some(level) = self.heading {
+      let spans = vec![Span::plain(&collapse_whitespace(&text))];
+      self.blocks.push(Block::Heading { level, spans });
+    } else if self.preformatted > 0 && !in_list {
+      self.blocks.push(Block::CodeBlock(text));
+    } else if self.quotes > 0 && !in_list {
+      self.blocks.push(Block::Quote(vec![Span::plain(&text)]));
+    } else {
+      let block = self.list_item(&text);
+      self.blocks.push(block);
+    }
+  }
+
+  // A paragraph, or a list item inside a list or a definition, with the
+  // item's marker if it's the first paragraph of the item
+  fn list_item(&mut self, text: &str) -> Block {
+    let depth = self.lists.len() + self.definitions;
+    if depth == 0 {
+      return Block::Paragraph(vec![Span::plain(text)]);
+    }
+    let marker = self.lists.last_mut().and_then(|list| list.pending.take());
+    // Lines after a line break keep the indentation of the item
+    let indent = "  ".repeat(depth - 1);
+    let text = text.lines().collect::<Vec<_>>().join(&format!("\n{indent}"));
+    Block::ListItem {
+      depth: depth - 1,
+      marker: marker.unwrap_or_default(),
+      spans: vec![Span::plain(&text)],
+    }
+  }
+}
+
+fn format_number(number: u32, format: &str) -> String {
+  match format {
+    "a" => letters(number),
+    "A" => letters(number).to_uppercase(),
+    "i" => roman(number).to_lowercase(),
+    "I" => roman(number),
+    _ => number.to_string(),
+  }
+}
+
+// a, b, ..., z, aa, ab, ... like browsers number `type="a"` lists
+fn letters(number: u32) -> String {
+  let mut number = number.max(1);
+  let mut letters = Vec::new();
+  while number > 0 {
+    number -= 1;
+    letters.push(char::from(b'a' + (number % 26) as u8));
+    number /= 26;
+  }
+  letters.iter().rev().collect()
+}
+
+fn roman(mut number: u32) -> String {
+  const NUMERALS: [(u32, &str); 13] = [
+    (1000, "M"),
+    (900, "CM"),
+    (500, "D"),
+    (400, "CD"),
+    (100, "C"),
+    (90, "XC"),
+    (50, "L"),
+    (40, "XL"),
+    (10, "X"),
+    (9, "IX"),
+    (5, "V"),
+    (4, "IV"),
+    (1, "I"),
+  ];
+  let mut roman = String::new();
+  for (value, numeral) in NUMERALS {
+    while number >= value {
+      roman.push_str(numeral);
+      number -= value;
+    }
+  }
+  roman
+}
+
+// Text of a paragraph with trailing spaces removed, `None` when it's empty
+fn paragraph_text(text: &str) -> Option<String> {
+  let text = text.lines().map(str::trim_end).collect::<Vec<_>>().join("\n");
+  // Keep the indentation of preformatted lines
+  let text = text.trim_matches('\n');
+  if text.trim().is_empty() { None } else { Some(text.to_string()) }
+}
+
+// Group blocks into sections starting at the top level headings
+fn split_sections(blocks: Vec<Block>) -> Vec<Section> {
+  let mut sections = Vec::new();
+  let mut current = Section::default();
+  for block in blocks {
+    if let Block::Heading { level, spans } = &block
+      && *level < SECTION_LEVELS
+    {
+      let title = spans.iter().map(|span| span.text.as_str()).collect();
+      let next =
+        Section { title: Some(title), level: *level, ..Default::default() };
+      let previous = std::mem::replace(&mut current, next);
+      if !previous.blocks.is_empty() {
+        sections.push(previous);
+      }
+    }
+    current.blocks.push(block);
+  }
+  if !current.blocks.is_empty() {
+    sections.push(current);
+  }
+
+  // Pages that start at `h2` still have top level sections
+  let top = sections
+    .iter()
+    .filter(|section| section.title.is_some())
+    .map(|section| section.level)
+    .min()
+    .unwrap_or(0);
+  for section in &mut sections {
+    section.level = section.level.saturating_sub(top);
+    for block in &mut section.blocks {
+      if let Block::Heading { level, .. } = block {
+        *level = level.saturating_sub(top);
+      }
+    }
+    section.text = blocks_text(&section.blocks);
+  }
+  sections
+}
+
+// Plain text of blocks, separated by blank lines
+fn blocks_text(blocks: &[Block]) -> String {
+  blocks.iter().map(Block::text).collect::<Vec<_>>().join("\n\n")
+}
+
+fn element_name(node: &Node) -> Option<&str> {
+  match &node.data {
+    NodeData::Element { name, .. } => Some(&name.local),
+    _ => None,
+  }
+}
+
+fn attribute(node: &Node, name: &str) -> Option<String> {
+  let NodeData::Element { attrs, .. } = &node.data else { return None };
+  attrs
+    .borrow()
+    .iter()
+    .find(|attr| &*attr.name.local == name)
+    .map(|attr| attr.value.to_string())
+}
+
+// The first element with the name below `node`, in document order
+fn find_element(node: &Node, name: &str) -> Option<Handle> {
+  node.children.borrow().iter().find_map(|child| {
+    if element_name(child) == Some(name) {
+      Some(child.clone())
+    } else {
+      find_element(child, name)
+    }
+  })
+}
+
+fn find_elements(node: &Node, name: &str, found: &mut Vec<Handle>) {
+  for child in node.children.borrow().iter() {
+    if element_name(child) == Some(name) {
+      found.push(child.clone());
+    }
+    find_elements(child, name, found);
+  }
+}
+
+fn text_content(node: &Node) -> String {
+  let mut text = String::new();
+  for child in node.children.borrow().iter() {
+    match &child.data {
+      NodeData::Text { contents } => text.push_str(&contents.borrow()),
+      _ => text.push_str(&text_content(child)),
+    }
+  }
+  text
+}
+
+fn collapse_whitespace(text: &str) -> String {
+  text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_html_error_display() {
+    let file_error = HtmlError::FileNotFound("test.html".to_string());
+    assert_eq!(format!("{file_error}"), "HTML file not found: test.html");
+
+    let invalid_error = HtmlError::InvalidHtml("Bad format".to_string());
+    assert_eq!(format!("{invalid_error}"), "Invalid HTML format: Bad format");
+  }
+
+  #[test]
+  fn test_resolve_url() {
+    let base = Some("https://example.com/docs/guide/intro.html?x=1");
+    assert_eq!(
+      resolve_url(base, "setup.html"),
+      "https://example.com/docs/guide/setup.html"
+    );
+    assert_eq!(
+      resolve_url(base, "../api/#top"),
+      "https://example.com/docs/api/#top"
+    );
+    assert_eq!(resolve_url(base, "/about"), "https://example.com/about");
+    assert_eq!(
+      resolve_url(base, "//cdn.example.com/a"),
+      "https://cdn.example.com/a"
+    );
+    assert_eq!(
+      resolve_url(base, "mailto:me@example.com"),
+      "mailto:me@example.com"
+    );
+    assert_eq!(
+      resolve_url(Some("https://example.com"), "a/b"),
+      "https://example.com/a/b"
+    );
+    assert_eq!(resolve_url(None, "setup.html"), "setup.html");
+  }
+
+  #[test]
+  fn test_list_markers() {
+    assert_eq!(format_number(3, "a"), "c");
+    assert_eq!(format_number(28, "A"), "AB");
+    assert_eq!(format_number(14, "i"), "xiv");
+    assert_eq!(format_number(7, "1"), "7");
+  }
+
+  #[test]
+  fn test_meta_charset() {
+    let page =
+      b"<html><head><meta charset=\"windows-1252\"></head><p>caf\xe9</p>";
+    assert_eq!(
+      decode(page, None),
+      "<html><head><meta charset=\"windows-1252\"></head><p>café</p>"
+    );
+    let page = b"<meta http-equiv=\"Content-Type\" content=\"text/html; charset=iso-8859-1\">\xe9";
+    assert!(decode(page, None).ends_with('é'));
+    assert_eq!(decode("é".as_bytes(), Some("utf-8")), "é");
+  }
+
+  #[test]
+  fn test_file_not_found() {
+    let result = html_to_text("definitely_nonexistent_file.html");
+    match result.unwrap_err() {
+      HtmlError::FileNotFound(path) => {
+        assert_eq!(path, "definitely_nonexistent_file.html");
+      }
+      _ => panic!("Expected FileNotFound error"),
+    }
+  }
+}
@@ -0,0 +1,39 @@
+use cli_html_to_text::HtmlMetadata;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+  let program =
+    std::env::args().next().unwrap_or_else(|| "cli-html-to-text".to_string());
+  let args: Vec<String> = std::env::args().skip(1).collect();
+
+  let metadata = args.iter().any(|arg| arg == "--metadata");
+  let file_path =
+    args.iter().find(|arg| !arg.starts_with("--")).ok_or_else(|| {
+      eprintln!("Usage: {program} [--metadata] <html_file>");
+      std::process::exit(1);
+    })?;
+
+  if metadata {
+    print_metadata(&cli_html_to_text::html_metadata(file_path)?);
+  } else {
+    println!("{}", cli_html_to_text::html_to_text(file_path)?);
+  }
+
+  Ok(())
+}
+
+fn print_metadata(metadata: &HtmlMetadata) {
+  let fields = [
+    ("Title", &metadata.title),
+    ("Author", &metadata.author),
+    ("Description", &metadata.description),
+    ("Language", &metadata.language),
+    ("Keywords", &metadata.keywords),
+    ("Published", &metadata.published),
+  ];
+
+  for (name, value) in fields {
+    if let Some(value) = value {
+      println!("{name}: {value}");
+    }
+  }
+}
@@ -0,0 +1,221 @@
+//! Saved web pages in MHTML, a MIME message holding the page and its
+//! resources, as written by the "Save as single file" of browsers
+
+/// Headers past this many bytes are not looked at to tell MHTML from HTML
+const SNIFF_LEN: usize = 16 * 1024;
+
+/// The HTML part of an archive
+pub(crate) struct Page {
+  pub html: Vec<u8>,
+  /// `charset` of the part's `Content-Type`
+  pub charset: Option<String>,
+  /// `Content-Location` of the part, the address the page was saved from
+  pub location: Option<String>,
+}
+
+/// Whether a file starts with the MIME headers of a multipart message
+/// rather than with markup
+pub(crate) fn is_mhtml(bytes: &[u8]) -> bool {
+  let (head, _) = split_message(&bytes[..bytes.len().min(SNIFF_LEN)]);
+  header(&parse_headers(head), "content-type")
+    .is_some_and(|value| value.to_ascii_lowercase().starts_with("multipart/"))
+}
+
+/// The first `text/html` part of an archive, decoded from its transfer
+/// encoding
+pub(crate) fn html_page(bytes: &[u8]) -> Option<Page> {
+  let (head, body) = split_message(bytes);
+  let headers = parse_headers(head);
+  let content_type = header(&headers, "content-type").unwrap_or_default();
+  let Some(boundary) = parameter(content_type, "boundary") else {
+    return html_part(bytes);
+  };
+
+  let delimiter = format!("--{boundary}");
+  let mut parts = split(body, delimiter.as_bytes()).skip(1);
+  parts.find_map(|part| {
+    // The delimiter ends the line it's on, "--" after it closes the message
+    if part.starts_with(b"--") {
+      return None;
+    }
+    let part = part.strip_prefix(b"\r").unwrap_or(part);
+    html_part(part.strip_prefix(b"\n").unwrap_or(part))
+  })
+}
+
+// A single part with its headers, `None` unless it's HTML
+fn html_part(part: &[u8]) -> Option<Page> {
+  let (head, body) = split_message(part);
+  let headers = parse_headers(head);
+  let content_type = header(&headers, "content-type")?;
+  let mime = content_type.split(';').next().unwrap_or_default().trim();
+  if !mime.eq_ignore_ascii_case("text/html")
+    && !mime.eq_ignore_ascii_case("application/xhtml+xml")
+  {
+    return None;
+  }
+
+  let encoding = header(&headers, "content-transfer-encoding")
+    .unwrap_or_default()
+    .to_ascii_lowercase();
+  let html = match encoding.as_str() {
+    "quoted-printable" => decode_quoted_printable(body),
+    "base64" => decode_base64(body),
+    _ => body.to_vec(),
+  };
+  Some(Page {
+    html,
+    charset: parameter(content_type, "charset"),
+    location: header(&headers, "content-location").map(str::to_string),
+  })
+}
+
+// Split a message into its headers and its body at the first blank line
+fn split_message(bytes: &[u8]) -> (&[u8], &[u8]) {
+  let crlf = find(bytes, b"\r\n\r\n").map(|at| (at, at + 4));
+  let lf = find(bytes, b"\n\n").map(|at| (at, at + 2));
+  match crlf.into_iter().chain(lf).min() {
+    Some((end, start)) => (&bytes[..end], &bytes[start..]),
+    None => (bytes, &[]),
+  }
+}
+
+// Headers as (lowercase name, value), with folded lines joined
+fn parse_headers(head: &[u8]) -> Vec<(String, String)> {
+  let mut headers: Vec<(String, String)> = Vec::new();
+  for line in String::from_utf8_lossy(head).lines() {
+    if line.starts_with([' ', '\t']) {
+      if let Some((_, value)) = headers.last_mut() {
+        value.push(' ');
+        value.push_str(line.trim());
+      }
+    } else if let Some((name, value)) = line.split_once(':') {
+      headers
+        .push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+    }
+  }
+  headers
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+  headers
+    .iter()
+    .find(|(header, _)| header == name)
+    .map(|(_, value)| value.as_str())
+}
+
+// A parameter of a header value like `multipart/related; boundary="x"`
+fn parameter(value: &str, name: &str) -> Option<String> {
+  value.split(';').skip(1).find_map(|parameter| {
+    let (key, value) = parameter.split_once('=')?;
+    key
+      .trim()
+      .eq_ignore_ascii_case(name)
+      .then(|| value.trim().trim_matches('"').to_string())
+  })
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+  haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+fn split<'a>(
+  mut bytes: &'a [u8],
+  delimiter: &'a [u8],
+) -> impl Iterator<Item = &'a [u8]> {
+  let mut done = false;
+  std::iter::from_fn(move || {
+    if done {
+      return None;
+    }
+    match find(bytes, delimiter) {
+      Some(at) => {
+        let part = &bytes[..at];
+        bytes = &bytes[at + delimiter.len()..];
+        Some(part)
+      }
+      None => {
+        done = true;
+        Some(bytes)
+      }
+    }
+  })
+}
+
+fn decode_quoted_printable(bytes: &[u8]) -> Vec<u8> {
+  let mut decoded = Vec::with_capacity(bytes.len());
+  let mut i = 0;
+  while i < bytes.len() {
+    if bytes[i] != b'=' {
+      decoded.push(bytes[i]);
+      i += 1;
+      continue;
+    }
+    // "=" at the end of a line is a soft line break
+    let rest = &bytes[i + 1..];
+    if rest.starts_with(b"\r\n") {
+      i += 3;
+    } else if rest.starts_with(b"\n") {
+      i += 2;
+    } else if let Some(byte) = rest
+      .get(..2)
+      .and_then(|hex| std::str::from_utf8(hex).ok())
+      .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+    {
+      decoded.push(byte);
+      i += 3;
+    } else {
+      decoded.push(b'=');
+      i += 1;
+    }
+  }
+  decoded
+}
+
+fn decode_base64(bytes: &[u8]) -> Vec<u8> {
+  let mut decoded = Vec::with_capacity(bytes.len() * 3 / 4);
+  let mut buffer = 0u32;
+  let mut bits = 0;
+  for &byte in bytes {
+    let value = match byte {
+      b'A'..=b'Z' => byte - b'A',
+      b'a'..=b'z' => byte - b'a' + 26,
+      b'0'..=b'9' => byte - b'0' + 52,
+      b'+' => 62,
+      b'/' => 63,
+      b'=' => break,
+      _ => continue,
+    };
+    buffer = (buffer << 6) | u32::from(value);
+    bits += 6;
+    if bits >= 8 {
+      bits -= 8;
+      decoded.push((buffer >> bits) as u8);
+    }
+  }
+  decoded
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_transfer_encodings() {
+    assert_eq!(
+      decode_quoted_printable(b"caf=C3=A9 and a soft=\r\n break, 1=3D1"),
+      "café and a soft break, 1=1".as_bytes()
+    );
+    assert_eq!(decode_base64(b"PHA+SGk8\r\nL3A+"), b"<p>Hi</p>");
+  }
+
+  #[test]
+  fn test_headers() {
+    let head = b"Content-Type: multipart/related;\r\n\ttype=\"text/html\";\r\n\tboundary=\"----Boundary\"\r\nMIME-Version: 1.0";
+    let headers = parse_headers(head);
+    let content_type = header(&headers, "content-type").unwrap();
+    assert_eq!(parameter(content_type, "boundary").unwrap(), "----Boundary");
+    assert_eq!(header(&headers, "mime-version"), Some("1.0"));
+    assert!(!is_mhtml(b"<!DOCTYPE html>\n<p>Content-Type: multipart/x</p>"));
+  }
+}
@@ -0,0 +1,309 @@
+//! Finding the article in a web page, in the spirit of Readability: menus,
+//! sidebars, footers and comments are left out, and the element holding
+//! most of the prose is taken as the article
+
+use markup5ever_rcdom::{Handle, Node, NodeData};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::{attribute, element_name, find_element};
+
+/// Elements that never hold article text
+const SKIPPED_ELEMENTS: &[&str] = &[
+  "head", "script", "style", "noscript", "template", "svg", "canvas", "iframe",
+  "object", "embed", "nav", "aside", "footer", "dialog", "button", "input",
+  "select", "textarea",
+];
+
+/// ARIA roles of page furniture
+const SKIPPED_ROLES: &[&str] =
+  &["navigation", "banner", "contentinfo", "complementary", "search", "dialog"];
+
+/// Starts of class and id words of elements that are unlikely to be part
+/// of the article
+const UNLIKELY_NAMES: &[&str] = &[
+  "advert",
+  "banner",
+  "breadcrumb",
+  "comment",
+  "cookie",
+  "disqus",
+  "footer",
+  "menu",
+  "modal",
+  "nav",
+  "newsletter",
+  "popup",
+  "promo",
+  "related",
+  "share",
+  "sharing",
+  "sidebar",
+  "social",
+  "sponsor",
+  "subscribe",
+  "toolbar",
+  "widget",
+];
+
+/// Starts of class and id words that keep an element despite an unlikely
+/// word, as in `sidebar-content`
+const LIKELY_NAMES: &[&str] =
+  &["article", "content", "entry", "main", "post", "story"];
+
+/// Elements that make up the layout of a page rather than its text
+const BLOCK_ELEMENTS: &[&str] = &[
+  "address",
+  "article",
+  "blockquote",
+  "div",
+  "dl",
+  "figure",
+  "form",
+  "h1",
+  "h2",
+  "h3",
+  "h4",
+  "h5",
+  "h6",
+  "header",
+  "main",
+  "ol",
+  "p",
+  "pre",
+  "section",
+  "table",
+  "ul",
+];
+
+/// Paragraphs with fewer letters than this don't count towards the score
+/// of their container
+const MIN_PARAGRAPH_LEN: usize = 25;
+
+/// `article` and `main` elements with fewer letters than this are taken
+/// for teasers and cards rather than the article
+const MIN_ARTICLE_LEN: usize = 250;
+
+/// Whether an element is page furniture that isn't read: scripts, menus,
+/// sidebars, footers, hidden elements and elements with class names like
+/// `share-buttons` or `comments`
+pub(crate) fn is_boilerplate(node: &Node) -> bool {
+  let Some(name) = element_name(node) else { return false };
+  if SKIPPED_ELEMENTS.contains(&name) {
+    return true;
+  }
+  // The header of a site, as opposed to the header of an article
+  if name == "header" && find_element(node, "nav").is_some() {
+    return true;
+  }
+  if attribute(node, "hidden").is_some()
+    || attribute(node, "aria-hidden").as_deref() == Some("true")
+  {
+    return true;
+  }
+  if let Some(style) = attribute(node, "style") {
+    let style: String = style
+      .to_ascii_lowercase()
+      .chars()
+      .filter(|c| !c.is_whitespace())
+      .collect();
+    if style.contains("display:none") || style.contains("visibility:hidden") {
+      return true;
+    }
+  }
+  if let Some(role) = attribute(node, "role")
+    && SKIPPED_ROLES.contains(&role.trim().to_ascii_lowercase().as_str())
+  {
+    return true;
+  }
+  if matches!(name, "html" | "body" | "article" | "main") {
+    return false;
+  }
+
+  let names = format!(
+    "{} {}",
+    attribute(node, "class").unwrap_or_default(),
+    attribute(node, "id").unwrap_or_default()
+  )
+  .to_ascii_lowercase();
+  let words: Vec<&str> = names
+    .split(|c: char| !c.is_ascii_alphanumeric())
+    .filter(|word| !word.is_empty())
+    .collect();
+  let starts_with_any = |names: &[&str]| {
+    words.iter().any(|word| names.iter().any(|n| word.starts_with(n)))
+  };
+  let unlikely = starts_with_any(UNLIKELY_NAMES)
+    || words.iter().any(|word| matches!(*word, "ad" | "ads"));
+  unlikely && !starts_with_any(LIKELY_NAMES)
+}
+
+/// The element holding the article of a page
+///
+/// An `article` or `main` element with enough text is taken as it is.
+/// Otherwise paragraphs give points to their parent and half as many to
+/// their grandparent, more for longer paragraphs with more commas, and the
+/// element with the most points, less the share of its text in links, wins.
+/// Pages without paragraphs are read from the `body`.
+pub(crate) fn article(document: &Handle) -> Handle {
+  let body = find_element(document, "body").unwrap_or_else(|| document.clone());
+  let mut found = Found::default();
+  found.collect(&body);
+
+  for candidates in [&found.articles, &found.mains] {
+    let best = candidates
+      .iter()
+      .map(|node| (node, measure(node).letters))
+      .max_by_key(|(_, letters)| *letters);
+    if let Some((node, letters)) = best
+      && letters >= MIN_ARTICLE_LEN
+    {
+      return node.clone();
+    }
+  }
+
+  // Scores in the order the candidates are found, so ties go to the first
+  let mut candidates: Vec<(Handle, f64)> = Vec::new();
+  let mut index: HashMap<*const Node, usize> = HashMap::new();
+  let mut add = |node: Handle, score: f64| {
+    let at = *index.entry(Rc::as_ptr(&node)).or_insert_with(|| {
+      candidates.push((node, 0.0));
+      candidates.len() - 1
+    });
+    candidates[at].1 += score;
+  };
+  for paragraph in &found.paragraphs {
+    let text = measure(paragraph);
+    if text.letters < MIN_PARAGRAPH_LEN {
+      continue;
+    }
+    let score = 1.0 + text.commas as f64 + (text.letters / 100).min(3) as f64;
+    if let Some(container) = parent(paragraph) {
+      if let Some(grandparent) = parent(&container) {
+        add(grandparent, score / 2.0);
+      }
+      add(container, score);
+    }
+  }
+
+  candidates
+    .into_iter()
+    .map(|(node, score)| {
+      let text = measure(&node);
+      let link_density = text.link_letters as f64 / text.letters.max(1) as f64;
+      (node, score * (1.0 - link_density))
+    })
+    .fold(None, |best: Option<(Handle, f64)>, (node, score)| match best {
+      Some(best) if best.1 >= score => Some(best),
+      _ => Some((node, score)),
+    })
+    .map_or(body, |(node, _)| node)
+}
+
+// Elements worth scoring, outside of page furniture
+#[derive(Default)]
+struct Found {
+  articles: Vec<Handle>,
+  mains: Vec<Handle>,
+  paragraphs: Vec<Handle>,
+}
+
+impl Found {
+  fn collect(&mut self, node: &Handle) {
+    for child in node.children.borrow().iter() {
+      let Some(name) = element_name(child) else { continue };
+      if is_boilerplate(child) {
+        continue;
+      }
+      match name {
+        "article" => self.articles.push(child.clone()),
+        "main" => self.mains.push(child.clone()),
+        _ if attribute(child, "role").as_deref() == Some("main") => {
+          self.mains.push(child.clone());
+        }
+        _ => {}
+      }
+      // A `div` of text and `br`s is a paragraph too
+      if matches!(name, "p" | "pre" | "blockquote" | "td")
+        || (name == "div" && !has_block_children(child))
+      {
+        self.paragraphs.push(child.clone());
+      }
+      self.collect(child);
+    }
+  }
+}
+
+fn has_block_children(node: &Node) -> bool {
+  node.children.borrow().iter().any(|child| {
+    element_name(child).is_some_and(|name| BLOCK_ELEMENTS.contains(&name))
+  })
+}
+
+#[derive(Debug, Default)]
+struct Measure {
+  // Non-whitespace characters
+  letters: usize,
+  link_letters: usize,
+  commas: usize,
+}
+
+// Count the text of an element, leaving out page furniture
+fn measure(node: &Node) -> Measure {
+  fn walk(node: &Node, in_link: bool, measure: &mut Measure) {
+    for child in node.children.borrow().iter() {
+      match &child.data {
+        NodeData::Text { contents } => {
+          let contents = contents.borrow();
+          let letters = contents.chars().filter(|c| !c.is_whitespace()).count();
+          measure.letters += letters;
+          if in_link {
+            measure.link_letters += letters;
+          }
+          measure.commas += contents.matches(',').count();
+        }
+        NodeData::Element { .. } if !is_boilerplate(child) => {
+          walk(child, in_link || element_name(child) == Some("a"), measure);
+        }
+        _ => {}
+      }
+    }
+  }
+  let mut measure = Measure::default();
+  walk(node, false, &mut measure);
+  measure
+}
+
+fn parent(node: &Node) -> Option<Handle> {
+  let weak = node.parent.take()?;
+  let parent = weak.upgrade();
+  node.parent.set(Some(weak));
+  parent
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use html5ever::tendril::TendrilSink;
+  use markup5ever_rcdom::RcDom;
+
+  fn first_element(html: &str) -> Handle {
+    let dom =
+      html5ever::parse_document(RcDom::default(), Default::default()).one(html);
+    let body = find_element(&dom.document, "body").unwrap();
+    body.children.borrow()[0].clone()
+  }
+
+  #[test]
+  fn test_boilerplate_names() {
+    let boilerplate = |html: &str| is_boilerplate(&first_element(html));
+    assert!(boilerplate(r#"<div class="share-buttons">x</div>"#));
+    assert!(boilerplate(r#"<div id="comments">x</div>"#));
+    assert!(boilerplate(r#"<div class="ad">x</div>"#));
+    assert!(boilerplate(r#"<div style="display: none">x</div>"#));
+    assert!(boilerplate(r#"<div role="navigation">x</div>"#));
+    assert!(!boilerplate(r#"<div class="sidebar-content">x</div>"#));
+    assert!(!boilerplate(r#"<div class="threads loads">x</div>"#));
+    assert!(!boilerplate(r#"<p>x</p>"#));
+  }
+}
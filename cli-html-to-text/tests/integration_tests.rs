@@ -0,0 +1,148 @@
+use cli_html_to_text::{html_metadata, html_sections, html_to_text};
+use document_converter::{Block, Span};
+use std::path::PathBuf;
+
+const ARTICLE: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <title>Field Notes | Birding Blog</title>
+  <meta property="og:title" content="Field Notes">
+  <meta name="author" content="Jane Doe">
+  <meta name="description" content="What we saw this spring.">
+  <meta property="article:published_time" content="2024-04-01T09:00:00Z">
+  <script>var tracking = "not text";</script>
+  <style>p { color: red }</style>
+</head>
+<body>
+  <header class="site-header"><a href="/">Birding Blog</a><nav><a href="/archive">Archive</a></nav></header>
+  <div class="layout">
+    <div class="share-buttons"><a href="https://social.example/share">Share</a></div>
+    <div id="story">
+      <p>Seen early in the   morning, by the <a href="/lakes/north">north lake</a>, were robins, crows and jays.</p>
+      <p>See <a href="https://example.org/guide">the guide</a> and <a href="https://example.org/guide">again</a>, or <a href="#top">jump</a>.</p>
+      <h2>Counts</h2>
+      <ol start="3"><li>Robins<ul><li>Males</li><li>Females</li></ul></li><li>Crows</li></ol>
+      <table><tr><th>Bird</th><th>Count</th></tr><tr><td>Robin</td><td>3</td></tr></table>
+      <pre>  indented
+    code</pre>
+      <p>Line one<br>line two <img src="x.png" alt="A robin"></p>
+      <p hidden>Hidden text</p>
+    </div>
+    <aside><p>Popular posts, from all over the place, and more, and more.</p></aside>
+    <div id="comments"><p>Great post, thanks, loved it, really did, yes.</p></div>
+  </div>
+  <footer><p>Copyright, all rights reserved, forever and ever.</p></footer>
+</body>
+</html>
+"##;
+
+// Write a page to the temp dir
+fn write_page(name: &str, extension: &str, content: &[u8]) -> PathBuf {
+  let path = std::env::temp_dir().join(format!(
+    "cli-html-to-text-{}-{name}.{extension}",
+    std::process::id()
+  ));
+  std::fs::write(&path, content).unwrap();
+  path
+}
+
+#[test]
+fn test_article_headings_lists_and_links() {
+  let path = write_page("article", "html", ARTICLE.as_bytes());
+  let file = path.to_str().unwrap();
+
+  let sections = html_sections(file).unwrap();
+  let titles: Vec<Option<&str>> =
+    sections.iter().map(|s| s.title.as_deref()).collect();
+  assert_eq!(titles, [Some("Field Notes"), Some("Counts"), Some("Links")]);
+  assert_eq!(
+    sections[0].text,
+    "Field Notes\n\nSeen early in the morning, by the north lake[1], were robins, crows and jays.\n\nSee the guide[2] and again[2], or jump."
+  );
+  assert_eq!(
+    sections[1].text,
+    "Counts\n\n3. Robins\n\n  • Males\n\n  • Females\n\n4. Crows\n\nBird | Count\n\nRobin | 3\n\n  indented\n    code\n\nLine one\nline two [A robin]"
+  );
+  assert_eq!(
+    sections[2].text,
+    "Links\n\n[1] /lakes/north\n\n[2] https://example.org/guide"
+  );
+
+  let metadata = html_metadata(file).unwrap();
+  assert_eq!(metadata.title.as_deref(), Some("Field Notes"));
+  assert_eq!(metadata.author.as_deref(), Some("Jane Doe"));
+  assert_eq!(metadata.language.as_deref(), Some("en"));
+  assert_eq!(metadata.published.as_deref(), Some("2024-04-01T09:00:00Z"));
+
+  std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_blocks_keep_the_structure() {
+  let page = r#"<html><body><h1>Birds</h1><blockquote><p>Quoted</p></blockquote><pre>let x = 1;</pre><table><caption>Seen</caption><thead><tr><td>Bird</td></tr></thead><tr><td>Robin</td></tr></table><p><img src="robin.png" alt="A robin"></p><ul><li>Jays</li></ul></body></html>"#;
+  let path = write_page("blocks", "html", page.as_bytes());
+
+  let sections = html_sections(path.to_str().unwrap()).unwrap();
+  assert_eq!(
+    sections[0].blocks,
+    [
+      Block::Heading { level: 0, spans: vec![Span::plain("Birds")] },
+      Block::Quote(vec![Span::plain("Quoted")]),
+      Block::CodeBlock("let x = 1;".to_string()),
+      Block::Paragraph(vec![Span::plain("Seen")]),
+      Block::Table {
+        header: true,
+        rows: vec![
+          vec![vec![Span::plain("Bird")]],
+          vec![vec![Span::plain("Robin")]]
+        ],
+      },
+      Block::Image { alt: "A robin".to_string() },
+      Block::ListItem {
+        depth: 0,
+        marker: "•".to_string(),
+        spans: vec![Span::plain("Jays")]
+      },
+    ]
+  );
+
+  std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_mhtml_archive() {
+  let page = "<html><head><title>Saved</title></head><body><article><h1>Saved page</h1><p>Caf=C3=A9 notes with a <a href=3D\"../next.html\">link</a>, a long=\r\n line and more text to read.</p></article></body></html>";
+  let archive = format!(
+    "From: <Saved by Blink>\r\nSubject: Saved\r\nMIME-Version: 1.0\r\nContent-Type: multipart/related;\r\n\ttype=\"text/html\";\r\n\tboundary=\"----MultipartBoundary--abc\"\r\n\r\n------MultipartBoundary--abc\r\nContent-Type: text/html\r\nContent-ID: <frame-1@mhtml.blink>\r\nContent-Transfer-Encoding: quoted-printable\r\nContent-Location: https://example.com/notes/saved.html\r\n\r\n{page}\r\n\r\n------MultipartBoundary--abc\r\nContent-Type: image/png\r\nContent-Transfer-Encoding: base64\r\nContent-Location: https://example.com/a.png\r\n\r\niVBORw0KGgo=\r\n\r\n------MultipartBoundary--abc--\r\n"
+  );
+  let path = write_page("archive", "mhtml", archive.as_bytes());
+
+  let text = html_to_text(path.to_str().unwrap()).unwrap();
+  assert_eq!(
+    text,
+    "Saved page\n\nCafé notes with a link[1], a long line and more text to read.\n\nLinks\n\n[1] https://example.com/next.html"
+  );
+
+  std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_legacy_charset() {
+  let page = b"<html><head><meta http-equiv=\"Content-Type\" content=\"text/html; charset=windows-1252\"></head><body><p>Caf\xe9 \x93quoted\x94</p></body></html>";
+  let path = write_page("charset", "htm", page);
+
+  let text = html_to_text(path.to_str().unwrap()).unwrap();
+  assert_eq!(text, "Café \u{201c}quoted\u{201d}");
+
+  std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_page_without_text() {
+  let path =
+    write_page("empty", "html", b"<html><body><nav>Home</nav></body></html>");
+  let result = html_to_text(path.to_str().unwrap());
+  assert!(matches!(result, Err(cli_html_to_text::HtmlError::InvalidHtml(_))));
+  std::fs::remove_file(path).unwrap();
+}
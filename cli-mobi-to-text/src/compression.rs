@@ -0,0 +1,280 @@
+//! Decompression of MOBI text records, PalmDOC LZ77 and HUFF/CDIC, and the
+//! trailing entries some books append to their records
+
+use crate::MobiError;
+
+/// Phrases nested deeper than this are taken for a corrupt dictionary
+const MAX_PHRASE_DEPTH: usize = 32;
+
+/// Decompress a record of PalmDOC's LZ77 variant
+pub(crate) fn palmdoc_decompress(data: &[u8]) -> Vec<u8> {
+  let mut output = Vec::with_capacity(data.len() * 2);
+  let mut i = 0;
+  while i < data.len() {
+    let byte = data[i];
+    i += 1;
+    match byte {
+      // A run of literal bytes
+      1..=8 => {
+        let end = (i + byte as usize).min(data.len());
+        output.extend_from_slice(&data[i..end]);
+        i = end;
+      }
+      // A distance and length to copy from the output so far
+      0x80..=0xbf => {
+        let Some(&next) = data.get(i) else { break };
+        i += 1;
+        let pair = (u16::from(byte) << 8 | u16::from(next)) & 0x3fff;
+        let distance = (pair >> 3) as usize;
+        let length = (pair & 7) as usize + 3;
+        if distance == 0 || distance > output.len() {
+          continue;
+        }
+        // The copy may overlap the bytes it adds
+        let start = output.len() - distance;
+        for at in start..start + length {
+          output.push(output[at]);
+        }
+      }
+      // A space followed by a character
+      0xc0..=0xff => {
+        output.push(b' ');
+        output.push(byte ^ 0x80);
+      }
+      _ => output.push(byte),
+    }
+  }
+  output
+}
+
+/// Decoder for records compressed with HUFF/CDIC, a Huffman code over a
+/// dictionary of phrases that may themselves be compressed
+pub(crate) struct HuffCdic {
+  /// Code length, whether it's terminal and max code for each top byte
+  cache: Vec<(u32, bool, u64)>,
+  min_codes: Vec<u64>,
+  max_codes: Vec<u64>,
+  phrases: Vec<Phrase>,
+}
+
+enum Phrase {
+  Compressed(Vec<u8>),
+  Decompressed(Vec<u8>),
+  /// Being decompressed, which it can't be part of
+  Expanding,
+}
+
+impl HuffCdic {
+  /// Read the HUFF record followed by its CDIC records
+  pub(crate) fn new(huff: &[u8], cdics: &[&[u8]]) -> Result<Self, MobiError> {
+    let invalid =
+      || MobiError::InvalidMobi("Invalid HUFF/CDIC dictionary".to_string());
+    if !huff.starts_with(b"HUFF") {
+      return Err(invalid());
+    }
+    let cache_offset = read_u32(huff, 8).ok_or_else(invalid)? as usize;
+    let base_offset = read_u32(huff, 12).ok_or_else(invalid)? as usize;
+
+    let mut cache = Vec::with_capacity(256);
+    for index in 0..256 {
+      let value =
+        read_u32(huff, cache_offset + index * 4).ok_or_else(invalid)?;
+      let length = value & 0x1f;
+      if length == 0 {
+        return Err(invalid());
+      }
+      let max_code = ((u64::from(value >> 8) + 1) << (32 - length)) - 1;
+      cache.push((length, value & 0x80 != 0, max_code));
+    }
+
+    // Indexed by code length, 0 is never used
+    let mut min_codes = vec![0];
+    let mut max_codes = vec![0];
+    for length in 1..=32u32 {
+      let at = base_offset + (length as usize - 1) * 8;
+      let min = read_u32(huff, at).ok_or_else(invalid)?;
+      let max = read_u32(huff, at + 4).ok_or_else(invalid)?;
+      min_codes.push(u64::from(min) << (32 - length));
+      max_codes.push(((u64::from(max) + 1) << (32 - length)) - 1);
+    }
+
+    let mut phrases = Vec::new();
+    for cdic in cdics {
+      if !cdic.starts_with(b"CDIC") {
+        return Err(invalid());
+      }
+      let count = read_u32(cdic, 8).ok_or_else(invalid)? as usize;
+      let bits = read_u32(cdic, 12).ok_or_else(invalid)?.min(31);
+      let in_record = (1usize << bits).min(count.saturating_sub(phrases.len()));
+      for index in 0..in_record {
+        let offset = read_u16(cdic, 16 + index * 2).ok_or_else(invalid)?;
+        let at = 16 + offset as usize;
+        let header = read_u16(cdic, at).ok_or_else(invalid)?;
+        let length = (header & 0x7fff) as usize;
+        let data = cdic.get(at + 2..at + 2 + length).ok_or_else(invalid)?;
+        phrases.push(if header & 0x8000 != 0 {
+          Phrase::Decompressed(data.to_vec())
+        } else {
+          Phrase::Compressed(data.to_vec())
+        });
+      }
+    }
+
+    Ok(HuffCdic { cache, min_codes, max_codes, phrases })
+  }
+
+  /// Decompress a text record
+  pub(crate) fn decompress(
+    &mut self,
+    data: &[u8],
+  ) -> Result<Vec<u8>, MobiError> {
+    let mut output = Vec::new();
+    self.unpack(data, 0, &mut output)?;
+    Ok(output)
+  }
+
+  fn unpack(
+    &mut self,
+    data: &[u8],
+    depth: usize,
+    output: &mut Vec<u8>,
+  ) -> Result<(), MobiError> {
+    let invalid = || {
+      MobiError::InvalidMobi("Invalid HUFF/CDIC compressed text".to_string())
+    };
+    if depth > MAX_PHRASE_DEPTH {
+      return Err(invalid());
+    }
+
+    let mut bits_left = data.len() as i64 * 8;
+    let mut bit = 0;
+    while bits_left > 0 {
+      let code = peek_u32(data, bit);
+      let (mut length, terminal, mut max_code) =
+        self.cache[(code >> 24) as usize];
+      if !terminal {
+        while length < 32 && code < self.min_codes[length as usize] {
+          length += 1;
+        }
+        max_code = self.max_codes[length as usize];
+      }
+      bit += length as usize;
+      bits_left -= i64::from(length);
+      if bits_left < 0 {
+        break;
+      }
+
+      let index = (max_code.wrapping_sub(code) >> (32 - length)) as usize;
+      let phrase = self.phrases.get_mut(index).ok_or_else(invalid)?;
+      match std::mem::replace(phrase, Phrase::Expanding) {
+        Phrase::Decompressed(text) => {
+          output.extend_from_slice(&text);
+          self.phrases[index] = Phrase::Decompressed(text);
+        }
+        Phrase::Compressed(compressed) => {
+          let mut text = Vec::new();
+          self.unpack(&compressed, depth + 1, &mut text)?;
+          output.extend_from_slice(&text);
+          self.phrases[index] = Phrase::Decompressed(text);
+        }
+        Phrase::Expanding => return Err(invalid()),
+      }
+    }
+    Ok(())
+  }
+}
+
+/// The number of bytes at the end of a text record that aren't text, as
+/// given by the extra data flags of the MOBI header
+///
+/// Each set flag above the lowest adds an entry that ends with its size,
+/// the lowest flag adds the bytes of a character continued from the next
+/// record.
+pub(crate) fn trailing_entries_len(record: &[u8], flags: u16) -> usize {
+  let mut len = 0;
+  let mut entries = flags >> 1;
+  while entries != 0 {
+    if entries & 1 != 0 {
+      len += trailing_entry_len(&record[..record.len().saturating_sub(len)]);
+    }
+    entries >>= 1;
+  }
+  if flags & 1 != 0
+    && let Some(&byte) = record.len().checked_sub(len + 1).map(|at| &record[at])
+  {
+    len += (byte & 0x3) as usize + 1;
+  }
+  len.min(record.len())
+}
+
+// The size of an entry, written backwards in 7-bit groups at its end
+fn trailing_entry_len(record: &[u8]) -> usize {
+  let mut len = 0;
+  for (group, &byte) in record.iter().rev().take(4).enumerate() {
+    len |= ((byte & 0x7f) as usize) << (7 * group);
+    if byte & 0x80 != 0 {
+      break;
+    }
+  }
+  len
+}
+
+// 32 bits of `data` starting at a bit offset, padded with zeros at the end
+fn peek_u32(data: &[u8], bit: usize) -> u64 {
+  let byte = bit / 8;
+  let mut window = 0u64;
+  for at in byte..byte + 5 {
+    window = (window << 8) | u64::from(data.get(at).copied().unwrap_or(0));
+  }
+  (window >> (8 - bit % 8)) & 0xffff_ffff
+}
+
+pub(crate) fn read_u16(bytes: &[u8], at: usize) -> Option<u16> {
+  Some(u16::from_be_bytes(bytes.get(at..at + 2)?.try_into().ok()?))
+}
+
+pub(crate) fn read_u32(bytes: &[u8], at: usize) -> Option<u32> {
+  Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_palmdoc_decompress() {
+    // Literals, a run of high bytes, a space pair and an overlapping copy
+    let compressed =
+      [b'a', b'b', 0x02, 0xc3, 0xa9, 0xe8, 0x80, 0x20 | 0x04, b'!', 0x00];
+    assert_eq!(palmdoc_decompress(&compressed), "abé hé hé !\0".as_bytes());
+  }
+
+  #[test]
+  fn test_huff_cdic_decompress() {
+    // One-bit codes, 1 for the phrase "Hi" and 0 for a compressed phrase
+    // that is eight times the first
+    let mut huff = b"HUFF\0\0\0\x18\0\0\0\x18\0\0\x04\x18".to_vec();
+    huff.resize(0x18, 0);
+    for _ in 0..256 {
+      huff.extend_from_slice(&((1u32 << 8) | 0x80 | 1).to_be_bytes());
+    }
+    huff.resize(huff.len() + 256, 0);
+    let mut cdic = b"CDIC\0\0\0\x10\0\0\0\x02\0\0\0\x01".to_vec();
+    cdic.extend_from_slice(&[0, 4, 0, 8]);
+    cdic.extend_from_slice(&[0x80, 2, b'H', b'i']);
+    cdic.extend_from_slice(&[0, 1, 0xff]);
+
+    let mut huff_cdic = HuffCdic::new(&huff, &[&cdic]).unwrap();
+    let text = huff_cdic.decompress(&[0b1011_1111]).unwrap();
+    assert_eq!(text, "Hi".repeat(15).as_bytes());
+  }
+
+  #[test]
+  fn test_trailing_entries() {
+    // Text, the second byte of a split character, then an entry of 3 bytes
+    let record = b"text\xa9\x01\x00\x00\x83";
+    assert_eq!(trailing_entries_len(record, 0b11), 5);
+    assert_eq!(trailing_entries_len(record, 0b10), 3);
+    assert_eq!(trailing_entries_len(b"text", 0), 0);
+  }
+}
@@ -0,0 +1,477 @@
+//! # CLI MOBI to Text Converter
+//!
+//! A Rust library for converting Kindle e-books without DRM, `.mobi`,
+//! `.azw` and `.azw3` files, to plain text.
+//!
+//! ## Features
+//! - Read MOBI 6 and KF8 (AZW3) books, also the combined files that hold both
+//! - Decompress PalmDOC and HUFF/CDIC compressed text
+//! - Split the text into chapters at page breaks and the files of KF8 books
+//! - Convert the HTML of the book to plain text
+//! - Keep the structure as blocks: headings, lists, tables, quotes and
+//!   pictures
+//! - Read the EXTH metadata: title, authors, publisher, ISBN and cover
+//!
+//! ## Usage
+//! ```rust
+//! use cli_mobi_to_text::mobi_to_text;
+//!
+//! match mobi_to_text("path/to/book.mobi") {
+//!     Ok(text) => println!("Extracted text: {}", text),
+//!     Err(e) => eprintln!("Error: {}", e),
+//! }
+//! ```
+
+use document_converter::Block;
+use encoding_rs::{Encoding, UTF_8, WINDOWS_1252};
+use serde::Serialize;
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+use std::path::Path;
+
+mod compression;
+
+use compression::{
+  HuffCdic, palmdoc_decompress, read_u16, read_u32, trailing_entries_len,
+};
+
+/// Version of this crate
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Length of the PalmDB header, before the list of records
+const PALMDB_HEADER_LEN: usize = 78;
+
+/// Custom error type for MOBI processing errors
+#[derive(Debug)]
+pub enum MobiError {
+  FileNotFound(String),
+  InvalidMobi(String),
+  /// The book is encrypted with DRM, which can't be removed here
+  DrmProtected,
+  HtmlConversion(String),
+}
+
+impl fmt::Display for MobiError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      MobiError::FileNotFound(path) => {
+        write!(f, "MOBI file not found: {path}")
+      }
+      MobiError::InvalidMobi(msg) => write!(f, "Invalid MOBI format: {msg}"),
+      MobiError::DrmProtected => {
+        write!(f, "MOBI book is DRM protected and can't be read")
+      }
+      MobiError::HtmlConversion(msg) => {
+        write!(f, "HTML conversion error: {msg}")
+      }
+    }
+  }
+}
+
+impl Error for MobiError {}
+
+/// A part of a MOBI book between page breaks
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chapter {
+  /// The first line of the text
+  pub title: String,
+  /// Plain text with paragraphs, list items and table rows separated by
+  /// blank lines
+  pub text: String,
+  /// The headings, paragraphs, list items, tables, quotes and pictures of
+  /// the chapter
+  pub blocks: Vec<Block>,
+}
+
+/// Bibliographic data from the EXTH header of a MOBI book
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MobiMetadata {
+  pub title: Option<String>,
+  pub authors: Vec<String>,
+  pub language: Option<String>,
+  pub publisher: Option<String>,
+  pub date: Option<String>,
+  pub description: Option<String>,
+  pub subjects: Vec<String>,
+  pub isbn: Option<String>,
+  /// The Amazon identifier of the book
+  pub asin: Option<String>,
+  pub cover: Option<Cover>,
+}
+
+/// The cover image of a MOBI book
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Cover {
+  pub media_type: String,
+  #[serde(skip)]
+  pub data: Vec<u8>,
+}
+
+/// Read the metadata of a MOBI book without converting its content
+///
+/// # Examples
+/// ```rust
+/// use cli_mobi_to_text::mobi_metadata;
+///
+/// if let Ok(metadata) = mobi_metadata("path/to/book.azw3") {
+///     println!("{}", metadata.title.unwrap_or_default());
+/// }
+/// ```
+pub fn mobi_metadata(file_path: &str) -> Result<MobiMetadata, MobiError> {
+  let book = Book::open(file_path)?;
+  let header = book.header()?;
+  let mut metadata = MobiMetadata::default();
+  let mut cover_offset = None;
+
+  for (kind, value) in &header.exth {
+    let text = || {
+      Some(header.encoding.decode(value).0.trim().to_string())
+        .filter(|text| !text.is_empty())
+    };
+    match kind {
+      100 => metadata.authors.extend(text()),
+      101 => metadata.publisher = text(),
+      103 => metadata.description = text(),
+      104 => metadata.isbn = text(),
+      105 => metadata.subjects.extend(text()),
+      106 => metadata.date = text(),
+      113 => metadata.asin = text(),
+      503 => metadata.title = text(),
+      524 => metadata.language = text(),
+      201 => cover_offset = read_u32(value, 0),
+      _ => {}
+    }
+  }
+  metadata.title = metadata.title.or(header.full_name);
+
+  metadata.cover = header
+    .first_image
+    .zip(cover_offset)
+    .and_then(|(first, offset)| book.record(first + offset as usize))
+    .and_then(|data| {
+      Some(Cover {
+        media_type: image_type(data)?.to_string(),
+        data: data.to_vec(),
+      })
+    });
+  Ok(metadata)
+}
+
+/// Convert a MOBI book to plain text
+///
+/// # Error Cases
+/// * `MobiError::FileNotFound` - The specified file doesn't exist
+/// * `MobiError::InvalidMobi` - The file is not a MOBI book, is corrupt or
+///   has no text
+/// * `MobiError::DrmProtected` - The book is encrypted
+///
+/// # Examples
+/// ```rust
+/// use cli_mobi_to_text::mobi_to_text;
+///
+/// match mobi_to_text("path/to/book.mobi") {
+///     Ok(text) => println!("Extracted text: {}", text),
+///     Err(e) => eprintln!("Error: {}", e),
+/// }
+/// ```
+pub fn mobi_to_text(file_path: &str) -> Result<String, MobiError> {
+  let chapters = mobi_chapters(file_path)?;
+  Ok(
+    chapters
+      .into_iter()
+      .map(|chapter| chapter.text)
+      .collect::<Vec<_>>()
+      .join("\n\n"),
+  )
+}
+
+/// Convert a MOBI book to plain text chapters
+///
+/// MOBI 6 books are split at their `<mbp:pagebreak>`s and KF8 books at the
+/// start of each of their HTML files. Joining the chapter texts with blank
+/// lines gives the same text as `mobi_to_text`.
+///
+/// # Examples
+/// ```rust
+/// use cli_mobi_to_text::mobi_chapters;
+///
+/// if let Ok(chapters) = mobi_chapters("path/to/book.mobi") {
+///     for chapter in chapters {
+///         println!("{}", chapter.title);
+///     }
+/// }
+/// ```
+pub fn mobi_chapters(file_path: &str) -> Result<Vec<Chapter>, MobiError> {
+  let book = Book::open(file_path)?;
+  let header = book.header()?;
+  let html = book.text(&header)?;
+
+  let mut chapters = Vec::new();
+  for (index, part) in split_parts(&html).into_iter().enumerate() {
+    let blocks = cli_html_to_text::html_blocks(part);
+    let text = blocks.iter().map(Block::text).collect::<Vec<_>>().join("\n\n");
+    let text = text.trim();
+    if text.is_empty() {
+      continue;
+    }
+    chapters.push(Chapter {
+      title: title_from_text(text, index),
+      text: text.to_string(),
+      blocks,
+    });
+  }
+
+  if chapters.is_empty() {
+    Err(MobiError::InvalidMobi("No readable content found in MOBI".to_string()))
+  } else {
+    Ok(chapters)
+  }
+}
+
+/// A PalmDB file and the byte ranges of its records
+struct Book {
+  data: Vec<u8>,
+  records: Vec<Range<usize>>,
+}
+
+/// What is needed from the PalmDOC, MOBI and EXTH headers of record 0
+///
+/// Combined MOBI 6 and KF8 files are read from their MOBI 6 part, which
+/// starts at record 0 and holds the whole text.
+struct Header {
+  compression: u16,
+  text_length: usize,
+  text_records: usize,
+  encoding: &'static Encoding,
+  full_name: Option<String>,
+  first_image: Option<usize>,
+  /// Index and count of the HUFF and CDIC records
+  huffman: (usize, usize),
+  extra_flags: u16,
+  exth: Vec<(u32, Vec<u8>)>,
+}
+
+impl Book {
+  fn open(file_path: &str) -> Result<Book, MobiError> {
+    let path = Path::new(file_path);
+    if !path.exists() {
+      return Err(MobiError::FileNotFound(file_path.to_string()));
+    }
+    let data = std::fs::read(path).map_err(|e| {
+      MobiError::InvalidMobi(format!("Failed to read MOBI: {e}"))
+    })?;
+
+    if data.get(60..68) != Some(b"BOOKMOBI") {
+      return Err(MobiError::InvalidMobi("Not a MOBI book".to_string()));
+    }
+    let truncated =
+      || MobiError::InvalidMobi("Truncated record list".to_string());
+    let count = read_u16(&data, 76).ok_or_else(truncated)? as usize;
+    let mut offsets = Vec::with_capacity(count + 1);
+    for index in 0..count {
+      let at = PALMDB_HEADER_LEN + index * 8;
+      offsets.push(read_u32(&data, at).ok_or_else(truncated)? as usize);
+    }
+    offsets.push(data.len());
+
+    let records = offsets
+      .windows(2)
+      .map(|pair| pair[0].min(data.len())..pair[1].clamp(pair[0], data.len()))
+      .collect();
+    Ok(Book { data, records })
+  }
+
+  fn record(&self, index: usize) -> Option<&[u8]> {
+    self.records.get(index).map(|range| &self.data[range.clone()])
+  }
+
+  fn header(&self) -> Result<Header, MobiError> {
+    let invalid = |msg: &str| MobiError::InvalidMobi(msg.to_string());
+    let record = self.record(0).ok_or_else(|| invalid("No records"))?;
+    let field = |at: usize| read_u32(record, at);
+
+    let compression =
+      read_u16(record, 0).ok_or_else(|| invalid("No header"))?;
+    let encryption =
+      read_u16(record, 12).ok_or_else(|| invalid("No header"))?;
+    if encryption != 0 {
+      return Err(MobiError::DrmProtected);
+    }
+    if record.get(16..20) != Some(b"MOBI") {
+      return Err(invalid("No MOBI header"));
+    }
+    let header_len = field(20).unwrap_or_default() as usize;
+    let encoding = match field(28) {
+      Some(65001) => UTF_8,
+      _ => WINDOWS_1252,
+    };
+    // Unused indexes are written as 0xffffffff
+    let index = |at: usize| {
+      field(at).filter(|&index| index != u32::MAX).map(|index| index as usize)
+    };
+
+    let full_name = field(84).zip(field(88)).and_then(|(offset, len)| {
+      let name = record.get(offset as usize..(offset + len) as usize)?;
+      Some(encoding.decode(name).0.trim().to_string())
+        .filter(|name| !name.is_empty())
+    });
+
+    let exth = if field(128).unwrap_or_default() & 0x40 != 0 {
+      read_exth(record.get(16 + header_len..).unwrap_or_default())
+    } else {
+      Vec::new()
+    };
+
+    Ok(Header {
+      compression,
+      text_length: field(4).unwrap_or_default() as usize,
+      text_records: read_u16(record, 8).unwrap_or_default() as usize,
+      encoding,
+      full_name,
+      first_image: index(108),
+      huffman: (index(112).unwrap_or_default(), index(116).unwrap_or_default()),
+      extra_flags: if header_len >= 0xe4 {
+        read_u16(record, 0xf2).unwrap_or_default()
+      } else {
+        0
+      },
+      exth,
+    })
+  }
+
+  // The HTML of the book, decompressed from its text records
+  fn text(&self, header: &Header) -> Result<String, MobiError> {
+    let mut huff_cdic = None;
+    if header.compression == 17480 {
+      let (first, count) = header.huffman;
+      let records: Vec<&[u8]> =
+        (first..first + count).filter_map(|index| self.record(index)).collect();
+      let (huff, cdics) = records.split_first().ok_or_else(|| {
+        MobiError::InvalidMobi("Missing HUFF/CDIC records".to_string())
+      })?;
+      huff_cdic = Some(HuffCdic::new(huff, cdics)?);
+    }
+
+    let mut text = Vec::with_capacity(header.text_length);
+    for index in 1..=header.text_records {
+      let Some(record) = self.record(index) else { break };
+      let record = &record
+        [..record.len() - trailing_entries_len(record, header.extra_flags)];
+      match (header.compression, &mut huff_cdic) {
+        (1, _) => text.extend_from_slice(record),
+        (2, _) => text.extend(palmdoc_decompress(record)),
+        (_, Some(huff_cdic)) => text.extend(huff_cdic.decompress(record)?),
+        (compression, None) => {
+          return Err(MobiError::InvalidMobi(format!(
+            "Unknown compression {compression}"
+          )));
+        }
+      }
+    }
+    if header.text_length > 0 {
+      text.truncate(header.text_length);
+    }
+    Ok(header.encoding.decode(&text).0.into_owned())
+  }
+}
+
+// The records of an EXTH header as (type, value)
+fn read_exth(exth: &[u8]) -> Vec<(u32, Vec<u8>)> {
+  let mut records = Vec::new();
+  if !exth.starts_with(b"EXTH") {
+    return records;
+  }
+  let count = read_u32(exth, 8).unwrap_or_default();
+  let mut at = 12;
+  for _ in 0..count {
+    let (Some(kind), Some(len)) = (read_u32(exth, at), read_u32(exth, at + 4))
+    else {
+      break;
+    };
+    let Some(value) = exth.get(at + 8..at + len as usize) else { break };
+    records.push((kind, value.to_vec()));
+    at += (len as usize).max(8);
+  }
+  records
+}
+
+// Split the HTML of a book at page breaks and where a KF8 file starts
+fn split_parts(html: &str) -> Vec<&str> {
+  let lower = html.to_ascii_lowercase();
+  let file_start = if lower.contains("<?xml") { "<?xml" } else { "<html" };
+  let mut starts: Vec<usize> = ["<mbp:pagebreak", file_start]
+    .iter()
+    .flat_map(|tag| lower.match_indices(tag).map(|(at, _)| at))
+    .filter(|&at| at > 0)
+    .collect();
+  starts.sort_unstable();
+  starts.dedup();
+
+  let mut parts = Vec::with_capacity(starts.len() + 1);
+  let mut previous = 0;
+  for start in starts {
+    parts.push(&html[previous..start]);
+    previous = start;
+  }
+  parts.push(&html[previous..]);
+  parts
+}
+
+fn image_type(data: &[u8]) -> Option<&'static str> {
+  if data.starts_with(&[0xff, 0xd8, 0xff]) {
+    Some("image/jpeg")
+  } else if data.starts_with(b"\x89PNG") {
+    Some("image/png")
+  } else if data.starts_with(b"GIF8") {
+    Some("image/gif")
+  } else {
+    None
+  }
+}
+
+fn title_from_text(text: &str, index: usize) -> String {
+  let line = text.lines().next().unwrap_or_default();
+  let title = line.trim();
+  if title.is_empty() {
+    format!("Part {}", index + 1)
+  } else {
+    title.chars().take(80).collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_mobi_error_display() {
+    let file_error = MobiError::FileNotFound("book.mobi".to_string());
+    assert_eq!(format!("{file_error}"), "MOBI file not found: book.mobi");
+    assert_eq!(
+      format!("{}", MobiError::DrmProtected),
+      "MOBI book is DRM protected and can't be read"
+    );
+  }
+
+  #[test]
+  fn test_split_parts() {
+    let html = "<html><body><p>One</p><mbp:pagebreak/><p>Two</p></body></html>";
+    assert_eq!(
+      split_parts(html),
+      ["<html><body><p>One</p>", "<mbp:pagebreak/><p>Two</p></body></html>"]
+    );
+    let html = "<?xml?><html><p>One</p></html><?xml?><html><p>Two</p></html>";
+    assert_eq!(
+      split_parts(html),
+      ["<?xml?><html><p>One</p></html>", "<?xml?><html><p>Two</p></html>"]
+    );
+  }
+
+  #[test]
+  fn test_read_exth() {
+    let exth = b"EXTH\0\0\0\x1c\0\0\0\x01\0\0\0\x64\0\0\0\x10Jane Doe\0\0\0\0";
+    assert_eq!(read_exth(exth), [(100, b"Jane Doe".to_vec())]);
+    assert!(read_exth(b"EXTH\0\0\0\x0c\0\0\0\x09").is_empty());
+  }
+}
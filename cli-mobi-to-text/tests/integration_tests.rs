@@ -0,0 +1,77 @@
+use cli_mobi_to_text::{MobiError, mobi_chapters, mobi_metadata, mobi_to_text};
+use document_converter::{Block, Span};
+
+const MOBI: &str = "../test-data/mobi/test.mobi";
+const AZW3: &str = "../test-data/mobi/test.azw3";
+
+#[test]
+fn test_mobi_chapters() {
+  let chapters = mobi_chapters(MOBI).unwrap();
+  let titles: Vec<&str> =
+    chapters.iter().map(|chapter| chapter.title.as_str()).collect();
+  assert_eq!(titles, ["Chapter 1", "Chapter 2"]);
+
+  // The "é" is split across two text records of the fixture
+  assert!(chapters[0].text.starts_with("Chapter 1\n\nThe keeper climbed"));
+  assert!(chapters[0].text.ends_with("\n\nCafé au lait — at dawn."));
+  assert_eq!(chapters[1].text, "Chapter 2\n\nThe storm came.");
+  assert_eq!(
+    chapters[1].blocks,
+    [
+      Block::Heading { level: 0, spans: vec![Span::plain("Chapter 2")] },
+      Block::Paragraph(vec![Span::plain("The storm came.")]),
+    ]
+  );
+}
+
+#[test]
+fn test_mobi_metadata() {
+  let metadata = mobi_metadata(MOBI).unwrap();
+  assert_eq!(metadata.title.as_deref(), Some("The Lighthouse"));
+  assert_eq!(metadata.authors, ["Jane Doe", "Richard Roe"]);
+  assert_eq!(metadata.language.as_deref(), Some("en"));
+  assert_eq!(metadata.publisher.as_deref(), Some("Hygg Press"));
+  assert_eq!(metadata.date.as_deref(), Some("2022-05-01"));
+  assert_eq!(metadata.isbn.as_deref(), Some("978-0-306-40615-7"));
+  assert_eq!(metadata.subjects, ["Fiction"]);
+
+  let cover = metadata.cover.unwrap();
+  assert_eq!(cover.media_type, "image/png");
+  assert!(cover.data.starts_with(b"\x89PNG"));
+}
+
+#[test]
+fn test_azw3_huff_cdic() {
+  assert_eq!(
+    mobi_to_text(AZW3).unwrap(),
+    "Chapter 1\n\nThe keeper climbed the stairs.\n\nChapter 2\n\nThe storm came, and the light held."
+  );
+  let metadata = mobi_metadata(AZW3).unwrap();
+  assert_eq!(metadata.title.as_deref(), Some("The Lighthouse (KF8)"));
+  assert_eq!(metadata.cover, None);
+}
+
+#[test]
+fn test_drm_protected() {
+  // Set the encryption type of record 0
+  let mut book = std::fs::read(MOBI).unwrap();
+  let record0 = u32::from_be_bytes(book[78..82].try_into().unwrap()) as usize;
+  book[record0 + 12..record0 + 14].copy_from_slice(&[0, 2]);
+  let path = std::env::temp_dir()
+    .join(format!("cli-mobi-to-text-{}-drm.azw", std::process::id()));
+  std::fs::write(&path, book).unwrap();
+
+  let result = mobi_to_text(path.to_str().unwrap());
+  assert!(matches!(result, Err(MobiError::DrmProtected)));
+
+  std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_not_a_mobi() {
+  let result = mobi_to_text("../test-data/sample.txt");
+  assert!(matches!(result, Err(MobiError::InvalidMobi(_))));
+
+  let result = mobi_to_text("../test-data/mobi/missing.mobi");
+  assert!(matches!(result, Err(MobiError::FileNotFound(_))));
+}
@@ -0,0 +1,987 @@
+//! # CLI ODT to Text Converter
+//!
+//! A Rust library for converting OpenDocument text files, as written by
+//! LibreOffice, to plain text without pandoc.
+//!
+//! ## Features
+//! - Extract the paragraphs of `content.xml` in document order
+//! - Split the text into sections at headings
+//! - Number and indent list items the way the list styles show them
+//! - Flatten tables to one line per row
+//! - Collect footnotes and endnotes into a section after the text
+//! - Keep the structure as blocks: headings, lists, tables, quotes, code
+//!   and pictures
+//! - Read the document properties: title, author, subject and language
+//!
+//! ## Usage
+//! ```rust
+//! use cli_odt_to_text::odt_to_text;
+//!
+//! match odt_to_text("path/to/letter.odt") {
+//!     Ok(text) => println!("Extracted text: {}", text),
+//!     Err(e) => eprintln!("Error: {}", e),
+//! }
+//! ```
+
+use document_converter::{Block, Span};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use xml::attribute::OwnedAttribute;
+use xml::reader::{EventReader, ParserConfig, XmlEvent};
+use zip::ZipArchive;
+use zip::result::ZipError;
+
+/// Version of this crate
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Headings of the first two levels start a new section, deeper ones stay in
+/// the text of their section
+const SECTION_LEVELS: usize = 2;
+
+/// Paragraph styles of quotations and of preformatted text, as LibreOffice
+/// and pandoc name them
+const QUOTE_STYLES: &[&str] = &["Quotations"];
+const CODE_STYLES: &[&str] = &["Preformatted_20_Text", "Source_20_Text"];
+
+const SVG_NAMESPACE: &str =
+  "urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0";
+
+/// Custom error type for ODT processing errors
+#[derive(Debug)]
+pub enum OdtError {
+  FileNotFound(String),
+  InvalidOdt(String),
+}
+
+impl fmt::Display for OdtError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      OdtError::FileNotFound(path) => write!(f, "ODT file not found: {path}"),
+      OdtError::InvalidOdt(msg) => write!(f, "Invalid ODT format: {msg}"),
+    }
+  }
+}
+
+impl Error for OdtError {}
+
+/// A heading and the text following it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Section {
+  /// The heading, `None` for text before the first heading
+  pub title: Option<String>,
+  /// Nesting depth, 0 for top level sections
+  pub level: usize,
+  /// Plain text starting with the heading, with paragraphs, list items and
+  /// table rows separated by blank lines
+  pub text: String,
+  /// The heading and the paragraphs, list items, tables, quotes, code and
+  /// pictures after it
+  pub blocks: Vec<Block>,
+}
+
+/// Document properties from `meta.xml`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OdtMetadata {
+  pub title: Option<String>,
+  /// The initial author, or the last one to edit the document
+  pub creator: Option<String>,
+  pub subject: Option<String>,
+  pub description: Option<String>,
+  pub language: Option<String>,
+  pub keywords: Option<String>,
+  /// Creation date as written in the document, e.g. `2024-01-01T09:00:00`
+  pub created: Option<String>,
+}
+
+/// Read the document properties of an ODT file without converting its
+/// content
+///
+/// # Examples
+/// ```rust
+/// use cli_odt_to_text::odt_metadata;
+///
+/// if let Ok(metadata) = odt_metadata("path/to/letter.odt") {
+///     println!("{}", metadata.title.unwrap_or_default());
+/// }
+/// ```
+pub fn odt_metadata(file_path: &str) -> Result<OdtMetadata, OdtError> {
+  let mut archive = open_archive(file_path)?;
+  let Some(meta) = read_part(&mut archive, "meta.xml")? else {
+    return Ok(OdtMetadata::default());
+  };
+
+  let mut metadata = OdtMetadata::default();
+  let mut initial_creator = None;
+  let mut keywords = Vec::new();
+  let mut field: Option<String> = None;
+  let mut value = String::new();
+  for event in xml_reader(&meta) {
+    let Ok(event) = event else { break };
+    match event {
+      XmlEvent::StartElement { name, .. } => {
+        field = Some(name.local_name);
+        value.clear();
+      }
+      XmlEvent::Characters(text) | XmlEvent::CData(text) => {
+        value.push_str(&text);
+      }
+      XmlEvent::EndElement { .. } => {
+        let Some(name) = field.take() else { continue };
+        let value = Some(collapse_whitespace(&value)).filter(|v| !v.is_empty());
+        match name.as_str() {
+          "title" => metadata.title = value,
+          "initial-creator" => initial_creator = value,
+          "creator" => metadata.creator = value,
+          "subject" => metadata.subject = value,
+          "description" => metadata.description = value,
+          "language" => metadata.language = value,
+          "keyword" => keywords.extend(value),
+          "creation-date" => metadata.created = value,
+          _ => {}
+        }
+      }
+      _ => {}
+    }
+  }
+  metadata.creator = initial_creator.or(metadata.creator);
+  if !keywords.is_empty() {
+    metadata.keywords = Some(keywords.join(", "));
+  }
+  Ok(metadata)
+}
+
+/// Convert an ODT file to plain text
+///
+/// # Error Cases
+/// * `OdtError::FileNotFound` - The specified file doesn't exist
+/// * `OdtError::InvalidOdt` - The file is not an OpenDocument file or has
+///   no text
+///
+/// # Examples
+/// ```rust
+/// use cli_odt_to_text::odt_to_text;
+///
+/// match odt_to_text("path/to/letter.odt") {
+///     Ok(text) => println!("Extracted text: {}", text),
+///     Err(e) => eprintln!("Error: {}", e),
+/// }
+/// ```
+pub fn odt_to_text(file_path: &str) -> Result<String, OdtError> {
+  let sections = odt_sections(file_path)?;
+  Ok(
+    sections
+      .into_iter()
+      .map(|section| section.text)
+      .collect::<Vec<_>>()
+      .join("\n\n"),
+  )
+}
+
+/// Convert an ODT file to plain text sections
+///
+/// A section starts at each heading of the first two outline levels.
+/// Footnotes and endnotes are marked `[1]`, `[2]`, ... in the text and
+/// collected in a final "Notes" section. Joining the section texts with
+/// blank lines gives the same text as `odt_to_text`.
+///
+/// # Examples
+/// ```rust
+/// use cli_odt_to_text::odt_sections;
+///
+/// if let Ok(sections) = odt_sections("path/to/letter.odt") {
+///     for section in sections {
+///         println!("{}", section.title.unwrap_or_default());
+///     }
+/// }
+/// ```
+pub fn odt_sections(file_path: &str) -> Result<Vec<Section>, OdtError> {
+  let mut archive = open_archive(file_path)?;
+  let content = read_part(&mut archive, "content.xml")?.ok_or_else(|| {
+    OdtError::InvalidOdt("Missing document content 'content.xml'".to_string())
+  })?;
+
+  // Styles used in the text are either automatic styles in `content.xml` or
+  // common styles in `styles.xml`
+  let mut styles = Styles::default();
+  if let Some(xml) = read_part(&mut archive, "styles.xml")? {
+    styles.parse(&xml);
+  }
+  styles.parse(&content);
+
+  let mut body = Body { styles, ..Default::default() };
+  body.read(&content);
+  let mut sections = split_sections(body.blocks);
+
+  let notes: Vec<Block> = body
+    .notes
+    .iter()
+    .enumerate()
+    .filter(|(_, text)| !text.is_empty())
+    .map(|(index, text)| Block::ListItem {
+      depth: 0,
+      marker: format!("[{}]", index + 1),
+      spans: vec![Span::plain(text)],
+    })
+    .collect();
+  if !notes.is_empty() {
+    let title = "Notes".to_string();
+    let heading = Block::Heading { level: 0, spans: vec![Span::plain(&title)] };
+    let blocks: Vec<Block> = std::iter::once(heading).chain(notes).collect();
+    sections.push(Section {
+      title: Some(title),
+      level: 0,
+      text: blocks_text(&blocks),
+      blocks,
+    });
+  }
+
+  if sections.is_empty() {
+    Err(OdtError::InvalidOdt("No readable content found in ODT".to_string()))
+  } else {
+    Ok(sections)
+  }
+}
+
+fn open_archive(file_path: &str) -> Result<ZipArchive<File>, OdtError> {
+  if !Path::new(file_path).exists() {
+    return Err(OdtError::FileNotFound(file_path.to_string()));
+  }
+  let file = File::open(file_path)
+    .map_err(|e| OdtError::InvalidOdt(format!("Failed to open ODT: {e}")))?;
+  ZipArchive::new(file)
+    .map_err(|e| OdtError::InvalidOdt(format!("Failed to open ODT: {e}")))
+}
+
+// Read a file of the package, `None` if the package doesn't have it
+fn read_part(
+  archive: &mut ZipArchive<File>,
+  name: &str,
+) -> Result<Option<Vec<u8>>, OdtError> {
+  let mut entry = match archive.by_name(name) {
+    Ok(entry) => entry,
+    Err(ZipError::FileNotFound) => return Ok(None),
+    Err(e) => {
+      return Err(OdtError::InvalidOdt(format!(
+        "Failed to read '{name}': {e}"
+      )));
+    }
+  };
+  let mut bytes = Vec::new();
+  entry.read_to_end(&mut bytes).map_err(|e| {
+    OdtError::InvalidOdt(format!("Failed to read '{name}': {e}"))
+  })?;
+  Ok(Some(bytes))
+}
+
+/// One level of a `text:list-style`
+#[derive(Debug, Clone, PartialEq)]
+enum ListLevel {
+  Bullet(String),
+  Number {
+    /// `1`, `a`, `A`, `i`, `I`, or empty for no number
+    format: String,
+    prefix: String,
+    suffix: String,
+    start: u32,
+    /// How many of the enclosing levels are shown, 2 gives `1.3`
+    display_levels: usize,
+  },
+}
+
+#[derive(Debug, Default)]
+struct ParagraphStyle {
+  parent: Option<String>,
+  outline_level: Option<usize>,
+  is_title: bool,
+}
+
+#[derive(Debug, Default)]
+struct Styles {
+  paragraphs: HashMap<String, ParagraphStyle>,
+  // (list style name, 1-based level) to the level definition
+  lists: HashMap<(String, usize), ListLevel>,
+}
+
+impl Styles {
+  fn parse(&mut self, xml: &[u8]) {
+    let mut list_style: Option<String> = None;
+    for event in xml_reader(xml) {
+      let Ok(event) = event else { break };
+      match event {
+        XmlEvent::StartElement { name, attributes, .. } => {
+          match name.local_name.as_str() {
+            "style"
+              if attribute(&attributes, "family").as_deref()
+                == Some("paragraph") =>
+            {
+              let Some(style_name) = attribute(&attributes, "name") else {
+                continue;
+              };
+              let display_name = attribute(&attributes, "display-name");
+              let style = ParagraphStyle {
+                parent: attribute(&attributes, "parent-style-name"),
+                outline_level: attribute(&attributes, "default-outline-level")
+                  .and_then(|v| v.parse::<usize>().ok())
+                  .filter(|&level| level >= 1)
+                  .map(|level| level - 1),
+                is_title: display_name.as_deref().unwrap_or(&style_name)
+                  == "Title",
+              };
+              self.paragraphs.insert(style_name, style);
+            }
+            "list-style" => list_style = attribute(&attributes, "name"),
+            kind @ ("list-level-style-bullet"
+            | "list-level-style-image"
+            | "list-level-style-number") => {
+              let (Some(style), Some(level)) = (
+                &list_style,
+                attribute(&attributes, "level").and_then(|v| v.parse().ok()),
+              ) else {
+                continue;
+              };
+              let definition = if kind == "list-level-style-number" {
+                ListLevel::Number {
+                  format: attribute(&attributes, "num-format")
+                    .unwrap_or_default(),
+                  prefix: attribute(&attributes, "num-prefix")
+                    .unwrap_or_default(),
+                  suffix: attribute(&attributes, "num-suffix")
+                    .unwrap_or_default(),
+                  start: attribute(&attributes, "start-value")
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(1),
+                  display_levels: attribute(&attributes, "display-levels")
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(1),
+                }
+              } else {
+                let bullet = attribute(&attributes, "bullet-char")
+                  .map(|bullet| printable_bullet(&bullet))
+                  .unwrap_or_else(|| "•".to_string());
+                ListLevel::Bullet(bullet)
+              };
+              self.lists.insert((style.clone(), level), definition);
+            }
+            _ => {}
+          }
+        }
+        XmlEvent::EndElement { name } if name.local_name == "list-style" => {
+          list_style = None;
+        }
+        _ => {}
+      }
+    }
+  }
+
+  // 0-based outline level of paragraphs in a heading or title style
+  fn heading_level(&self, style: &str) -> Option<usize> {
+    // Follow the parents a few steps, style chains are short and may loop
+    let mut name = Some(style);
+    for _ in 0..10 {
+      let style = self.paragraphs.get(name?)?;
+      if style.is_title {
+        return Some(0);
+      }
+      if let Some(level) = style.outline_level.filter(|&level| level < 10) {
+        return Some(level);
+      }
+      name = style.parent.as_deref();
+    }
+    None
+  }
+
+  // Whether the style is one of `names` or based on one of them
+  fn is_based_on(&self, style: &str, names: &[&str]) -> bool {
+    let mut name = Some(style);
+    for _ in 0..10 {
+      let Some(current) = name else { return false };
+      if names.contains(&current) {
+        return true;
+      }
+      name = self.paragraphs.get(current).and_then(|s| s.parent.as_deref());
+    }
+    false
+  }
+}
+
+// Bullets from symbol fonts are private use characters that don't show
+// without the font
+fn printable_bullet(bullet: &str) -> String {
+  match bullet.chars().next() {
+    Some(c) if !('\u{e000}'..='\u{f8ff}').contains(&c) => c.to_string(),
+    _ => "•".to_string(),
+  }
+}
+
+fn format_number(number: u32, format: &str) -> String {
+  match format {
+    "a" => letters(number),
+    "A" => letters(number).to_uppercase(),
+    "i" => roman(number).to_lowercase(),
+    "I" => roman(number),
+    "" => String::new(),
+    _ => number.to_string(),
+  }
+}
+
+// a, b, ..., z, aa, bb, ... like LibreOffice letters lists
+fn letters(number: u32) -> String {
+  let number = number.max(1) - 1;
+  let letter = char::from(b'a' + (number % 26) as u8);
+  letter.to_string().repeat(number as usize / 26 + 1)
+}
+
+fn roman(mut number: u32) -> String {
+  const NUMERALS: [(u32, &str); 13] = [
+    (1000, "M"),
+    (900, "CM"),
+    (500, "D"),
+    (400, "CD"),
+    (100, "C"),
+    (90, "XC"),
+    (50, "L"),
+    (40, "XL"),
+    (10, "X"),
+    (9, "IX"),
+    (5, "V"),
+    (4, "IV"),
+    (1, "I"),
+  ];
+  let mut roman = String::new();
+  for (value, numeral) in NUMERALS {
+    while number >= value {
+      roman.push_str(numeral);
+      number -= value;
+    }
+  }
+  roman
+}
+
+struct Paragraph {
+  text: String,
+  /// 0-based outline level of a `text:h`
+  outline_level: Option<usize>,
+  style: Option<String>,
+  // Whether the text ends in whitespace that the next whitespace collapses
+  // into, true at the start where whitespace is dropped
+  space: bool,
+  /// Descriptions of the pictures anchored in the paragraph
+  images: Vec<String>,
+}
+
+impl Paragraph {
+  fn new(outline_level: Option<usize>, style: Option<String>) -> Self {
+    Self {
+      text: String::new(),
+      outline_level,
+      style,
+      space: true,
+      images: Vec::new(),
+    }
+  }
+
+  // Add text from the XML, where runs of whitespace show as one space
+  fn push_text(&mut self, text: &str) {
+    for c in text.chars() {
+      if matches!(c, ' ' | '\t' | '\r' | '\n') {
+        if !self.space {
+          self.text.push(' ');
+          self.space = true;
+        }
+      } else {
+        self.text.push(c);
+        self.space = false;
+      }
+    }
+  }
+
+  // Add the spaces, tabs and line breaks of `text:s`, `text:tab` and
+  // `text:line-break`, whitespace in the XML after them is dropped
+  fn push_literal(&mut self, text: &str) {
+    self.text.push_str(text);
+    self.space = true;
+  }
+}
+
+struct List {
+  style: Option<String>,
+  /// Number of the current item
+  count: u32,
+  /// `text:start-value` of the current item
+  start_value: Option<u32>,
+  /// Whether the current item still needs its marker, which goes on its
+  /// first paragraph
+  pending: bool,
+}
+
+#[derive(Default)]
+struct Table {
+  header: bool,
+  rows: Vec<Vec<String>>,
+  cells: Vec<String>,
+  cell: Vec<String>,
+}
+
+// A `draw:frame`, which holds a picture or a text box
+#[derive(Default)]
+struct Frame {
+  image: bool,
+  /// Text of its `svg:title` and `svg:desc`
+  description: String,
+}
+
+// The body of `content.xml`, read into blocks
+#[derive(Default)]
+struct Body {
+  styles: Styles,
+  blocks: Vec<Block>,
+  lists: Vec<List>,
+  // Number of the last item of each (style, level) list, for lists that
+  // continue the numbering of an earlier one
+  last_counts: HashMap<(Option<String>, usize), u32>,
+  // Text of the footnotes and endnotes in the order they appear, numbered
+  // from 1 in the text
+  notes: Vec<String>,
+}
+
+impl Body {
+  fn read(&mut self, xml: &[u8]) {
+    // Paragraphs nest when a text box or a note sits inside a paragraph
+    let mut paragraphs: Vec<Paragraph> = Vec::new();
+    let mut tables: Vec<Table> = Vec::new();
+    let mut frames: Vec<Frame> = Vec::new();
+    let mut in_description = false;
+    let mut in_body = false;
+    let mut note_depth = 0;
+    // Depth inside elements whose text isn't shown: tracked deletions,
+    // comments, note labels, drawing shapes and index templates
+    let mut skip = 0;
+
+    for event in xml_reader(xml) {
+      // Keep what was read before a malformed part of the document
+      let Ok(event) = event else { break };
+      match event {
+        XmlEvent::StartElement { name, attributes, .. } => {
+          if skip > 0 {
+            skip += 1;
+            continue;
+          }
+          let local = name.local_name.as_str();
+          if local == "body" {
+            in_body = true;
+          }
+          if !in_body {
+            continue;
+          }
+          // Pictures are described by the title and description of their
+          // frame
+          if name.namespace.as_deref() == Some(SVG_NAMESPACE)
+            && matches!(local, "title" | "desc")
+            && !frames.is_empty()
+          {
+            in_description = true;
+            continue;
+          }
+          if matches!(
+            local,
+            "tracked-changes"
+              | "annotation"
+              | "sequence-decls"
+              | "note-citation"
+              | "number"
+              | "covered-table-cell"
+              | "forms"
+          ) || local.ends_with("-source")
+            || name.namespace.as_deref() == Some(SVG_NAMESPACE)
+          {
+            skip = 1;
+            continue;
+          }
+          let style = attribute(&attributes, "style-name");
+          match (local, paragraphs.last_mut()) {
+            ("p", _) => paragraphs.push(Paragraph::new(None, style)),
+            ("h", _) => {
+              let level = attribute(&attributes, "outline-level")
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(1);
+              let level = level.clamp(1, 10) - 1;
+              paragraphs.push(Paragraph::new(Some(level), style));
+            }
+            ("list", _) => self.start_list(style, &attributes),
+            ("list-item" | "list-header", _) => {
+              if let Some(list) = self.lists.last_mut() {
+                list.pending = local == "list-item";
+                list.start_value = attribute(&attributes, "start-value")
+                  .and_then(|v| v.parse().ok());
+              }
+            }
+            ("table", _) => tables.push(Table::default()),
+            ("table-header-rows", _) => {
+              if let Some(table) = tables.last_mut()
+                && table.rows.is_empty()
+              {
+                table.header = true;
+              }
+            }
+            ("frame", _) => frames.push(Frame::default()),
+            ("image", _) => {
+              if let Some(frame) = frames.last_mut() {
+                frame.image = true;
+              }
+            }
+            ("s", Some(paragraph)) => {
+              let count = attribute(&attributes, "c")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1usize);
+              paragraph.push_literal(&" ".repeat(count.min(100)));
+            }
+            ("tab", Some(paragraph)) => paragraph.push_literal("\t"),
+            ("line-break", Some(paragraph)) => paragraph.push_literal("\n"),
+            ("note", Some(paragraph)) => {
+              self.notes.push(String::new());
+              paragraph.push_literal(&format!("[{}]", self.notes.len()));
+            }
+            ("note-body", _) => note_depth += 1,
+            _ => {}
+          }
+        }
+        XmlEvent::Characters(text)
+        | XmlEvent::Whitespace(text)
+        | XmlEvent::CData(text) => {
+          if in_description {
+            if let Some(frame) = frames.last_mut() {
+              frame.description.push_str(&text);
+            }
+          } else if skip == 0
+            && let Some(paragraph) = paragraphs.last_mut()
+          {
+            paragraph.push_text(&text);
+          }
+        }
+        XmlEvent::EndElement { name } => {
+          if skip > 0 {
+            skip -= 1;
+            continue;
+          }
+          if !in_body {
+            continue;
+          }
+          match name.local_name.as_str() {
+            "title" | "desc" if in_description => in_description = false,
+            "frame" => {
+              let Some(frame) = frames.pop() else { continue };
+              if frame.image
+                && let Some(paragraph) = paragraphs.last_mut()
+              {
+                let alt = collapse_whitespace(&frame.description);
+                paragraph.images.push(alt);
+              }
+            }
+            "p" | "h" => {
+              let Some(paragraph) = paragraphs.pop() else { continue };
+              let images = paragraph
+                .images
+                .iter()
+                .map(|alt| Block::Image { alt: alt.clone() });
+              let Some(text) = paragraph_text(&paragraph.text) else {
+                if note_depth == 0 && tables.is_empty() {
+                  self.blocks.extend(images);
+                }
+                continue;
+              };
+              if note_depth > 0 {
+                if let Some(note) = self.notes.last_mut() {
+                  if !note.is_empty() {
+                    note.push(' ');
+                  }
+                  note.push_str(&collapse_whitespace(&text));
+                }
+              } else if let Some(table) = tables.last_mut() {
+                let text = self.list_item(&text).text();
+                table.cell.push(collapse_whitespace(&text));
+              } else {
+                let style = paragraph.style.as_deref();
+                let level = paragraph.outline_level.or_else(|| {
+                  style.and_then(|style| self.styles.heading_level(style))
+                });
+                let is_style = |names: &[&str]| {
+                  style
+                    .is_some_and(|style| self.styles.is_based_on(style, names))
+                };
+                let block = if let Some(level) = level {
+                  let text = collapse_whitespace(&text);
+                  Block::Heading { level, spans: vec![Span::plain(&text)] }
+                } else if self.lists.is_empty() && is_style(CODE_STYLES) {
+                  Block::CodeBlock(text)
+                } else if self.lists.is_empty() && is_style(QUOTE_STYLES) {
+                  Block::Quote(vec![Span::plain(&text)])
+                } else {
+                  self.list_item(&text)
+                };
+                self.blocks.push(block);
+                self.blocks.extend(images);
+              }
+            }
+            "list" => {
+              if let Some(list) = self.lists.pop() {
+                let key = (list.style, self.lists.len() + 1);
+                self.last_counts.insert(key, list.count);
+              }
+            }
+            "list-item" | "list-header" => {
+              if let Some(list) = self.lists.last_mut() {
+                list.pending = false;
+              }
+            }
+            "note-body" => note_depth -= 1,
+            "table-cell" => {
+              if let Some(table) = tables.last_mut() {
+                let cell = table.cell.join(" ");
+                table.cells.push(cell);
+                table.cell.clear();
+              }
+            }
+            "table-row" => {
+              if let Some(table) = tables.last_mut() {
+                let cells = std::mem::take(&mut table.cells);
+                if cells.iter().any(|cell| !cell.is_empty()) {
+                  table.rows.push(cells);
+                } else if table.rows.is_empty() {
+                  table.header = false;
+                }
+              }
+            }
+            "table" => {
+              let Some(table) = tables.pop() else { continue };
+              match tables.last_mut() {
+                // A nested table becomes part of the cell it sits in
+                Some(outer) => outer
+                  .cell
+                  .extend(table.rows.iter().map(|row| row.join(" | "))),
+                None if !table.rows.is_empty() => {
+                  self.blocks.push(Block::Table {
+                    header: table.header,
+                    rows: table
+                      .rows
+                      .iter()
+                      .map(|row| {
+                        row.iter().map(|cell| vec![Span::plain(cell)]).collect()
+                      })
+                      .collect(),
+                  });
+                }
+                None => {}
+              }
+            }
+            "body" => in_body = false,
+            _ => {}
+          }
+        }
+        _ => {}
+      }
+    }
+  }
+
+  fn start_list(
+    &mut self,
+    style: Option<String>,
+    attributes: &[OwnedAttribute],
+  ) {
+    // Nested lists without a style of their own use the levels of the
+    // enclosing list's style
+    let style = style.or_else(|| self.lists.last()?.style.clone());
+    let level = self.lists.len() + 1;
+    let continues = attribute(attributes, "continue-numbering").as_deref()
+      == Some("true")
+      || attribute(attributes, "continue-list").is_some();
+    let count = match self.last_counts.get(&(style.clone(), level)) {
+      Some(&count) if continues => count,
+      _ => match self.list_level(&style, level) {
+        Some(ListLevel::Number { start, .. }) => start.saturating_sub(1),
+        _ => 0,
+      },
+    };
+    self.lists.push(List { style, count, start_value: None, pending: false });
+  }
+
+  fn list_level(
+    &self,
+    style: &Option<String>,
+    level: usize,
+  ) -> Option<&ListLevel> {
+    self.styles.lists.get(&(style.clone()?, level))
+  }
+
+  // A paragraph, or a list item when it's inside a list, with the item's
+  // marker if it's the first paragraph of the item
+  fn list_item(&mut self, text: &str) -> Block {
+    let spans = vec![Span::plain(text)];
+    let depth = self.lists.len();
+    let Some(list) = self.lists.last_mut() else {
+      return Block::Paragraph(spans);
+    };
+    if !list.pending {
+      return Block::ListItem {
+        depth: depth - 1,
+        marker: String::new(),
+        spans,
+      };
+    }
+    list.pending = false;
+    list.count = list.start_value.take().unwrap_or(list.count + 1);
+
+    let marker = match self.list_level(&self.lists[depth - 1].style, depth) {
+      None => "•".to_string(),
+      Some(ListLevel::Bullet(bullet)) => bullet.clone(),
+      Some(ListLevel::Number {
+        format,
+        prefix,
+        suffix,
+        display_levels,
+        ..
+      }) => {
+        if format.is_empty() {
+          String::new()
+        } else {
+          let first = depth.saturating_sub(*display_levels);
+          let numbers: Vec<String> = (first..depth)
+            .map(|outer| {
+              let outer_format = match self
+                .list_level(&self.lists[depth - 1].style, outer + 1)
+              {
+                Some(ListLevel::Number { format, .. })
+                  if !format.is_empty() =>
+                {
+                  format.as_str()
+                }
+                _ => "1",
+              };
+              format_number(self.lists[outer].count, outer_format)
+            })
+            .collect();
+          format!("{prefix}{}{suffix}", numbers.join("."))
+        }
+      }
+    };
+    Block::ListItem { depth: depth - 1, marker, spans }
+  }
+}
+
+// Text of a paragraph with trailing spaces removed, `None` when it's empty
+fn paragraph_text(text: &str) -> Option<String> {
+  let text = text.lines().map(str::trim_end).collect::<Vec<_>>().join("\n");
+  // Keep the indentation of code and preformatted lines
+  let text = text.trim_matches('\n');
+  if text.trim().is_empty() { None } else { Some(text.to_string()) }
+}
+
+// Group blocks into sections starting at the top level headings
+fn split_sections(blocks: Vec<Block>) -> Vec<Section> {
+  let mut sections = Vec::new();
+  let mut current = Section::default();
+  for block in blocks {
+    if let Block::Heading { level, spans } = &block
+      && *level < SECTION_LEVELS
+    {
+      let title = spans.iter().map(|span| span.text.as_str()).collect();
+      let next =
+        Section { title: Some(title), level: *level, ..Default::default() };
+      let previous = std::mem::replace(&mut current, next);
+      if !previous.blocks.is_empty() {
+        sections.push(previous);
+      }
+    }
+    current.blocks.push(block);
+  }
+  if !current.blocks.is_empty() {
+    sections.push(current);
+  }
+
+  // Documents that start at "Heading 2" still have top level sections
+  let top = sections
+    .iter()
+    .filter(|section| section.title.is_some())
+    .map(|section| section.level)
+    .min()
+    .unwrap_or(0);
+  for section in &mut sections {
+    section.level = section.level.saturating_sub(top);
+    for block in &mut section.blocks {
+      if let Block::Heading { level, .. } = block {
+        *level = level.saturating_sub(top);
+      }
+    }
+    section.text = blocks_text(&section.blocks);
+  }
+  sections
+}
+
+// Plain text of blocks, separated by blank lines
+fn blocks_text(blocks: &[Block]) -> String {
+  blocks.iter().map(Block::text).collect::<Vec<_>>().join("\n\n")
+}
+
+fn xml_reader(bytes: &[u8]) -> EventReader<&[u8]> {
+  ParserConfig::new().create_reader(bytes)
+}
+
+fn attribute(attributes: &[OwnedAttribute], name: &str) -> Option<String> {
+  attributes
+    .iter()
+    .find(|attr| attr.name.local_name == name)
+    .map(|attr| attr.value.clone())
+}
+
+fn collapse_whitespace(text: &str) -> String {
+  text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_odt_error_display() {
+    let file_error = OdtError::FileNotFound("test.odt".to_string());
+    assert_eq!(format!("{file_error}"), "ODT file not found: test.odt");
+
+    let invalid_error = OdtError::InvalidOdt("Bad format".to_string());
+    assert_eq!(format!("{invalid_error}"), "Invalid ODT format: Bad format");
+  }
+
+  #[test]
+  fn test_list_markers() {
+    assert_eq!(format_number(3, "a"), "c");
+    assert_eq!(format_number(28, "A"), "BB");
+    assert_eq!(format_number(14, "i"), "xiv");
+    assert_eq!(format_number(7, "1"), "7");
+    assert_eq!(printable_bullet("\u{e00c}"), "•");
+    assert_eq!(printable_bullet("–"), "–");
+  }
+
+  #[test]
+  fn test_whitespace_collapses_like_odf() {
+    let mut paragraph = Paragraph::new(None, None);
+    paragraph.push_text("\n  Two ");
+    paragraph.push_literal(" ");
+    paragraph.push_text("  spaces\tand\n");
+    paragraph.push_literal("\t");
+    paragraph.push_text("tab");
+    assert_eq!(paragraph.text, "Two  spaces and \ttab");
+  }
+
+  #[test]
+  fn test_file_not_found() {
+    let result = odt_to_text("definitely_nonexistent_file.odt");
+    match result.unwrap_err() {
+      OdtError::FileNotFound(path) => {
+        assert_eq!(path, "definitely_nonexistent_file.odt");
+      }
+      _ => panic!("Expected FileNotFound error"),
+    }
+  }
+}
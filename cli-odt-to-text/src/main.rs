@@ -0,0 +1,40 @@
+use cli_odt_to_text::OdtMetadata;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+  let program =
+    std::env::args().next().unwrap_or_else(|| "cli-odt-to-text".to_string());
+  let args: Vec<String> = std::env::args().skip(1).collect();
+
+  let metadata = args.iter().any(|arg| arg == "--metadata");
+  let file_path =
+    args.iter().find(|arg| !arg.starts_with("--")).ok_or_else(|| {
+      eprintln!("Usage: {program} [--metadata] <odt_file>");
+      std::process::exit(1);
+    })?;
+
+  if metadata {
+    print_metadata(&cli_odt_to_text::odt_metadata(file_path)?);
+  } else {
+    println!("{}", cli_odt_to_text::odt_to_text(file_path)?);
+  }
+
+  Ok(())
+}
+
+fn print_metadata(metadata: &OdtMetadata) {
+  let fields = [
+    ("Title", &metadata.title),
+    ("Creator", &metadata.creator),
+    ("Subject", &metadata.subject),
+    ("Description", &metadata.description),
+    ("Language", &metadata.language),
+    ("Keywords", &metadata.keywords),
+    ("Created", &metadata.created),
+  ];
+
+  for (name, value) in fields {
+    if let Some(value) = value {
+      println!("{name}: {value}");
+    }
+  }
+}
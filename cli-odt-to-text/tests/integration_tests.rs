@@ -0,0 +1,163 @@
+use cli_odt_to_text::{odt_metadata, odt_sections, odt_to_text};
+use document_converter::{Block, Span};
+use std::io::Write;
+use std::path::PathBuf;
+use zip::write::SimpleFileOptions;
+
+const FIXTURE: &str = "../test-data/odf/test.odt";
+
+const NAMESPACES: &str = r#"xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:draw="urn:oasis:names:tc:opendocument:xmlns:drawing:1.0" xmlns:svg="urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:meta="urn:oasis:names:tc:opendocument:xmlns:meta:1.0""#;
+
+// Write an ODT with the given automatic styles and body text to the temp
+// dir
+fn write_odt(name: &str, automatic_styles: &str, text: &str) -> PathBuf {
+  let path = std::env::temp_dir()
+    .join(format!("cli-odt-to-text-{}-{name}.odt", std::process::id()));
+  let mut zip = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+  let options = SimpleFileOptions::default()
+    .compression_method(zip::CompressionMethod::Stored);
+
+  let parts = [
+    ("mimetype", "application/vnd.oasis.opendocument.text".to_string()),
+    (
+      "meta.xml",
+      format!(
+        r#"<office:document-meta {NAMESPACES}><office:meta><dc:title>Field Notes</dc:title><meta:initial-creator>Jane Doe</meta:initial-creator><dc:creator>John Roe</dc:creator><dc:language>de-DE</dc:language><meta:keyword>birds</meta:keyword><meta:keyword>spring</meta:keyword></office:meta></office:document-meta>"#
+      ),
+    ),
+    (
+      "styles.xml",
+      format!(
+        r#"<office:document-styles {NAMESPACES}><office:styles><style:style style:name="Heading_20_1" style:display-name="Heading 1" style:family="paragraph" style:default-outline-level="1"/><style:style style:name="Title" style:family="paragraph"/></office:styles></office:document-styles>"#
+      ),
+    ),
+    (
+      "content.xml",
+      format!(
+        r#"<office:document-content {NAMESPACES}><office:automatic-styles>{automatic_styles}</office:automatic-styles><office:body><office:text><text:sequence-decls><text:sequence-decl text:name="Table"/></text:sequence-decls>{text}</office:text></office:body></office:document-content>"#
+      ),
+    ),
+  ];
+  for (name, content) in parts {
+    zip.start_file(name, options).unwrap();
+    zip.write_all(content.as_bytes()).unwrap();
+  }
+  zip.finish().unwrap();
+  path
+}
+
+#[test]
+fn test_fixture_text() {
+  let text = odt_to_text(FIXTURE).unwrap();
+  assert_eq!(text, "Test123\n\nlol\n\ndoes This woRk?");
+
+  let sections = odt_sections(FIXTURE).unwrap();
+  assert_eq!(sections.len(), 1);
+  assert_eq!(sections[0].title, None);
+
+  let metadata = odt_metadata(FIXTURE).unwrap();
+  assert_eq!(metadata.title, None);
+  assert_eq!(
+    metadata.created.as_deref(),
+    Some("2025-07-01T10:27:37.939694895")
+  );
+}
+
+#[test]
+fn test_headings_lists_tables_and_notes() {
+  let styles = r#"<style:style style:name="P1" style:family="paragraph" style:parent-style-name="Title"/><text:list-style style:name="L1"><text:list-level-style-number text:level="1" style:num-format="a" style:num-suffix=")"/><text:list-level-style-number text:level="2" style:num-format="i" style:num-suffix="." text:display-levels="2"/></text:list-style><text:list-style style:name="L2"><text:list-level-style-bullet text:level="1" text:bullet-char=""/><text:list-level-style-bullet text:level="2" text:bullet-char="–"/></text:list-style>"#;
+  let item = |text: &str| {
+    format!(r#"<text:list-item><text:p>{text}</text:p></text:list-item>"#)
+  };
+  let text = [
+    r#"<text:p text:style-name="P1">Field Notes</text:p>"#.to_string(),
+    r#"<text:h text:outline-level="1">Birds</text:h>"#.to_string(),
+    r#"<text:p>Seen <text:span>early</text:span> in   the<text:s text:c="2"/>morning<text:note text:note-class="footnote"><text:note-citation>1</text:note-citation><text:note-body><text:p>Around six.</text:p></text:note-body></text:note>.<office:annotation><text:p>check this</text:p></office:annotation></text:p>"#.to_string(),
+    r#"<text:tracked-changes><text:changed-region><text:deletion><text:p>removed</text:p></text:deletion></text:changed-region></text:tracked-changes>"#.to_string(),
+    format!(
+      r#"<text:list text:style-name="L1">{}<text:list-item><text:p>Crows</text:p><text:list>{}{}</text:list></text:list-item></text:list>"#,
+      item("Robins"),
+      item("Carrion"),
+      item("Hooded"),
+    ),
+    r#"<text:p>Between the lists.</text:p>"#.to_string(),
+    format!(
+      r#"<text:list text:style-name="L1" text:continue-numbering="true">{}</text:list>"#,
+      item("Jays")
+    ),
+    format!(
+      r#"<text:list text:style-name="L2">{}<text:list-item><text:p>Ducks</text:p><text:list>{}</text:list></text:list-item></text:list>"#,
+      item("Geese"),
+      item("Mallard"),
+    ),
+    r#"<text:h text:outline-level="3">Counts</text:h>"#.to_string(),
+    r#"<table:table><table:table-header-rows><table:table-row><table:table-cell><text:p>Bird</text:p></table:table-cell><table:table-cell><text:p>Count</text:p></table:table-cell></table:table-row></table:table-header-rows><table:table-row><table:table-cell><text:p>Robin</text:p></table:table-cell><table:table-cell><text:p>3</text:p><draw:frame><svg:title>chart</svg:title></draw:frame></table:table-cell></table:table-row></table:table>"#.to_string(),
+    r#"<text:p><text:tab/>indented<text:line-break/>second line</text:p>"#.to_string(),
+  ]
+  .concat();
+  let path = write_odt("structure", styles, &text);
+  let file = path.to_str().unwrap();
+
+  let sections = odt_sections(file).unwrap();
+  let titles: Vec<Option<&str>> =
+    sections.iter().map(|s| s.title.as_deref()).collect();
+  assert_eq!(titles, [Some("Field Notes"), Some("Birds"), Some("Notes")]);
+  assert_eq!(sections[0].text, "Field Notes");
+  assert_eq!(
+    sections[1].text,
+    "Birds\n\nSeen early in the  morning[1].\n\na) Robins\n\nb) Crows\n\n  b.i. Carrion\n\n  b.ii. Hooded\n\nBetween the lists.\n\nc) Jays\n\n• Geese\n\n• Ducks\n\n  – Mallard\n\nCounts\n\nBird | Count\n\nRobin | 3\n\n\tindented\nsecond line"
+  );
+  assert_eq!(sections[2].text, "Notes\n\n[1] Around six.");
+
+  let metadata = odt_metadata(file).unwrap();
+  assert_eq!(metadata.title.as_deref(), Some("Field Notes"));
+  assert_eq!(metadata.creator.as_deref(), Some("Jane Doe"));
+  assert_eq!(metadata.language.as_deref(), Some("de-DE"));
+  assert_eq!(metadata.keywords.as_deref(), Some("birds, spring"));
+
+  std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_blocks_keep_the_structure() {
+  let styles = r#"<style:style style:name="P1" style:family="paragraph" style:parent-style-name="Quotations"/>"#;
+  let text = [
+    r#"<text:h text:outline-level="1">Birds</text:h>"#,
+    r#"<text:p text:style-name="P1">Quoted</text:p>"#,
+    r#"<text:p text:style-name="Preformatted_20_Text">let x = 1;</text:p>"#,
+    r#"<table:table><table:table-header-rows><table:table-row><table:table-cell><text:p>Bird</text:p></table:table-cell></table:table-row></table:table-header-rows><table:table-row><table:table-cell><text:p>Robin</text:p></table:table-cell></table:table-row></table:table>"#,
+    r#"<text:p><draw:frame><draw:image/><svg:title>A robin</svg:title></draw:frame></text:p>"#,
+  ]
+  .concat();
+  let path = write_odt("blocks", styles, &text);
+
+  let sections = odt_sections(path.to_str().unwrap()).unwrap();
+  assert_eq!(
+    sections[0].blocks,
+    [
+      Block::Heading { level: 0, spans: vec![Span::plain("Birds")] },
+      Block::Quote(vec![Span::plain("Quoted")]),
+      Block::CodeBlock("let x = 1;".to_string()),
+      Block::Table {
+        header: true,
+        rows: vec![
+          vec![vec![Span::plain("Bird")]],
+          vec![vec![Span::plain("Robin")]]
+        ],
+      },
+      Block::Image { alt: "A robin".to_string() },
+    ]
+  );
+  assert_eq!(
+    sections[0].text,
+    "Birds\n\nQuoted\n\nlet x = 1;\n\nBird\n\nRobin\n\n[A robin]"
+  );
+
+  std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_not_an_odt() {
+  let result = odt_to_text("../test-data/sample.txt");
+  assert!(matches!(result, Err(cli_odt_to_text::OdtError::InvalidOdt(_))));
+}
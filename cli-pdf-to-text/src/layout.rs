@@ -0,0 +1,773 @@
+use pdf_extract::{MediaBox, OutputDev, OutputError, Transform};
+use std::collections::{HashMap, HashSet};
+
+// Distances below are multiples of the font size of the text involved
+
+/// Baselines closer than this are on the same row
+const SAME_ROW: f64 = 0.5;
+/// A gap between runs on a row wider than this is a space
+const SPACE_GAP: f64 = 0.1;
+/// A gap between runs on a row wider than this may be a column gutter
+const FRAGMENT_GAP: f64 = 1.5;
+/// A line indented further than its neighbour by this starts a paragraph
+const INDENT: f64 = 0.8;
+/// Assumed width of a character, for turning indentation into spaces
+const CHAR_WIDTH: f64 = 0.5;
+/// Largest indentation written, in spaces
+const MAX_INDENT: usize = 40;
+
+/// Line spacing wider than this multiple of the usual spacing ends a
+/// paragraph
+const PARAGRAPH_GAP: f64 = 1.4;
+/// A line ending this fraction of the block width before the right edge is
+/// the last line of its paragraph
+const SHORT_LINE: f64 = 0.25;
+
+/// Rows at the top and bottom of a page checked for headers and footers
+const FURNITURE_ROWS: usize = 2;
+/// Fewest pages a header or footer must repeat on to be left out, it must
+/// also repeat on half of the pages
+const FURNITURE_REPEATS: usize = 3;
+/// Distance from the body a header or footer with changing text keeps
+const FURNITURE_GAP: f64 = 2.5;
+/// Most digits in a page number
+const MAX_PAGE_DIGITS: usize = 4;
+
+/// A column needs at least this many lines
+const MIN_COLUMN_LINES: usize = 3;
+/// A column is at least this fraction of the width being split
+const MIN_COLUMN_WIDTH: f64 = 0.3;
+/// Fraction of lines allowed to cross a gutter, e.g. full width headings
+const MAX_GUTTER_CROSSINGS: f64 = 0.1;
+/// Columns are searched for within columns this many times
+const MAX_COLUMN_DEPTH: usize = 2;
+
+/// Text drawn by one text showing operator, in points from the top left of
+/// the page
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Run {
+  pub x0: f64,
+  pub x1: f64,
+  /// Baseline
+  pub y: f64,
+  pub size: f64,
+  pub text: String,
+}
+
+/// Collects the text runs of a page with their positions
+#[derive(Default)]
+pub(crate) struct RunCollector {
+  page_top: f64,
+  current: Option<Run>,
+  pub runs: Vec<Run>,
+}
+
+impl RunCollector {
+  fn finish_run(&mut self) {
+    if let Some(run) = self.current.take()
+      && !run.text.trim().is_empty()
+    {
+      self.runs.push(run);
+    }
+  }
+}
+
+impl OutputDev for RunCollector {
+  fn begin_page(
+    &mut self,
+    _page_num: u32,
+    media_box: &MediaBox,
+    _art_box: Option<(f64, f64, f64, f64)>,
+  ) -> Result<(), OutputError> {
+    self.page_top = media_box.ury;
+    Ok(())
+  }
+
+  fn end_page(&mut self) -> Result<(), OutputError> {
+    self.finish_run();
+    Ok(())
+  }
+
+  fn output_character(
+    &mut self,
+    trm: &Transform,
+    width: f64,
+    _spacing: f64,
+    font_size: f64,
+    char: &str,
+  ) -> Result<(), OutputError> {
+    let scale = (trm.m11 * trm.m22 - trm.m12 * trm.m21).abs().sqrt();
+    let size = font_size * scale;
+    if !size.is_finite() || size <= 0.0 {
+      return Ok(());
+    }
+
+    let (x, y) = (trm.m31, self.page_top - trm.m32);
+    let x1 = x + width * size;
+    match &mut self.current {
+      Some(run) if (y - run.y).abs() <= size * SAME_ROW => {
+        run.text.push_str(char);
+        run.x1 = run.x1.max(x1);
+      }
+      _ => {
+        self.finish_run();
+        self.current = Some(Run { x0: x, x1, y, size, text: char.to_string() });
+      }
+    }
+    Ok(())
+  }
+
+  fn begin_word(&mut self) -> Result<(), OutputError> {
+    self.finish_run();
+    Ok(())
+  }
+
+  fn end_word(&mut self) -> Result<(), OutputError> {
+    self.finish_run();
+    Ok(())
+  }
+
+  fn end_line(&mut self) -> Result<(), OutputError> {
+    Ok(())
+  }
+}
+
+/// A row of text, or the part of a row on one side of a wide gap
+#[derive(Debug, Clone, PartialEq)]
+struct Line {
+  /// Index of the row on the page, counted from the top
+  row: usize,
+  x0: f64,
+  x1: f64,
+  y: f64,
+  size: f64,
+  text: String,
+}
+
+/// Lay out the runs of each page as text
+///
+/// Columns are read one after another, running headers, footers and page
+/// numbers are left out, and each paragraph becomes one line, indented by
+/// its indentation on the page. Paragraphs are separated by blank lines.
+pub(crate) fn layout_pages(pages: &[Vec<Run>]) -> Vec<String> {
+  let mut pages: Vec<Vec<Line>> =
+    pages.iter().map(|runs| page_lines(runs)).collect();
+
+  let furniture = furniture_rows(&pages);
+  for (lines, rows) in pages.iter_mut().zip(&furniture) {
+    lines.retain(|line| !rows.contains(&line.row));
+  }
+
+  pages.into_iter().map(page_text).collect()
+}
+
+// Group runs into rows by baseline, then split rows at wide gaps
+fn page_lines(runs: &[Run]) -> Vec<Line> {
+  let mut runs: Vec<&Run> = runs.iter().collect();
+  runs.sort_by(|a, b| a.y.total_cmp(&b.y).then(a.x0.total_cmp(&b.x0)));
+
+  let mut rows: Vec<Vec<&Run>> = Vec::new();
+  for run in runs {
+    match rows.last_mut() {
+      Some(row)
+        if (run.y - row[0].y).abs() <= run.size.min(row[0].size) * SAME_ROW =>
+      {
+        row.push(run)
+      }
+      _ => rows.push(vec![run]),
+    }
+  }
+
+  let mut lines = Vec::new();
+  for (row_index, mut row) in rows.into_iter().enumerate() {
+    row.sort_by(|a, b| a.x0.total_cmp(&b.x0));
+
+    let mut fragment: Vec<&Run> = Vec::new();
+    for run in row {
+      if let Some(last) = fragment.last()
+        && run.x0 - last.x1 > FRAGMENT_GAP * run.size.max(last.size)
+      {
+        lines.push(join_runs(row_index, &fragment));
+        fragment.clear();
+      }
+      fragment.push(run);
+    }
+    if !fragment.is_empty() {
+      lines.push(join_runs(row_index, &fragment));
+    }
+  }
+  lines
+}
+
+fn join_runs(row: usize, runs: &[&Run]) -> Line {
+  let mut text = String::new();
+  let mut last_x1: Option<f64> = None;
+  for run in runs {
+    if let Some(last_x1) = last_x1
+      && run.x0 - last_x1 > SPACE_GAP * run.size
+    {
+      text.push(' ');
+    }
+    text.push_str(&run.text);
+    last_x1 = Some(last_x1.map_or(run.x1, |x1: f64| x1.max(run.x1)));
+  }
+
+  // The size most of the text is set in, so superscripts don't count
+  let size = runs
+    .iter()
+    .max_by_key(|run| run.text.chars().count())
+    .map_or(0.0, |run| run.size);
+
+  Line {
+    row,
+    x0: runs.first().map_or(0.0, |run| run.x0),
+    x1: last_x1.unwrap_or(0.0),
+    y: runs.first().map_or(0.0, |run| run.y),
+    size,
+    text: text.split_whitespace().collect::<Vec<_>>().join(" "),
+  }
+}
+
+// Rows near the top or bottom of a page that repeat at the same height on
+// most pages, such as running headers and footers, or that hold page numbers
+// following the page sequence
+fn furniture_rows(pages: &[Vec<Line>]) -> Vec<HashSet<usize>> {
+  // (top or bottom, height, text) of a candidate row
+  type Key = (bool, i64, String);
+  // (top or bottom, height, printed number less the page index)
+  type Numbering = (bool, i64, i64);
+  type Candidate = (usize, Vec<Key>, Option<Numbering>);
+
+  let candidates: Vec<Vec<Candidate>> = pages
+    .iter()
+    .enumerate()
+    .map(|(page, lines)| {
+      let mut rows: Vec<(usize, f64, f64, String)> = Vec::new();
+      for line in lines {
+        match rows.last_mut() {
+          Some((row, _, _, text)) if *row == line.row => {
+            text.push(' ');
+            text.push_str(&line.text);
+          }
+          _ => rows.push((line.row, line.y, line.size, line.text.clone())),
+        }
+      }
+
+      let count = rows.len();
+      (0..count)
+        .filter(|&i| i < FURNITURE_ROWS || i + FURNITURE_ROWS >= count)
+        .map(|i| {
+          let (row, y, size, text) = &rows[i];
+          let top = i < FURNITURE_ROWS;
+          let height = (y / 4.0).round() as i64;
+          let mut keys = vec![(top, height, mask(text))];
+
+          // Headers naming the current section change from page to page,
+          // so the outermost row also matches on its first word when it
+          // stands apart from the body
+          let neighbour = if top {
+            rows.get(i + 1)
+          } else {
+            i.checked_sub(1).map(|i| &rows[i])
+          };
+          // Page numbers may sit further out than a header
+          let further = if top { &rows[..i] } else { &rows[i + 1..] };
+          let outermost =
+            further.iter().all(|(_, _, _, text)| page_number(text).is_some());
+          let apart = neighbour.is_none_or(|(_, other, _, _)| {
+            (other - y).abs() > size * FURNITURE_GAP
+          });
+          if outermost
+            && apart
+            && let Some(word) = text.split_whitespace().next()
+          {
+            keys.push((top, height, format!("{} …", mask(word))));
+          }
+
+          let numbering = page_number(text)
+            .map(|number| (top, height, i64::from(number) - page as i64));
+          (*row, keys, numbering)
+        })
+        .collect()
+    })
+    .collect();
+
+  let mut repeats: HashMap<&Key, HashSet<usize>> = HashMap::new();
+  let mut numbered: HashMap<Numbering, usize> = HashMap::new();
+  for (page, rows) in candidates.iter().enumerate() {
+    for (_, keys, numbering) in rows {
+      for key in keys {
+        repeats.entry(key).or_default().insert(page);
+      }
+      if let Some(numbering) = numbering {
+        *numbered.entry(*numbering).or_default() += 1;
+      }
+    }
+  }
+
+  let most = FURNITURE_REPEATS.max(pages.len() / 2);
+  candidates
+    .iter()
+    .map(|rows| {
+      rows
+        .iter()
+        .filter(|(_, keys, numbering)| {
+          numbering.is_some_and(|numbering| numbered[&numbering] > 1)
+            || keys.iter().any(|key| repeats[key].len() >= most)
+        })
+        .map(|(row, _, _)| *row)
+        .collect()
+    })
+    .collect()
+}
+
+// Lowercase with digits masked, so "Page 7" and "Page 8" compare equal
+fn mask(text: &str) -> String {
+  text
+    .chars()
+    .map(|c| if c.is_ascii_digit() { '#' } else { c })
+    .collect::<String>()
+    .to_lowercase()
+}
+
+// The number of "7", "- 7 -", "vii", "Page 7" or "7 of 20"
+fn page_number(text: &str) -> Option<u32> {
+  let text = text
+    .trim_matches(|c: char| c.is_whitespace() || matches!(c, '-' | '–' | '|'))
+    .to_lowercase();
+  let text = text.strip_prefix("page ").unwrap_or(&text);
+
+  let number = |part: &str| {
+    let part = part.trim();
+    if !part.is_empty()
+      && part.len() <= MAX_PAGE_DIGITS
+      && part.chars().all(|c| c.is_ascii_digit())
+    {
+      part.parse().ok()
+    } else {
+      roman_number(part)
+    }
+  };
+
+  match text.split_once(" of ") {
+    Some((page, total)) => number(total).and(number(page)),
+    None => number(text),
+  }
+}
+
+// The value of a lowercase roman numeral below 400, as front matter is
+// numbered, written the usual way so words such as "ill" or "civil" aren't
+// taken for one
+fn roman_number(text: &str) -> Option<u32> {
+  const NUMERALS: [(&str, u32); 9] = [
+    ("c", 100),
+    ("xc", 90),
+    ("l", 50),
+    ("xl", 40),
+    ("x", 10),
+    ("ix", 9),
+    ("v", 5),
+    ("iv", 4),
+    ("i", 1),
+  ];
+
+  let mut rest = text;
+  let mut value = 0;
+  for (numeral, worth) in NUMERALS {
+    // Only c, x and i repeat, up to three times
+    let times = if matches!(numeral, "c" | "x" | "i") { 3 } else { 1 };
+    for _ in 0..times {
+      match rest.strip_prefix(numeral) {
+        Some(after) => {
+          rest = after;
+          value += worth;
+        }
+        None => break,
+      }
+    }
+  }
+  (rest.is_empty() && value > 0).then_some(value)
+}
+
+fn page_text(lines: Vec<Line>) -> String {
+  let mut paragraphs = Vec::new();
+  for block in reading_order(lines) {
+    paragraphs.extend(block_paragraphs(&block));
+  }
+  paragraphs.join("\n\n")
+}
+
+// Split the page into blocks of lines read top to bottom: column by column
+// between lines that cross the gutters, e.g. full width headings
+fn reading_order(mut lines: Vec<Line>) -> Vec<Vec<Line>> {
+  lines.sort_by(|a, b| a.y.total_cmp(&b.y).then(a.x0.total_cmp(&b.x0)));
+
+  let (Some(left), Some(right)) = (
+    lines.iter().map(|line| line.x0).min_by(f64::total_cmp),
+    lines.iter().map(|line| line.x1).max_by(f64::total_cmp),
+  ) else {
+    return Vec::new();
+  };
+
+  let mut gutters = find_gutters(&lines, left, right, 0);
+  if gutters.is_empty() {
+    return vec![lines];
+  }
+  gutters.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+  let mut blocks = Vec::new();
+  let mut columns: Vec<Vec<Line>> = vec![Vec::new(); gutters.len() + 1];
+  let mut spanning: Vec<Line> = Vec::new();
+
+  for line in lines {
+    let crosses =
+      gutters.iter().any(|&(start, end)| line.x0 < end && line.x1 > start);
+    if crosses {
+      for column in &mut columns {
+        if !column.is_empty() {
+          blocks.push(std::mem::take(column));
+        }
+      }
+      spanning.push(line);
+    } else {
+      if !spanning.is_empty() {
+        blocks.push(std::mem::take(&mut spanning));
+      }
+      let column = gutters.iter().filter(|&&(_, end)| end <= line.x0).count();
+      columns[column].push(line);
+    }
+  }
+
+  blocks.extend(columns.into_iter().filter(|column| !column.is_empty()));
+  if !spanning.is_empty() {
+    blocks.push(spanning);
+  }
+  blocks
+}
+
+// Find vertical strips between `left` and `right` that hardly any line
+// crosses and that have a column of text on both sides
+fn find_gutters(
+  lines: &[Line],
+  left: f64,
+  right: f64,
+  depth: usize,
+) -> Vec<(f64, f64)> {
+  let inside: Vec<&Line> =
+    lines.iter().filter(|line| line.x0 >= left && line.x1 <= right).collect();
+  if depth > MAX_COLUMN_DEPTH || inside.len() < MIN_COLUMN_LINES * 2 {
+    return Vec::new();
+  }
+
+  // How many lines cover each point across the width
+  let buckets = (right - left).ceil().max(1.0) as usize;
+  let mut coverage = vec![0usize; buckets];
+  for line in &inside {
+    let start = ((line.x0 - left).floor() as usize).min(buckets - 1);
+    let end = ((line.x1 - left).ceil() as usize).min(buckets);
+    for count in &mut coverage[start..end] {
+      *count += 1;
+    }
+  }
+
+  let max_crossings =
+    (inside.len() as f64 * MAX_GUTTER_CROSSINGS).ceil() as usize;
+  let mut sizes: Vec<f64> = inside.iter().map(|line| line.size).collect();
+  sizes.sort_by(f64::total_cmp);
+  let min_gap = sizes[sizes.len() / 2];
+
+  let mut best: Option<(f64, f64)> = None;
+  let mut i = 0;
+  while i < buckets {
+    if coverage[i] > max_crossings {
+      i += 1;
+      continue;
+    }
+    let start = i;
+    while i < buckets && coverage[i] <= max_crossings {
+      i += 1;
+    }
+    // Strips reaching the edge are margins or ragged line ends
+    if start == 0 || i == buckets {
+      continue;
+    }
+
+    let gutter = (left + start as f64, left + i as f64);
+    let wider = best.is_none_or(|(s, e)| gutter.1 - gutter.0 > e - s);
+    if gutter.1 - gutter.0 >= min_gap
+      && wider
+      && is_column(&inside, left, gutter.0, right - left)
+      && is_column(&inside, gutter.1, right, right - left)
+    {
+      best = Some(gutter);
+    }
+  }
+
+  let Some(gutter) = best else {
+    return Vec::new();
+  };
+  let mut gutters = vec![gutter];
+  gutters.extend(find_gutters(lines, left, gutter.0, depth + 1));
+  gutters.extend(find_gutters(lines, gutter.1, right, depth + 1));
+  gutters
+}
+
+// Whether the lines between `left` and `right` look like a column of text
+// rather than, say, a narrow table column
+fn is_column(lines: &[&Line], left: f64, right: f64, total_width: f64) -> bool {
+  let mut widths: Vec<f64> = lines
+    .iter()
+    .filter(|line| line.x0 >= left && line.x1 <= right)
+    .map(|line| line.x1 - line.x0)
+    .collect();
+  if widths.len() < MIN_COLUMN_LINES {
+    return false;
+  }
+  widths.sort_by(f64::total_cmp);
+  let width = right - left;
+  width >= total_width * MIN_COLUMN_WIDTH
+    && widths[widths.len() / 2] >= width * 0.5
+}
+
+// Join the lines of a block into paragraphs
+fn block_paragraphs(block: &[Line]) -> Vec<String> {
+  let Some(first) = block.first() else {
+    return Vec::new();
+  };
+  let left = block.iter().map(|line| line.x0).fold(first.x0, f64::min);
+  let right = block.iter().map(|line| line.x1).fold(first.x1, f64::max);
+  let width = right - left;
+
+  // Indentation is measured from the edge most lines start at, so headings
+  // set out in the margin don't indent the body
+  let mut edges: HashMap<i64, usize> = HashMap::new();
+  for line in block {
+    *edges.entry(line.x0.round() as i64).or_default() += 1;
+  }
+  let margin = edges
+    .into_iter()
+    .max_by_key(|&(edge, count)| (count, -edge))
+    .map_or(left, |(edge, _)| edge as f64);
+
+  // Usual distance between consecutive rows
+  let mut sizes: Vec<f64> = block.iter().map(|line| line.size).collect();
+  sizes.sort_by(f64::total_cmp);
+  let size = sizes[sizes.len() / 2];
+  let mut gaps: Vec<f64> = block
+    .windows(2)
+    .map(|pair| pair[1].y - pair[0].y)
+    .filter(|&gap| gap > size * SAME_ROW)
+    .collect();
+  gaps.sort_by(f64::total_cmp);
+  let spacing = gaps.get(gaps.len() / 2).copied();
+
+  let indent = |line: &Line| line.x0 - left;
+  let mut paragraphs = Vec::new();
+  let mut current: Vec<&Line> = vec![first];
+
+  for pair in block.windows(2) {
+    let (a, b) = (&pair[0], &pair[1]);
+    let size = a.size.max(b.size);
+    let gap = b.y - a.y;
+
+    let breaks = if gap <= size * SAME_ROW {
+      // Further along the same row
+      false
+    } else {
+      let wide_gap = gap > spacing.unwrap_or(size * 1.2) * PARAGRAPH_GAP;
+      let new_size = (a.size - b.size).abs() > size * 0.15;
+      let short_line = a.x1 < right - width * SHORT_LINE;
+      // A full line followed by indented ones is a hanging indent, as in
+      // lists
+      let indented = indent(b) > indent(a) + size * INDENT
+        && (current.len() > 1 || short_line);
+      let outdented =
+        indent(b) < indent(a) - size * INDENT && current.len() > 1;
+      let short = short_line
+        && (a.text.ends_with(['.', ':', '!', '?'])
+          || b
+            .text
+            .starts_with(|c: char| c.is_uppercase() || c.is_ascii_digit()));
+      wide_gap || new_size || indented || outdented || short
+    };
+
+    if breaks {
+      paragraphs.push(paragraph_text(&current, margin));
+      current.clear();
+    }
+    current.push(b);
+  }
+  paragraphs.push(paragraph_text(&current, margin));
+  paragraphs
+}
+
+fn paragraph_text(lines: &[&Line], margin: f64) -> String {
+  let indent = lines
+    .iter()
+    .map(|line| {
+      ((line.x0 - margin).max(0.0) / (line.size * CHAR_WIDTH)).round() as usize
+    })
+    .min()
+    .unwrap_or(0)
+    .min(MAX_INDENT);
+
+  let mut text = " ".repeat(indent);
+  for (i, line) in lines.iter().enumerate() {
+    if i > 0 {
+      // Rejoin words hyphenated across lines, "hyph-" "enated" and
+      // "SYS-" "TEMS", keeping the hyphen of "Post-" "Script"
+      let before = text.strip_suffix('-').and_then(|text| text.chars().last());
+      let after = line.text.chars().next();
+      if let (Some(before), Some(after)) = (before, after)
+        && before.is_alphabetic()
+        && after.is_alphabetic()
+      {
+        if before.is_lowercase() == after.is_lowercase() {
+          text.pop();
+        }
+      } else if text.ends_with('\u{ad}') {
+        text.pop();
+      } else {
+        text.push(' ');
+      }
+    }
+    text.push_str(&line.text);
+  }
+  text
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn run(x0: f64, y: f64, text: &str) -> Run {
+    // 10pt text, 5pt per character
+    Run {
+      x0,
+      x1: x0 + text.len() as f64 * 5.0,
+      y,
+      size: 10.0,
+      text: text.to_string(),
+    }
+  }
+
+  #[test]
+  fn test_paragraphs_and_hyphenation() {
+    let runs = vec![
+      run(50.0, 100.0, "The first paragraph wraps onto a"),
+      run(50.0, 112.0, "second line, and this one ends with a hyph-"),
+      run(50.0, 124.0, "enated word."),
+      run(70.0, 136.0, "An indented line starts the next paragraph"),
+      run(50.0, 148.0, "which continues here."),
+      run(50.0, 160.0, "• A list item long enough to fill the line wraps"),
+      run(62.0, 172.0, "under its first word."),
+    ];
+
+    let text = &layout_pages(&[runs])[0];
+    assert_eq!(
+      text,
+      "The first paragraph wraps onto a second line, and this one ends with \
+       a hyphenated word.\n\nAn indented line starts the next paragraph \
+       which continues here.\n\n• A list item long enough to fill the \
+       line wraps under its first word."
+    );
+  }
+
+  #[test]
+  fn test_columns_are_read_in_order() {
+    let mut runs = vec![run(
+      50.0,
+      80.0,
+      "A heading that spans both of the columns on the page",
+    )];
+    for i in 0..4 {
+      let y = 100.0 + i as f64 * 12.0;
+      runs.push(run(50.0, y, &format!("left column line {i} with text")));
+      runs.push(run(250.0, y, &format!("right column line {i} with text")));
+    }
+
+    let text = &layout_pages(&[runs])[0];
+    let heading = text.find("A heading").unwrap();
+    let left = text.find("left column line 3").unwrap();
+    let right = text.find("right column line 0").unwrap();
+    assert!(heading < left && left < right, "{text}");
+  }
+
+  #[test]
+  fn test_running_headers_and_page_numbers_are_removed() {
+    let topics = ["Objects", "Filters", "Encryption", "Functions"];
+    let pages: Vec<Vec<Run>> = topics
+      .iter()
+      .enumerate()
+      .map(|(i, topic)| {
+        vec![
+          run(50.0, 45.0, &format!("Section 3.{} {topic}", i + 1)),
+          run(50.0, 100.0, &format!("{topic} are described here.")),
+          run(150.0, 780.0, &(i + 1).to_string()),
+        ]
+      })
+      .collect();
+
+    let texts = layout_pages(&pages);
+    assert_eq!(texts[0], "Objects are described here.");
+    assert_eq!(texts[3], "Functions are described here.");
+  }
+
+  #[test]
+  fn test_body_text_at_the_page_edges_is_kept() {
+    // A table total and a one word line end the second page, and "ill" ends
+    // the third, none follow the page numbers
+    let topics = ["Objects", "Filters", "Encryption", "Functions"];
+    let endings = ["Summary", "42", "ill", "Summary"];
+    let pages: Vec<Vec<Run>> = topics
+      .iter()
+      .zip(endings)
+      .enumerate()
+      .map(|(i, (topic, ending))| {
+        vec![
+          run(50.0, 100.0, &format!("{topic} are described here.")),
+          run(50.0, 112.0, ending),
+          run(150.0, 780.0, &format!("- {} -", i + 7)),
+        ]
+      })
+      .collect();
+
+    let texts = layout_pages(&pages);
+    assert_eq!(texts[1], "Filters are described here. 42");
+    assert_eq!(texts[2], "Encryption are described here. ill");
+    assert_eq!(texts[3], "Functions are described here. Summary");
+  }
+
+  #[test]
+  fn test_numbers_out_of_sequence_are_kept() {
+    let topics = ["Objects", "Filters", "Encryption"];
+    let pages: Vec<Vec<Run>> = topics
+      .iter()
+      .zip(["3", "12", "5"])
+      .map(|(topic, number)| {
+        vec![
+          run(50.0, 100.0, &format!("{topic} are described here.")),
+          run(150.0, 780.0, number),
+        ]
+      })
+      .collect();
+
+    let texts = layout_pages(&pages);
+    // 3 and 5 number the first and third pages, 12 is the second page's text
+    assert_eq!(texts[0], "Objects are described here.");
+    assert_eq!(texts[1], "Filters are described here. 12");
+    assert_eq!(texts[2], "Encryption are described here.");
+  }
+
+  #[test]
+  fn test_page_number() {
+    let numbers =
+      [("7", 7), ("- 12 -", 12), ("xiv", 14), ("Page 3", 3), ("3 of 20", 3)];
+    for (text, number) in numbers {
+      assert_eq!(page_number(text), Some(number), "{text}");
+    }
+    for text in ["Chapter 7", "1.2 Scope", "civil", "ill", "12345", ""] {
+      assert_eq!(page_number(text), None, "{text}");
+    }
+  }
+}
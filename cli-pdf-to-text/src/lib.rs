@@ -1,81 +1,410 @@
-use std::{
-  env,
-  io::{BufWriter, Cursor},
-};
+mod layout;
+mod outline;
 
-pub fn pdf_to_text(
-  pdf_path: &str,
-) -> Result<String, Box<dyn std::error::Error>> {
-  #[cfg(target_os = "windows")]
-  redirect_stderr::redirect_stdout()?;
+use rayon::prelude::*;
+use std::cell::Cell;
+use std::error::Error;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+pub use outline::{OutlineEntry, pdf_outline, pdf_outline_with_password};
+
+/// Version of this crate
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Errors opening an encrypted PDF
+///
+/// Other failures, such as missing files or malformed PDFs, are reported
+/// with the error of the underlying library.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PdfError {
+  /// The PDF is encrypted and no password was given
+  PasswordRequired,
+  /// The given password opens neither the user nor the owner lock
+  IncorrectPassword,
+}
+
+impl fmt::Display for PdfError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      PdfError::PasswordRequired => {
+        write!(f, "PDF is encrypted and needs a password")
+      }
+      PdfError::IncorrectPassword => {
+        write!(f, "Incorrect password for encrypted PDF")
+      }
+    }
+  }
+}
+
+impl Error for PdfError {}
+
+/// Text of a single PDF page
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page {
+  /// 1-based position of the page in the document
+  pub number: u32,
+  pub text: String,
+}
 
-  #[allow(unused_assignments)]
-  let mut original_fd = -1;
+/// What went wrong while extracting the pages of a PDF
+///
+/// A page that can't be decoded doesn't fail the whole extraction: its text
+/// is replaced by a placeholder, its number is listed in `pages_failed` and
+/// the reason is added to `warnings`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConversionReport {
+  /// Number of pages whose text was extracted
+  pub pages_ok: usize,
+  /// 1-based numbers of the pages replaced by a placeholder
+  pub pages_failed: Vec<u32>,
+  /// 1-based numbers of the pages that show images but have no text, such
+  /// as scanned pages, which only OCR can read
+  pub pages_without_text: Vec<u32>,
+  /// One message per problem, naming the page it happened on
+  pub warnings: Vec<String>,
+}
 
-  #[allow(unused_assignments)]
-  let mut duplicate_fd = -1;
+impl ConversionReport {
+  /// One line summary of the failed pages, `None` if every page was
+  /// extracted
+  pub fn summary(&self) -> Option<String> {
+    match self.pages_failed.as_slice() {
+      [] => None,
+      [number] => Some(format!("Page {number} could not be extracted")),
+      failed => Some(format!("{} pages could not be extracted", failed.len())),
+    }
+  }
+}
 
-  #[cfg(not(target_os = "windows"))]
-  {
-    extern crate libc;
+/// Extract the text of a whole PDF
+pub fn pdf_to_text(pdf_path: &str) -> Result<String, Box<dyn Error>> {
+  pdf_to_text_with_password(pdf_path, None)
+}
 
-    use std::fs::File;
-    use std::io::{self, Write};
-    use std::os::fd::AsRawFd;
-    use std::os::unix::io::FromRawFd;
+/// Extract the text of a whole PDF, unlocking it with `password` if it is
+/// encrypted
+pub fn pdf_to_text_with_password(
+  pdf_path: &str,
+  password: Option<&str>,
+) -> Result<String, Box<dyn Error>> {
+  let pages: Vec<String> = pdf_to_pages_with_password(pdf_path, password)?
+    .into_iter()
+    .map(|page| page.text)
+    .filter(|text| !text.is_empty())
+    .collect();
+  Ok(pages.join("\n\n"))
+}
 
-    let stdout = io::stdout();
-    original_fd = stdout.as_raw_fd();
+/// Extract the text of each page of a PDF, in page order
+///
+/// The text is rebuilt from glyph positions: columns are read one after
+/// another, running headers, footers and page numbers are left out, and
+/// each paragraph becomes one line keeping its indentation, with a blank
+/// line between paragraphs.
+///
+/// Pages are extracted independently on the rayon thread pool, which gives
+/// the same text as extracting them one after another.
+///
+/// Nothing is written to stdout or stderr: diagnostics about fonts and
+/// content the extractor can't make sense of go to the [`log`] facade, for
+/// the embedding application to record or drop. This makes extraction safe
+/// to run from several threads at once.
+///
+/// [`log`]: https://docs.rs/log
+pub fn pdf_to_pages(pdf_path: &str) -> Result<Vec<Page>, Box<dyn Error>> {
+  pdf_to_pages_with_password(pdf_path, None)
+}
+
+/// Extract the text of each page of a PDF, unlocking it with the user
+/// `password` if it is encrypted
+///
+/// Encrypted PDFs with an empty user password, which only restrict
+/// printing or copying, open without one. Otherwise a missing or wrong
+/// password is reported as a [`PdfError`].
+pub fn pdf_to_pages_with_password(
+  pdf_path: &str,
+  password: Option<&str>,
+) -> Result<Vec<Page>, Box<dyn Error>> {
+  Ok(pdf_to_pages_with_report(pdf_path, password)?.0)
+}
 
-    duplicate_fd = unsafe { libc::dup(original_fd) };
+/// Extract the text of each page of a PDF like
+/// [`pdf_to_pages_with_password`], along with a report of the pages that
+/// could not be extracted
+///
+/// Only failing to open the document is an error. A page whose content
+/// can't be decoded reads `[Page N could not be extracted]` instead.
+pub fn pdf_to_pages_with_report(
+  pdf_path: &str,
+  password: Option<&str>,
+) -> Result<(Vec<Page>, ConversionReport), Box<dyn Error>> {
+  let doc = load_document(pdf_path, password)?;
+  Ok(extract_pages(&doc, true))
+}
 
-    let dev_null = File::open("/dev/null")
-      .map_err(|e| format!("Failed to open /dev/null: {e}"))?;
-    unsafe {
-      libc::dup2(dev_null.as_raw_fd(), original_fd);
+// Load a PDF, decrypting it with `password` or the empty password
+fn load_document(
+  pdf_path: &str,
+  password: Option<&str>,
+) -> Result<lopdf::Document, Box<dyn Error>> {
+  let loaded = match password {
+    Some(password) => lopdf::Document::load_with_password(pdf_path, password),
+    None => lopdf::Document::load(pdf_path),
+  };
+  let mut doc = match loaded {
+    Ok(doc) => doc,
+    Err(lopdf::Error::InvalidPassword) => {
+      return Err(PdfError::IncorrectPassword.into());
     }
+    Err(e) => return Err(e.into()),
+  };
+
+  // Without a password that unlocks it, lopdf leaves the document encrypted
+  // and nearly empty instead of failing
+  if doc.is_encrypted() && !doc.was_encrypted() {
+    return Err(
+      match password {
+        Some(_) => PdfError::IncorrectPassword,
+        None => PdfError::PasswordRequired,
+      }
+      .into(),
+    );
   }
 
-  let path = std::path::Path::new(pdf_path);
+  // The objects are decrypted on load, the dictionary describing the
+  // encryption no longer applies
+  doc.trailer.remove(b"Encrypt");
+  Ok(doc)
+}
 
-  let mut output_buf = Vec::new();
-  {
-    let mut output_file = BufWriter::new(Cursor::new(&mut output_buf));
+fn extract_pages(
+  doc: &pdf_extract::Document,
+  parallel: bool,
+) -> (Vec<Page>, ConversionReport) {
+  let numbers: Vec<u32> = doc.get_pages().into_keys().collect();
+  let extract = |&number: &u32| extract_page(doc, number);
 
-    let doc = pdf_extract::Document::load(path)?;
+  let results: Vec<Result<Vec<layout::Run>, String>> = if parallel {
+    numbers.par_iter().map(extract).collect()
+  } else {
+    numbers.iter().map(extract).collect()
+  };
 
-    pdf_extract::print_metadata(&doc);
+  let mut report = ConversionReport::default();
+  let runs: Vec<Vec<layout::Run>> = numbers
+    .iter()
+    .zip(results)
+    .map(|(&number, result)| match result {
+      Ok(runs) => {
+        report.pages_ok += 1;
+        if runs.is_empty() && has_images(doc, number) {
+          report.pages_without_text.push(number);
+        }
+        runs
+      }
+      Err(e) => {
+        report.pages_failed.push(number);
+        report.warnings.push(format!("page {number}: {e}"));
+        Vec::new()
+      }
+    })
+    .collect();
 
-    let mut output = Box::new(pdf_extract::PlainTextOutput::new(
-      &mut output_file as &mut dyn std::io::Write,
-    ));
+  // Headers and footers are found by comparing pages, so layout waits for
+  // every page
+  let pages = numbers
+    .into_iter()
+    .zip(layout::layout_pages(&runs))
+    .map(|(number, text)| {
+      let text = if report.pages_failed.contains(&number) {
+        format!("[Page {number} could not be extracted]")
+      } else {
+        text
+      };
+      Page { number, text }
+    })
+    .collect();
+  (pages, report)
+}
+
+// Whether the page draws any image directly, which on a page without text
+// is most likely a scan
+fn has_images(doc: &pdf_extract::Document, number: u32) -> bool {
+  doc
+    .get_pages()
+    .get(&number)
+    .and_then(|&id| doc.get_page_images(id).ok())
+    .is_some_and(|images| !images.is_empty())
+}
 
-    pdf_extract::output_doc(&doc, output.as_mut())?;
+// Collect the glyph runs of one page, turning both errors and panics of the
+// extractor into a message so one broken page can't take the others down
+fn extract_page(
+  doc: &pdf_extract::Document,
+  number: u32,
+) -> Result<Vec<layout::Run>, String> {
+  EXTRACTING.set(true);
+  let result = panic::catch_unwind(AssertUnwindSafe(|| {
+    let mut output = layout::RunCollector::default();
+    pdf_extract::output_doc_page(doc, &mut output, number).map(|()| output.runs)
+  }));
+  EXTRACTING.set(false);
+  match result {
+    Ok(Ok(runs)) => Ok(runs),
+    Ok(Err(e)) => Err(e.to_string()),
+    Err(payload) => Err(
+      payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "the extractor panicked".to_string()),
+    ),
   }
+}
+
+thread_local! {
+  static EXTRACTING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Whether this thread is extracting the text of a page
+///
+/// Panics of the extractor are caught and listed in the report of the
+/// conversion, so a program's panic hook can use this to leave them out.
+pub fn is_extracting_page() -> bool {
+  EXTRACTING.get()
+}
 
-  #[cfg(target_os = "windows")]
-  redirect_stderr::restore_stdout()?;
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use lopdf::{Dictionary, Object, ObjectId, Stream, dictionary};
 
-  #[cfg(not(target_os = "windows"))]
-  {
-    extern crate libc;
+  #[test]
+  fn test_parallel_extraction_matches_sequential() {
+    let doc = pdf_extract::Document::load(
+      "../test-data/pdf/pdfreference1.7old-1-50.pdf",
+    )
+    .unwrap();
 
-    use std::fs::File;
-    use std::io::{self, Write};
-    use std::os::fd::AsRawFd;
-    use std::os::unix::io::FromRawFd;
+    let parallel = extract_pages(&doc, true);
+    assert_eq!(parallel.0.len(), 50);
+    assert_eq!(parallel.1.pages_ok, 50);
+    assert_eq!(parallel, extract_pages(&doc, false));
+  }
 
-    unsafe {
-      libc::dup2(duplicate_fd, original_fd);
+  // A document with one page per content stream and resources returned by
+  // `build`, which can add the objects they refer to
+  fn document_with_pages(
+    build: impl FnOnce(&mut lopdf::Document) -> Vec<(String, Dictionary)>,
+  ) -> pdf_extract::Document {
+    let mut doc = lopdf::Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+    let mut kids = Vec::new();
+    for (content, resources) in build(&mut doc) {
+      let content_id =
+        doc.add_object(Stream::new(dictionary! {}, content.into_bytes()));
+      kids.push(Object::from(doc.add_object(dictionary! {
+        "Type" => "Page",
+        "Parent" => pages_id,
+        "Contents" => content_id,
+        "Resources" => resources,
+      })));
     }
+    doc.objects.insert(
+      pages_id,
+      Object::Dictionary(dictionary! {
+        "Type" => "Pages",
+        "Count" => kids.len() as i64,
+        "Kids" => kids,
+        "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+      }),
+    );
+    let catalog_id = doc.add_object(dictionary! {
+      "Type" => "Catalog",
+      "Pages" => pages_id,
+    });
+    doc.trailer.set("Root", catalog_id);
+    doc
   }
 
-  // println!("{:?}", output_buf);
-  // panic!();
+  fn text_page(font: ObjectId, text: &str) -> (String, Dictionary) {
+    (
+      format!("BT /F1 24 Tf 72 720 Td ({text}) Tj ET"),
+      dictionary! {
+        "Font" => dictionary! { "F1" => font },
+      },
+    )
+  }
 
-  let res = std::str::from_utf8(&output_buf)
-    .map_err(|e| format!("Failed to convert PDF output to UTF-8: {e}"))?
-    .to_owned();
+  fn helvetica(doc: &mut lopdf::Document) -> ObjectId {
+    doc.add_object(dictionary! {
+      "Type" => "Font",
+      "Subtype" => "Type1",
+      "BaseFont" => "Helvetica",
+    })
+  }
 
-  Ok(res)
+  #[test]
+  fn test_broken_page_is_replaced_by_placeholder() {
+    // The second page points at a font that doesn't exist
+    let doc = document_with_pages(|doc| {
+      let font = helvetica(doc);
+      vec![
+        text_page(font, "First page"),
+        text_page((999, 0), "Second page"),
+        text_page(font, "Third page"),
+      ]
+    });
+    let (pages, report) = extract_pages(&doc, true);
+
+    let texts: Vec<&str> =
+      pages.iter().map(|page| page.text.as_str()).collect();
+    assert_eq!(
+      texts,
+      ["First page", "[Page 2 could not be extracted]", "Third page"]
+    );
+    assert_eq!(report.pages_ok, 2);
+    assert_eq!(report.pages_failed, [2]);
+    assert_eq!(report.warnings.len(), 1);
+    assert!(report.warnings[0].starts_with("page 2: "));
+    assert_eq!(
+      report.summary().as_deref(),
+      Some("Page 2 could not be extracted")
+    );
+  }
+
+  #[test]
+  fn test_pages_with_only_images_are_reported() {
+    let doc = document_with_pages(|doc| {
+      let font = helvetica(doc);
+      let image = doc.add_object(Stream::new(
+        dictionary! {
+          "Type" => "XObject",
+          "Subtype" => "Image",
+          "Width" => 1,
+          "Height" => 1,
+          "ColorSpace" => "DeviceGray",
+          "BitsPerComponent" => 8,
+        },
+        vec![0],
+      ));
+      vec![
+        text_page(font, "Typed page"),
+        (
+          "q 612 0 0 792 0 0 cm /Im1 Do Q".to_string(),
+          dictionary! {
+            "XObject" => dictionary! { "Im1" => image },
+          },
+        ),
+        (String::new(), dictionary! {}),
+      ]
+    });
+
+    let (pages, report) = extract_pages(&doc, false);
+    assert_eq!(pages[0].text, "Typed page");
+    assert_eq!(report.pages_ok, 3);
+    // The blank third page has nothing to OCR
+    assert_eq!(report.pages_without_text, [2]);
+  }
 }
@@ -1,13 +1,53 @@
+use std::env;
+
+use getopts::Options;
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-  let file = std::env::args().nth(1).ok_or_else(|| {
-    eprintln!(
-      "Usage: {} <pdf_file>",
-      std::env::args().next().unwrap_or_else(|| "cli-pdf-to-text".to_string())
-    );
+  // Pages the extractor panics on are listed as warnings instead
+  let default_hook = std::panic::take_hook();
+  std::panic::set_hook(Box::new(move |info| {
+    if !cli_pdf_to_text::is_extracting_page() {
+      default_hook(info);
+    }
+  }));
+
+  let args: Vec<String> = env::args().collect();
+  let mut opts = Options::new();
+
+  opts.optopt("p", "password", "unlock an encrypted PDF", "PASSWORD");
+  opts.optflag("h", "help", "print this help menu");
+
+  let matches = opts.parse(&args[1..])?;
+  let brief = format!("Usage: {} FILE [options]", args[0]);
+
+  if matches.opt_present("h") {
+    print!("{}", opts.usage(&brief));
+    return Ok(());
+  }
+
+  let Some(file) = matches.free.first() else {
+    eprint!("{}", opts.usage(&brief));
     std::process::exit(1);
-  })?;
+  };
+  let password = matches.opt_str("p");
 
-  println!("{}", cli_pdf_to_text::pdf_to_text(&file)?);
+  match cli_pdf_to_text::pdf_to_pages_with_report(file, password.as_deref()) {
+    Ok((pages, report)) => {
+      let texts: Vec<String> = pages
+        .into_iter()
+        .map(|page| page.text)
+        .filter(|text| !text.is_empty())
+        .collect();
+      println!("{}", texts.join("\n\n"));
+      for warning in &report.warnings {
+        eprintln!("Warning: {warning}");
+      }
+    }
+    Err(e) => {
+      eprintln!("Error: {e}");
+      std::process::exit(1);
+    }
+  }
 
   Ok(())
 }
@@ -0,0 +1,313 @@
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use std::collections::{HashMap, HashSet};
+
+/// How deep name trees are followed, real documents need a handful of levels
+const MAX_NAME_TREE_DEPTH: usize = 32;
+
+/// An entry of the PDF outline, the bookmarks panel of PDF viewers
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlineEntry {
+  pub title: String,
+  /// Page the entry points to, numbered like [`crate::Page::number`]
+  pub page: u32,
+  /// Nesting depth, 0 for top level entries
+  pub level: usize,
+}
+
+/// Read the outline of a PDF, flattened in reading order
+///
+/// Children follow their parent with a `level` one deeper. Entries whose
+/// destination isn't a page of this document, e.g. links to other files,
+/// are left out. A PDF without an outline gives an empty list.
+pub fn pdf_outline(
+  pdf_path: &str,
+) -> Result<Vec<OutlineEntry>, Box<dyn std::error::Error>> {
+  pdf_outline_with_password(pdf_path, None)
+}
+
+/// Read the outline of a PDF, unlocking it with `password` if it is
+/// encrypted
+pub fn pdf_outline_with_password(
+  pdf_path: &str,
+  password: Option<&str>,
+) -> Result<Vec<OutlineEntry>, Box<dyn std::error::Error>> {
+  let doc = crate::load_document(pdf_path, password)?;
+  Ok(document_outline(&doc))
+}
+
+fn document_outline(doc: &Document) -> Vec<OutlineEntry> {
+  let Ok(catalog) = doc.catalog() else {
+    return Vec::new();
+  };
+  let Ok(outlines) = doc.get_dict_in_dict(catalog, b"Outlines") else {
+    return Vec::new();
+  };
+
+  let walker = OutlineWalker {
+    doc,
+    catalog,
+    pages: doc
+      .get_pages()
+      .into_iter()
+      .map(|(number, id)| (id, number))
+      .collect(),
+  };
+  let mut entries = Vec::new();
+  let mut seen = HashSet::new();
+  walker.walk(outlines.get(b"First").ok(), 0, &mut seen, &mut entries);
+  entries
+}
+
+struct OutlineWalker<'a> {
+  doc: &'a Document,
+  catalog: &'a Dictionary,
+  pages: HashMap<ObjectId, u32>,
+}
+
+impl OutlineWalker<'_> {
+  // Visit the item `first` and its siblings, depth first
+  fn walk(
+    &self,
+    first: Option<&Object>,
+    level: usize,
+    seen: &mut HashSet<ObjectId>,
+    entries: &mut Vec<OutlineEntry>,
+  ) {
+    let mut next = first;
+    while let Some(object) = next {
+      // Broken files can link items in a cycle
+      let Ok(id) = object.as_reference() else { break };
+      if !seen.insert(id) {
+        break;
+      }
+      let Ok(item) = self.doc.get_dictionary(id) else { break };
+
+      let title = item
+        .get(b"Title")
+        .ok()
+        .and_then(|title| self.doc.dereference(title).ok())
+        .and_then(|(_, title)| lopdf::decode_text_string(title).ok());
+      if let (Some(title), Some(page)) = (title, self.item_page(item)) {
+        let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+        entries.push(OutlineEntry { title, page, level });
+      }
+
+      self.walk(item.get(b"First").ok(), level + 1, seen, entries);
+      next = item.get(b"Next").ok();
+    }
+  }
+
+  // Page of an outline item, from its /Dest or a /GoTo action
+  fn item_page(&self, item: &Dictionary) -> Option<u32> {
+    if let Ok(dest) = item.get(b"Dest") {
+      return self.destination_page(dest, 0);
+    }
+
+    let action = self.resolve(item.get(b"A").ok()?)?.as_dict().ok()?;
+    if action.get(b"S").ok()?.as_name().ok()? != b"GoTo" {
+      return None;
+    }
+    self.destination_page(action.get(b"D").ok()?, 0)
+  }
+
+  // A destination is an array starting with the page, a name looked up in
+  // the document's destinations, or a dictionary with the array under /D
+  fn destination_page(&self, dest: &Object, depth: usize) -> Option<u32> {
+    if depth > MAX_NAME_TREE_DEPTH {
+      return None;
+    }
+
+    match self.resolve(dest)? {
+      Object::Array(array) => match array.first()? {
+        Object::Reference(id) => self.pages.get(id).copied(),
+        // Some producers write a 0-based page index instead of a reference
+        Object::Integer(index) => {
+          u32::try_from(*index).ok().and_then(|i| i.checked_add(1))
+        }
+        _ => None,
+      },
+      Object::Dictionary(dict) => {
+        self.destination_page(dict.get(b"D").ok()?, depth + 1)
+      }
+      Object::Name(name) => {
+        let dests = self.doc.get_dict_in_dict(self.catalog, b"Dests").ok()?;
+        self.destination_page(dests.get(name).ok()?, depth + 1)
+      }
+      Object::String(name, _) => {
+        let names = self.doc.get_dict_in_dict(self.catalog, b"Names").ok()?;
+        let tree = self.doc.get_dict_in_dict(names, b"Dests").ok()?;
+        let dest = self.name_tree_lookup(tree, name, 0)?;
+        self.destination_page(dest, depth + 1)
+      }
+      _ => None,
+    }
+  }
+
+  fn name_tree_lookup<'b>(
+    &'b self,
+    node: &'b Dictionary,
+    key: &[u8],
+    depth: usize,
+  ) -> Option<&'b Object> {
+    if depth > MAX_NAME_TREE_DEPTH {
+      return None;
+    }
+
+    if let Ok(names) = node.get(b"Names").and_then(Object::as_array) {
+      let found = names
+        .chunks(2)
+        .find(|pair| pair[0].as_str().is_ok_and(|name| name == key));
+      if let Some([_, value]) = found {
+        return Some(value);
+      }
+    }
+
+    let kids = node.get(b"Kids").and_then(Object::as_array).ok()?;
+    kids.iter().find_map(|kid| {
+      let kid = self.resolve(kid)?.as_dict().ok()?;
+      self.name_tree_lookup(kid, key, depth + 1)
+    })
+  }
+
+  fn resolve<'b>(&'b self, object: &'b Object) -> Option<&'b Object> {
+    self.doc.dereference(object).ok().map(|(_, object)| object)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use lopdf::{Stream, dictionary};
+
+  // Two pages, an outline with a nested entry, a named destination and an
+  // entry pointing nowhere
+  fn document_with_outline() -> Document {
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+    let content = doc.add_object(Stream::new(dictionary! {}, Vec::new()));
+    let page_ids: Vec<ObjectId> = (0..2)
+      .map(|_| {
+        doc.add_object(dictionary! {
+          "Type" => "Page",
+          "Parent" => pages_id,
+          "Contents" => content,
+        })
+      })
+      .collect();
+    doc.objects.insert(
+      pages_id,
+      Object::Dictionary(dictionary! {
+        "Type" => "Pages",
+        "Kids" => page_ids.iter().map(|&id| id.into()).collect::<Vec<Object>>(),
+        "Count" => 2,
+        "MediaBox" => vec![0.into(), 0.into(), 100.into(), 100.into()],
+      }),
+    );
+
+    let outlines_id = doc.new_object_id();
+    let first_id = doc.new_object_id();
+    let child_id = doc.new_object_id();
+    let second_id = doc.new_object_id();
+    let broken_id = doc.new_object_id();
+    doc.objects.insert(
+      first_id,
+      Object::Dictionary(dictionary! {
+        "Title" => Object::string_literal("Introduction"),
+        "Parent" => outlines_id,
+        "Dest" => vec![page_ids[0].into(), "Fit".into()],
+        "First" => child_id,
+        "Last" => child_id,
+        "Next" => second_id,
+      }),
+    );
+    doc.objects.insert(
+      child_id,
+      Object::Dictionary(dictionary! {
+        "Title" => Object::string_literal("Scope"),
+        "Parent" => first_id,
+        "A" => dictionary! {
+          "S" => "GoTo",
+          "D" => vec![page_ids[1].into(), "Fit".into()],
+        },
+      }),
+    );
+    doc.objects.insert(
+      second_id,
+      Object::Dictionary(dictionary! {
+        "Title" => Object::string_literal("Syntax"),
+        "Parent" => outlines_id,
+        "Dest" => Object::string_literal("syntax"),
+        "Next" => broken_id,
+      }),
+    );
+    doc.objects.insert(
+      broken_id,
+      Object::Dictionary(dictionary! {
+        "Title" => Object::string_literal("Elsewhere"),
+        "Parent" => outlines_id,
+        "A" => dictionary! { "S" => "URI", "URI" => "https://example.com" },
+      }),
+    );
+    doc.objects.insert(
+      outlines_id,
+      Object::Dictionary(dictionary! {
+        "Type" => "Outlines",
+        "First" => first_id,
+        "Last" => broken_id,
+      }),
+    );
+
+    let catalog_id = doc.add_object(dictionary! {
+      "Type" => "Catalog",
+      "Pages" => pages_id,
+      "Outlines" => outlines_id,
+      "Names" => dictionary! {
+        "Dests" => dictionary! {
+          "Names" => vec![
+            Object::string_literal("syntax"),
+            vec![page_ids[1].into(), "Fit".into()].into(),
+          ],
+        },
+      },
+    });
+    doc.trailer.set("Root", catalog_id);
+    doc
+  }
+
+  #[test]
+  fn test_document_outline() {
+    let entries = document_outline(&document_with_outline());
+    let entries: Vec<_> = entries
+      .iter()
+      .map(|entry| (entry.title.as_str(), entry.page, entry.level))
+      .collect();
+    assert_eq!(
+      entries,
+      vec![("Introduction", 1, 0), ("Scope", 2, 1), ("Syntax", 2, 0),]
+    );
+  }
+
+  #[test]
+  fn test_document_without_outline() {
+    let mut doc = document_with_outline();
+    let catalog = doc.catalog_mut().unwrap();
+    catalog.remove(b"Outlines");
+    assert!(document_outline(&doc).is_empty());
+  }
+
+  #[test]
+  fn test_page_index_destinations() {
+    let doc = document_with_outline();
+    let walker = OutlineWalker {
+      doc: &doc,
+      catalog: doc.catalog().unwrap(),
+      pages: HashMap::new(),
+    };
+    let page = |index: i64| {
+      walker.destination_page(&vec![index.into(), "Fit".into()].into(), 0)
+    };
+    assert_eq!(page(0), Some(1));
+    assert_eq!(page(-1), None);
+    assert_eq!(page(u32::MAX.into()), None);
+  }
+}
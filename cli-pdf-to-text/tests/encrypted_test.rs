@@ -0,0 +1,101 @@
+use cli_pdf_to_text::PdfError;
+use lopdf::{
+  Document, EncryptionState, EncryptionVersion, Object, Permissions, Stream,
+  dictionary,
+};
+use std::path::PathBuf;
+
+// A one page PDF saying "Confidential datasheet", encrypted with the given
+// user password
+fn encrypted_pdf(name: &str, user_password: &str) -> PathBuf {
+  let mut doc = Document::with_version("1.5");
+  let pages_id = doc.new_object_id();
+  let font_id = doc.add_object(dictionary! {
+    "Type" => "Font",
+    "Subtype" => "Type1",
+    "BaseFont" => "Helvetica",
+  });
+  let content = b"BT /F1 24 Tf 72 720 Td (Confidential datasheet) Tj ET";
+  let content_id =
+    doc.add_object(Stream::new(dictionary! {}, content.to_vec()));
+  let page_id = doc.add_object(dictionary! {
+    "Type" => "Page",
+    "Parent" => pages_id,
+    "Contents" => content_id,
+    "Resources" => dictionary! { "Font" => dictionary! { "F1" => font_id } },
+  });
+  doc.objects.insert(
+    pages_id,
+    Object::Dictionary(dictionary! {
+      "Type" => "Pages",
+      "Kids" => vec![page_id.into()],
+      "Count" => 1,
+      "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+    }),
+  );
+  let catalog_id = doc.add_object(dictionary! {
+    "Type" => "Catalog",
+    "Pages" => pages_id,
+  });
+  doc.trailer.set("Root", catalog_id);
+  doc.trailer.set(
+    "ID",
+    vec![
+      Object::string_literal(name.as_bytes()),
+      Object::string_literal(name.as_bytes()),
+    ],
+  );
+
+  let version = EncryptionVersion::V2 {
+    document: &doc,
+    owner_password: "owner",
+    user_password,
+    key_length: 128,
+    permissions: Permissions::all(),
+  };
+  let state = EncryptionState::try_from(version).unwrap();
+  doc.encrypt(&state).unwrap();
+
+  let path = std::env::temp_dir()
+    .join(format!("cli-pdf-to-text-{}-{name}.pdf", std::process::id()));
+  doc.save(&path).unwrap();
+  path
+}
+
+fn pdf_error(result: Result<String, Box<dyn std::error::Error>>) -> PdfError {
+  result.unwrap_err().downcast::<PdfError>().unwrap().as_ref().clone()
+}
+
+#[test]
+fn test_encrypted_pdf_needs_password() {
+  let path = encrypted_pdf("needs-password", "secret");
+  let path = path.to_str().unwrap();
+
+  assert_eq!(
+    pdf_error(cli_pdf_to_text::pdf_to_text(path)),
+    PdfError::PasswordRequired
+  );
+  assert_eq!(
+    pdf_error(cli_pdf_to_text::pdf_to_text_with_password(path, Some("guess"))),
+    PdfError::IncorrectPassword
+  );
+
+  let text =
+    cli_pdf_to_text::pdf_to_text_with_password(path, Some("secret")).unwrap();
+  assert_eq!(text, "Confidential datasheet");
+
+  std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_encrypted_pdf_with_empty_user_password_opens() {
+  let path = encrypted_pdf("empty-password", "");
+  let path = path.to_str().unwrap();
+
+  assert_eq!(
+    cli_pdf_to_text::pdf_to_text(path).unwrap(),
+    "Confidential datasheet"
+  );
+
+  std::fs::remove_file(path).unwrap();
+}
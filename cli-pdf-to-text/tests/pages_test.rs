@@ -0,0 +1,52 @@
+#[test]
+fn test_pdf_to_pages() {
+  let pages = cli_pdf_to_text::pdf_to_pages(
+    "../test-data/pdf/pdfreference1.7old-1-50.pdf",
+  )
+  .unwrap();
+
+  assert_eq!(pages.len(), 50);
+  assert!(pages.iter().zip(1..).all(|(page, number)| page.number == number));
+  assert!(pages[0].text.contains("PDF Reference"));
+  assert!(pages[1].text.contains("All rights reserved"));
+}
+
+#[test]
+fn test_concurrent_conversions() {
+  let path = "../test-data/pdf/pdfreference1.7old-1-50.pdf";
+  let expected = cli_pdf_to_text::pdf_to_text(path).unwrap();
+
+  std::thread::scope(|scope| {
+    let handles: Vec<_> = (0..4)
+      .map(|_| scope.spawn(|| cli_pdf_to_text::pdf_to_text(path).unwrap()))
+      .collect();
+    for handle in handles {
+      assert_eq!(handle.join().unwrap(), expected);
+    }
+  });
+}
+
+#[test]
+fn test_missing_pdf_is_an_error() {
+  assert!(
+    cli_pdf_to_text::pdf_to_pages("../test-data/pdf/missing.pdf").is_err()
+  );
+}
+
+#[test]
+fn test_pdf_outline() {
+  let outline = cli_pdf_to_text::pdf_outline(
+    "../test-data/pdf/pdfreference1.7old-1-50.pdf",
+  )
+  .unwrap();
+
+  let intro = outline
+    .iter()
+    .position(|entry| entry.title == "1 Introduction")
+    .expect("outline has the introduction");
+  assert_eq!(outline[intro].page, 25);
+  assert_eq!(outline[intro].level, 0);
+  assert_eq!(outline[intro + 1].title, "1.1 About This Book");
+  assert_eq!(outline[intro + 1].level, 1);
+  assert!(outline.iter().any(|entry| entry.level == 3));
+}
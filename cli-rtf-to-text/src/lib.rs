@@ -0,0 +1,274 @@
+//! # CLI RTF to Text Converter
+//!
+//! A Rust library for converting Rich Text Format documents to plain text
+//! without pandoc.
+//!
+//! ## Features
+//! - Tokenize control words, control symbols and groups of RTF 1.x
+//! - Decode `\u` unicode escapes and `\'hh` bytes in the code page of the
+//!   document or its fonts
+//! - Split the text into sections at headings, from paragraph styles or
+//!   outline levels
+//! - Flatten tables to one line per row
+//! - Keep the structure as blocks: headings, lists, tables, quotes, code
+//!   and pictures
+//! - Skip font and color tables, picture data, headers, footers and other
+//!   destinations that aren't part of the text
+//! - Collect footnotes into a section after the text
+//! - Read the document properties of the `\info` group
+//!
+//! ## Usage
+//! ```rust
+//! use cli_rtf_to_text::rtf_to_text;
+//!
+//! match rtf_to_text("path/to/letter.rtf") {
+//!     Ok(text) => println!("Extracted text: {}", text),
+//!     Err(e) => eprintln!("Error: {}", e),
+//! }
+//! ```
+
+mod reader;
+
+use document_converter::{Block, Span};
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+
+/// Version of this crate
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Headings of the first two levels start a new section, deeper ones stay in
+/// the text of their section
+const SECTION_LEVELS: usize = 2;
+
+/// Custom error type for RTF processing errors
+#[derive(Debug)]
+pub enum RtfError {
+  FileNotFound(String),
+  InvalidRtf(String),
+}
+
+impl fmt::Display for RtfError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      RtfError::FileNotFound(path) => write!(f, "RTF file not found: {path}"),
+      RtfError::InvalidRtf(msg) => write!(f, "Invalid RTF format: {msg}"),
+    }
+  }
+}
+
+impl Error for RtfError {}
+
+/// A heading and the text following it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Section {
+  /// The heading, `None` for text before the first heading
+  pub title: Option<String>,
+  /// Nesting depth, 0 for top level sections
+  pub level: usize,
+  /// Plain text starting with the heading, with paragraphs and table rows
+  /// separated by blank lines
+  pub text: String,
+  /// The heading and the paragraphs, list items, tables, quotes, code and
+  /// pictures after it
+  pub blocks: Vec<Block>,
+}
+
+/// Document properties from the `\info` group
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RtfMetadata {
+  pub title: Option<String>,
+  pub author: Option<String>,
+  pub subject: Option<String>,
+  pub keywords: Option<String>,
+  pub comment: Option<String>,
+  pub company: Option<String>,
+  /// Creation date, e.g. `2024-01-05`
+  pub created: Option<String>,
+}
+
+/// Read the document properties of an RTF file
+///
+/// # Examples
+/// ```rust
+/// use cli_rtf_to_text::rtf_metadata;
+///
+/// if let Ok(metadata) = rtf_metadata("path/to/letter.rtf") {
+///     println!("{}", metadata.title.unwrap_or_default());
+/// }
+/// ```
+pub fn rtf_metadata(file_path: &str) -> Result<RtfMetadata, RtfError> {
+  Ok(reader::read(&read_rtf(file_path)?).metadata)
+}
+
+/// Convert an RTF file to plain text
+///
+/// # Error Cases
+/// * `RtfError::FileNotFound` - The specified file doesn't exist
+/// * `RtfError::InvalidRtf` - The file is not an RTF document or has no text
+///
+/// # Examples
+/// ```rust
+/// use cli_rtf_to_text::rtf_to_text;
+///
+/// match rtf_to_text("path/to/letter.rtf") {
+///     Ok(text) => println!("Extracted text: {}", text),
+///     Err(e) => eprintln!("Error: {}", e),
+/// }
+/// ```
+pub fn rtf_to_text(file_path: &str) -> Result<String, RtfError> {
+  let sections = rtf_sections(file_path)?;
+  Ok(
+    sections
+      .into_iter()
+      .map(|section| section.text)
+      .collect::<Vec<_>>()
+      .join("\n\n"),
+  )
+}
+
+/// Convert an RTF file to plain text sections
+///
+/// A section starts at each heading of the first two levels, as given by
+/// the paragraph style or outline level. Footnotes are marked `[1]`, `[2]`,
+/// ... in the text and collected in a final "Notes" section. Joining the
+/// section texts with blank lines gives the same text as `rtf_to_text`.
+///
+/// # Examples
+/// ```rust
+/// use cli_rtf_to_text::rtf_sections;
+///
+/// if let Ok(sections) = rtf_sections("path/to/letter.rtf") {
+///     for section in sections {
+///         println!("{}", section.title.unwrap_or_default());
+///     }
+/// }
+/// ```
+pub fn rtf_sections(file_path: &str) -> Result<Vec<Section>, RtfError> {
+  let document = reader::read(&read_rtf(file_path)?);
+
+  let mut sections = split_sections(document.blocks);
+  if !document.notes.is_empty() {
+    let title = "Notes".to_string();
+    let heading = Block::Heading { level: 0, spans: vec![Span::plain(&title)] };
+    let notes =
+      document.notes.iter().enumerate().map(|(index, text)| Block::ListItem {
+        depth: 0,
+        marker: format!("[{}]", index + 1),
+        spans: vec![Span::plain(text)],
+      });
+    let blocks: Vec<Block> = std::iter::once(heading).chain(notes).collect();
+    sections.push(Section {
+      title: Some(title),
+      level: 0,
+      text: blocks_text(&blocks),
+      blocks,
+    });
+  }
+
+  if sections.is_empty() {
+    Err(RtfError::InvalidRtf("No readable content found in RTF".to_string()))
+  } else {
+    Ok(sections)
+  }
+}
+
+fn read_rtf(file_path: &str) -> Result<Vec<u8>, RtfError> {
+  if !Path::new(file_path).exists() {
+    return Err(RtfError::FileNotFound(file_path.to_string()));
+  }
+  let bytes = std::fs::read(file_path)
+    .map_err(|e| RtfError::InvalidRtf(format!("Failed to read file: {e}")))?;
+
+  // Some editors write a byte order mark or blank lines before the header
+  let start = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(&bytes);
+  let start = start.trim_ascii_start();
+  if !start.starts_with(b"{\\rtf") {
+    return Err(RtfError::InvalidRtf(
+      "Missing {\\rtf header, not an RTF document".to_string(),
+    ));
+  }
+  Ok(bytes)
+}
+
+// Group blocks into sections starting at the top level headings
+fn split_sections(blocks: Vec<Block>) -> Vec<Section> {
+  let mut sections = Vec::new();
+  let mut current = Section::default();
+  for block in blocks {
+    if let Block::Heading { level, spans } = &block
+      && *level < SECTION_LEVELS
+    {
+      let title = spans.iter().map(|span| span.text.as_str()).collect();
+      let next =
+        Section { title: Some(title), level: *level, ..Default::default() };
+      let previous = std::mem::replace(&mut current, next);
+      if !previous.blocks.is_empty() {
+        sections.push(previous);
+      }
+    }
+    current.blocks.push(block);
+  }
+  if !current.blocks.is_empty() {
+    sections.push(current);
+  }
+
+  // Documents that start at "Heading 2" still have top level sections
+  let top = sections
+    .iter()
+    .filter(|section| section.title.is_some())
+    .map(|section| section.level)
+    .min()
+    .unwrap_or(0);
+  for section in &mut sections {
+    section.level = section.level.saturating_sub(top);
+    for block in &mut section.blocks {
+      if let Block::Heading { level, .. } = block {
+        *level = level.saturating_sub(top);
+      }
+    }
+    section.text = blocks_text(&section.blocks);
+  }
+  sections
+}
+
+// Plain text of blocks, separated by blank lines
+fn blocks_text(blocks: &[Block]) -> String {
+  blocks.iter().map(Block::text).collect::<Vec<_>>().join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_rtf_error_display() {
+    let file_error = RtfError::FileNotFound("test.rtf".to_string());
+    assert_eq!(format!("{file_error}"), "RTF file not found: test.rtf");
+
+    let invalid_error = RtfError::InvalidRtf("Bad format".to_string());
+    assert_eq!(format!("{invalid_error}"), "Invalid RTF format: Bad format");
+  }
+
+  #[test]
+  fn test_split_sections() {
+    let paragraph = |text| Block::Paragraph(vec![Span::plain(text)]);
+    let heading =
+      |level, text| Block::Heading { level, spans: vec![Span::plain(text)] };
+    let blocks = vec![
+      paragraph("Dear reader,"),
+      heading(1, "Terms"),
+      paragraph("First."),
+      heading(2, "Details"),
+      paragraph("Second."),
+      heading(1, "Signature"),
+    ];
+    let sections = split_sections(blocks);
+    let titles: Vec<_> = sections
+      .iter()
+      .map(|section| (section.title.as_deref(), section.level))
+      .collect();
+    assert_eq!(titles, [(None, 0), (Some("Terms"), 0), (Some("Signature"), 0)]);
+    assert_eq!(sections[1].text, "Terms\n\nFirst.\n\nDetails\n\nSecond.");
+  }
+}
@@ -0,0 +1,40 @@
+use cli_rtf_to_text::RtfMetadata;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+  let program =
+    std::env::args().next().unwrap_or_else(|| "cli-rtf-to-text".to_string());
+  let args: Vec<String> = std::env::args().skip(1).collect();
+
+  let metadata = args.iter().any(|arg| arg == "--metadata");
+  let file_path =
+    args.iter().find(|arg| !arg.starts_with("--")).ok_or_else(|| {
+      eprintln!("Usage: {program} [--metadata] <rtf_file>");
+      std::process::exit(1);
+    })?;
+
+  if metadata {
+    print_metadata(&cli_rtf_to_text::rtf_metadata(file_path)?);
+  } else {
+    println!("{}", cli_rtf_to_text::rtf_to_text(file_path)?);
+  }
+
+  Ok(())
+}
+
+fn print_metadata(metadata: &RtfMetadata) {
+  let fields = [
+    ("Title", &metadata.title),
+    ("Author", &metadata.author),
+    ("Subject", &metadata.subject),
+    ("Keywords", &metadata.keywords),
+    ("Comment", &metadata.comment),
+    ("Company", &metadata.company),
+    ("Created", &metadata.created),
+  ];
+
+  for (name, value) in fields {
+    if let Some(value) = value {
+      println!("{name}: {value}");
+    }
+  }
+}
@@ -0,0 +1,777 @@
+//! A tokenizer for RTF that follows groups and control words through the
+//! document, collecting its paragraphs, list items, tables, pictures,
+//! footnotes and properties
+
+use crate::RtfMetadata;
+use document_converter::{Block, Span};
+use encoding_rs::{Encoding, WINDOWS_1252};
+use std::collections::HashMap;
+
+/// Destinations whose groups hold no text of the document. Groups starting
+/// with `\*` are skipped as well, whatever their destination.
+const SKIPPED_DESTINATIONS: &[&str] = &[
+  "colortbl",
+  "pict",
+  "nonshppict",
+  "shp",
+  "object",
+  "header",
+  "headerl",
+  "headerr",
+  "headerf",
+  "footer",
+  "footerl",
+  "footerr",
+  "footerf",
+  "ftnsep",
+  "ftnsepc",
+  "ftncn",
+  "aftnsep",
+  "aftnsepc",
+  "aftncn",
+  "fldinst",
+  "pn",
+  "xe",
+  "tc",
+  "txe",
+  "rxe",
+  "bkmkstart",
+  "bkmkend",
+  "listtable",
+  "listoverridetable",
+  "revtbl",
+  "rsidtbl",
+  "filetbl",
+  "generator",
+  "template",
+  "themedata",
+  "colorschememapping",
+  "latentstyles",
+  "datastore",
+  "xmlnstbl",
+];
+
+/// Outline level of body text, lower levels are headings
+const BODY_TEXT_LEVEL: i32 = 9;
+
+/// Longest control word the specification allows
+const MAX_WORD_LEN: usize = 32;
+
+/// Paragraph styles of quotations and of code, named like Word's built-in
+/// styles without spaces
+const QUOTE_STYLES: &[&str] = &["quote", "intensequote", "blocktext"];
+const CODE_STYLES: &[&str] = &["sourcecode", "htmlpreformatted", "code"];
+
+#[derive(Debug, Default)]
+pub(crate) struct Document {
+  pub(crate) blocks: Vec<Block>,
+  pub(crate) notes: Vec<String>,
+  pub(crate) metadata: RtfMetadata,
+}
+
+/// Where the text of a group goes
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Destination {
+  Text,
+  /// The marker of a list item, `\listtext` or `\pntext`
+  ListText,
+  Footnote,
+  FontTable,
+  StyleSheet,
+  Info,
+  /// A property of the `\info` group, e.g. `title`
+  InfoField(&'static str),
+  CreationTime,
+  Skip,
+}
+
+/// Properties that are restored at the end of each group
+#[derive(Debug, Clone, Copy)]
+struct State {
+  destination: Destination,
+  /// Number of fallback characters that follow each `\u`
+  fallback_len: usize,
+  /// Font for `\'hh` bytes, `None` for the default font
+  font: Option<i32>,
+  /// Set by `\v`, hidden text is left out
+  hidden: bool,
+}
+
+/// A style of the `\stylesheet` group as it is read
+#[derive(Debug, Default)]
+struct StyleEntry {
+  number: Option<i32>,
+  outline_level: Option<i32>,
+  name: String,
+}
+
+/// Read the text and properties of an RTF document
+///
+/// Unbalanced groups are tolerated, text after the closing brace of the
+/// document is ignored.
+pub(crate) fn read(rtf: &[u8]) -> Document {
+  Reader::default().read(rtf)
+}
+
+#[derive(Default)]
+struct Reader {
+  state: State,
+  stack: Vec<State>,
+  /// Code page bytes waiting to be decoded together, for multibyte code
+  /// pages
+  bytes: Vec<u8>,
+  /// Fallback characters of the last `\u` still to skip
+  skip: usize,
+  /// Set by `\*`, the group is skipped unless its destination is known
+  ignorable: bool,
+  high_surrogate: Option<u32>,
+  /// Code page of `\ansicpg`
+  encoding: Option<&'static Encoding>,
+  default_font: Option<i32>,
+  /// Code page of each font that sets its own
+  fonts: HashMap<i32, &'static Encoding>,
+  /// The font table entry being read
+  font_entry: Option<i32>,
+  style_entry: StyleEntry,
+  /// Heading level of each paragraph style that is a heading
+  style_levels: HashMap<i32, usize>,
+  /// Paragraph styles of quotations and of code
+  quote_styles: Vec<i32>,
+  code_styles: Vec<i32>,
+  paragraph: String,
+  paragraph_style: Option<i32>,
+  outline_level: Option<i32>,
+  /// Marker and `\ilvl` of a list item
+  marker: String,
+  list_level: usize,
+  /// Pictures in the paragraph, which follow it
+  pictures: usize,
+  in_table: bool,
+  cells: Vec<String>,
+  /// Rows of the table being read and whether the first is a header row
+  rows: Vec<Vec<String>>,
+  header: bool,
+  header_row: bool,
+  note: String,
+  field: String,
+  /// Year, month and day of `\creatim`
+  created: [Option<i32>; 3],
+  document: Document,
+}
+
+impl Default for State {
+  fn default() -> Self {
+    State {
+      destination: Destination::Text,
+      fallback_len: 1,
+      font: None,
+      hidden: false,
+    }
+  }
+}
+
+impl Reader {
+  fn read(mut self, rtf: &[u8]) -> Document {
+    let mut at = 0;
+    while let Some(&byte) = rtf.get(at) {
+      at += 1;
+      match byte {
+        b'{' => self.open_group(),
+        b'}' => {
+          if !self.close_group() {
+            break;
+          }
+        }
+        b'\\' => at = self.control(rtf, at),
+        // Line breaks in the source are only there to keep lines short
+        b'\r' | b'\n' => {}
+        _ if self.skip > 0 => self.skip -= 1,
+        b'\t' => self.push_text(" "),
+        _ => self.bytes.push(byte),
+      }
+    }
+
+    self.end_paragraph();
+    self.end_row();
+    self.end_table();
+    self.document
+  }
+
+  fn open_group(&mut self) {
+    self.flush();
+    self.skip = 0;
+    self.ignorable = false;
+    self.stack.push(self.state);
+  }
+
+  // Restore the state of the enclosing group, false at the end of the
+  // document
+  fn close_group(&mut self) -> bool {
+    self.flush();
+    self.skip = 0;
+    self.ignorable = false;
+    let Some(outer) = self.stack.pop() else {
+      return false;
+    };
+    let inner = std::mem::replace(&mut self.state, outer);
+    if inner.destination == outer.destination {
+      return true;
+    }
+
+    match inner.destination {
+      Destination::Footnote => {
+        let note = collapse_whitespace(&std::mem::take(&mut self.note));
+        self.document.notes.push(note);
+      }
+      Destination::InfoField(name) => {
+        let value = collapse_whitespace(&std::mem::take(&mut self.field));
+        let value = Some(value).filter(|value| !value.is_empty());
+        let metadata = &mut self.document.metadata;
+        match name {
+          "title" => metadata.title = value,
+          "author" => metadata.author = value,
+          "subject" => metadata.subject = value,
+          "keywords" => metadata.keywords = value,
+          "doccomm" => metadata.comment = value,
+          "company" => metadata.company = value,
+          _ => {}
+        }
+      }
+      Destination::CreationTime => {
+        if let [Some(year), month, day] = self.created {
+          self.document.metadata.created = Some(format!(
+            "{year:04}-{:02}-{:02}",
+            month.unwrap_or(1),
+            day.unwrap_or(1)
+          ));
+        }
+      }
+      _ => {}
+    }
+    true
+  }
+
+  // Read the control word or symbol after a backslash at `at`, returning
+  // where the next token starts
+  fn control(&mut self, rtf: &[u8], mut at: usize) -> usize {
+    let Some(&first) = rtf.get(at) else {
+      return at;
+    };
+    if !first.is_ascii_alphabetic() {
+      return self.symbol(rtf, at);
+    }
+
+    let start = at;
+    while at - start < MAX_WORD_LEN
+      && rtf.get(at).is_some_and(u8::is_ascii_alphabetic)
+    {
+      at += 1;
+    }
+    let word = String::from_utf8_lossy(&rtf[start..at]);
+
+    let number_start = at;
+    if rtf.get(at) == Some(&b'-') {
+      at += 1;
+    }
+    while rtf.get(at).is_some_and(u8::is_ascii_digit) {
+      at += 1;
+    }
+    let parameter = std::str::from_utf8(&rtf[number_start..at])
+      .ok()
+      .and_then(|number| number.parse::<i32>().ok());
+
+    // A space ends the control word and is not part of the text
+    if rtf.get(at) == Some(&b' ') {
+      at += 1;
+    }
+
+    // Binary data, e.g. of pictures, is skipped by its length
+    if word == "bin" {
+      let len = parameter.unwrap_or(0).max(0) as usize;
+      return at.saturating_add(len).min(rtf.len());
+    }
+
+    self.word(&word, parameter);
+    at
+  }
+
+  fn symbol(&mut self, rtf: &[u8], at: usize) -> usize {
+    let symbol = rtf[at];
+    if symbol == b'\'' {
+      let byte = rtf
+        .get(at + 1..at + 3)
+        .and_then(|hex| std::str::from_utf8(hex).ok())
+        .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+      let Some(byte) = byte else {
+        return at + 1;
+      };
+      if self.skip > 0 {
+        self.skip -= 1;
+      } else {
+        self.bytes.push(byte);
+      }
+      return at + 3;
+    }
+
+    if self.skip > 0 {
+      self.skip -= 1;
+      return at + 1;
+    }
+    match symbol {
+      b'*' => self.ignorable = true,
+      b'\\' | b'{' | b'}' => self.bytes.push(symbol),
+      b'~' => self.push_text("\u{a0}"),
+      b'_' => self.push_text("\u{2011}"),
+      b'\r' | b'\n' => self.end_paragraph(),
+      // Optional hyphens, index subentries and formulas
+      _ => {}
+    }
+    at + 1
+  }
+
+  fn word(&mut self, word: &str, parameter: Option<i32>) {
+    if self.skip > 0 {
+      self.skip -= 1;
+      return;
+    }
+    self.flush();
+
+    // Word writes some document properties and its pictures as optional
+    // destinations
+    if std::mem::take(&mut self.ignorable)
+      && !matches!(word, "company" | "shppict")
+    {
+      self.state.destination = Destination::Skip;
+      return;
+    }
+
+    let destination = self.state.destination;
+    let text = destination == Destination::Text;
+    match word {
+      "ansicpg" => self.encoding = parameter.and_then(code_page),
+      "mac" => self.encoding = Some(encoding_rs::MACINTOSH),
+      "deff" => self.default_font = parameter,
+      "f" if destination == Destination::FontTable => {
+        self.font_entry = parameter;
+      }
+      "f" => self.state.font = parameter,
+      "fcharset" | "cpg" if destination == Destination::FontTable => {
+        let encoding = match word {
+          "fcharset" => parameter.and_then(charset_code_page),
+          _ => parameter,
+        }
+        .and_then(code_page);
+        if let (Some(font), Some(encoding)) = (self.font_entry, encoding) {
+          self.fonts.insert(font, encoding);
+        }
+      }
+      "uc" => self.state.fallback_len = parameter.unwrap_or(1).max(0) as usize,
+      "u" => {
+        if let Some(code) = parameter {
+          self.unicode(code);
+        }
+        self.skip = self.state.fallback_len;
+      }
+
+      "par" | "sect" | "page" => self.end_paragraph(),
+      "line" => self.push_text("\n"),
+      "tab" => self.push_text(" "),
+      "cell" | "nestcell" if text => self.end_cell(),
+      "row" if text => self.end_row(),
+      "pard" if text => {
+        self.paragraph_style = None;
+        self.outline_level = None;
+        self.list_level = 0;
+        self.in_table = false;
+      }
+      "intbl" if text => self.in_table = true,
+      "ilvl" if text => {
+        self.list_level = parameter.unwrap_or(0).clamp(0, 8) as usize;
+      }
+      "listtext" | "pntext" if text => {
+        self.state.destination = Destination::ListText;
+        self.marker.clear();
+      }
+      "trowd" if text => self.header_row = false,
+      "trhdr" if text => self.header_row = true,
+      "pict" if text => {
+        self.pictures += 1;
+        self.state.destination = Destination::Skip;
+      }
+      "s" if destination == Destination::StyleSheet => {
+        self.style_entry.number = parameter;
+      }
+      "s" if text => self.paragraph_style = parameter,
+      // Character, section and table styles are never headings
+      "cs" | "ds" | "ts" if destination == Destination::StyleSheet => {
+        self.style_entry.number = None;
+      }
+      "outlinelevel" if destination == Destination::StyleSheet => {
+        self.style_entry.outline_level = parameter;
+      }
+      "outlinelevel" if text => self.outline_level = parameter,
+      "plain" => {
+        self.state.font = None;
+        self.state.hidden = false;
+      }
+      "v" => self.state.hidden = parameter != Some(0),
+
+      "fonttbl" => self.state.destination = Destination::FontTable,
+      "stylesheet" => {
+        self.state.destination = Destination::StyleSheet;
+        self.style_entry = StyleEntry::default();
+      }
+      "info" => self.state.destination = Destination::Info,
+      "title" | "author" | "subject" | "keywords" | "doccomm" | "company"
+        if destination == Destination::Info =>
+      {
+        let name = match word {
+          "title" => "title",
+          "author" => "author",
+          "subject" => "subject",
+          "keywords" => "keywords",
+          "doccomm" => "doccomm",
+          _ => "company",
+        };
+        self.state.destination = Destination::InfoField(name);
+        self.field.clear();
+      }
+      "creatim" if destination == Destination::Info => {
+        self.state.destination = Destination::CreationTime;
+        self.created = [None; 3];
+      }
+      "yr" if destination == Destination::CreationTime => {
+        self.created[0] = parameter;
+      }
+      "mo" if destination == Destination::CreationTime => {
+        self.created[1] = parameter;
+      }
+      "dy" if destination == Destination::CreationTime => {
+        self.created[2] = parameter;
+      }
+      "footnote" => {
+        self.state.destination = Destination::Footnote;
+        self.note.clear();
+      }
+      // The number of the next footnote, at its reference in the text
+      "chftn" if text => {
+        let number = self.document.notes.len() + 1;
+        self.push_text(&format!("[{number}]"));
+      }
+      word if SKIPPED_DESTINATIONS.contains(&word) => {
+        self.state.destination = Destination::Skip;
+      }
+
+      "emdash" => self.push_text("—"),
+      "endash" => self.push_text("–"),
+      "bullet" => self.push_text("•"),
+      "lquote" => self.push_text("‘"),
+      "rquote" => self.push_text("’"),
+      "ldblquote" => self.push_text("“"),
+      "rdblquote" => self.push_text("”"),
+      "emspace" | "enspace" | "qmspace" => self.push_text(" "),
+      _ => {}
+    }
+  }
+
+  fn unicode(&mut self, code: i32) {
+    // Parameters are signed 16-bit numbers
+    let code = if code < 0 { code + 0x10000 } else { code } as u32;
+    match code {
+      0xd800..=0xdbff => self.high_surrogate = Some(code),
+      0xdc00..=0xdfff => {
+        if let Some(high) = self.high_surrogate.take() {
+          let code = 0x10000 + ((high - 0xd800) << 10) + (code - 0xdc00);
+          self.push_char(char::from_u32(code).unwrap_or('\u{fffd}'));
+        }
+      }
+      _ => self.push_char(char::from_u32(code).unwrap_or('\u{fffd}')),
+    }
+  }
+
+  fn push_char(&mut self, c: char) {
+    self.push_text(c.encode_utf8(&mut [0; 4]));
+  }
+
+  fn push_text(&mut self, text: &str) {
+    self.flush();
+    self.append(text);
+  }
+
+  // Decode the pending code page bytes in the encoding of the current font
+  fn flush(&mut self) {
+    if self.bytes.is_empty() {
+      return;
+    }
+    let bytes = std::mem::take(&mut self.bytes);
+    let encoding = self
+      .state
+      .font
+      .or(self.default_font)
+      .and_then(|font| self.fonts.get(&font).copied())
+      .or(self.encoding)
+      .unwrap_or(WINDOWS_1252);
+    let (text, _) = encoding.decode_without_bom_handling(&bytes);
+    self.append(&text);
+  }
+
+  fn append(&mut self, text: &str) {
+    match self.state.destination {
+      Destination::Text | Destination::Footnote if self.state.hidden => {}
+      Destination::Text => self.paragraph.push_str(text),
+      Destination::ListText => self.marker.push_str(text),
+      Destination::Footnote => self.note.push_str(text),
+      Destination::InfoField(_) => self.field.push_str(text),
+      Destination::StyleSheet => {
+        // Each style ends with a semicolon after its name
+        for c in text.chars() {
+          if c == ';' {
+            self.end_style();
+          } else {
+            self.style_entry.name.push(c);
+          }
+        }
+      }
+      _ => {}
+    }
+  }
+
+  fn end_style(&mut self) {
+    let entry = std::mem::take(&mut self.style_entry);
+    let level = entry
+      .outline_level
+      .or_else(|| heading_level_from_name(entry.name.trim()))
+      .filter(|level| (0..BODY_TEXT_LEVEL).contains(level));
+    if let (Some(number), Some(level)) = (entry.number, level) {
+      self.style_levels.insert(number, level as usize);
+    }
+    let name = entry.name.to_lowercase().replace(' ', "");
+    if let Some(number) = entry.number {
+      if QUOTE_STYLES.contains(&name.as_str()) {
+        self.quote_styles.push(number);
+      } else if CODE_STYLES.contains(&name.as_str()) {
+        self.code_styles.push(number);
+      }
+    }
+    // Styles without a number are the "Normal" style 0
+    self.style_entry.number = Some(0);
+  }
+
+  fn end_paragraph(&mut self) {
+    match self.state.destination {
+      Destination::Footnote => self.note.push(' '),
+      Destination::Text if self.in_table => self.paragraph.push('\n'),
+      Destination::Text => {
+        self.end_table();
+        let text = paragraph_text(&std::mem::take(&mut self.paragraph));
+        let marker = collapse_whitespace(&std::mem::take(&mut self.marker));
+        let pictures = std::mem::take(&mut self.pictures);
+        if !text.is_empty() {
+          let block = self.paragraph_block(text, marker);
+          self.document.blocks.push(block);
+        }
+        let pictures =
+          (0..pictures).map(|_| Block::Image { alt: String::new() });
+        self.document.blocks.extend(pictures);
+      }
+      _ => {}
+    }
+  }
+
+  // A heading, list item, quote, code or paragraph, by the paragraph's
+  // style, outline level and list marker
+  fn paragraph_block(&self, text: String, marker: String) -> Block {
+    let spans = vec![Span::plain(&text)];
+    let level = self
+      .outline_level
+      .or_else(|| {
+        let style = self.paragraph_style?;
+        self.style_levels.get(&style).map(|&level| level as i32)
+      })
+      .filter(|level| (0..BODY_TEXT_LEVEL).contains(level));
+    let style = self.paragraph_style.unwrap_or(0);
+    if let Some(level) = level {
+      Block::Heading { level: level as usize, spans }
+    } else if !marker.is_empty() {
+      Block::ListItem { depth: self.list_level, marker, spans }
+    } else if self.code_styles.contains(&style) {
+      Block::CodeBlock(text)
+    } else if self.quote_styles.contains(&style) {
+      Block::Quote(spans)
+    } else {
+      Block::Paragraph(spans)
+    }
+  }
+
+  fn end_cell(&mut self) {
+    let text = collapse_whitespace(&std::mem::take(&mut self.paragraph));
+    self.cells.push(text);
+  }
+
+  fn end_row(&mut self) {
+    let cells = std::mem::take(&mut self.cells);
+    // Cells that only hold a nested table are left out
+    let row: Vec<String> =
+      cells.into_iter().filter(|cell| !cell.is_empty()).collect();
+    if !row.is_empty() {
+      if self.rows.is_empty() {
+        self.header = self.header_row;
+      }
+      self.rows.push(row);
+    }
+  }
+
+  fn end_table(&mut self) {
+    let rows = std::mem::take(&mut self.rows);
+    if rows.is_empty() {
+      return;
+    }
+    self.document.blocks.push(Block::Table {
+      header: self.header,
+      rows: rows
+        .iter()
+        .map(|row| row.iter().map(|cell| vec![Span::plain(cell)]).collect())
+        .collect(),
+    });
+  }
+}
+
+// Word's built-in heading styles are named "heading 1" to "heading 9"
+fn heading_level_from_name(name: &str) -> Option<i32> {
+  let name = name.to_lowercase();
+  if name == "title" {
+    return Some(0);
+  }
+  let number = name.strip_prefix("heading")?.trim();
+  number.parse::<i32>().ok().filter(|&n| n >= 1).map(|n| n - 1)
+}
+
+/// The code page of a `\fcharset`, `None` for the document's own
+fn charset_code_page(charset: i32) -> Option<i32> {
+  match charset {
+    77 => Some(10000),
+    128 => Some(932),
+    129 => Some(949),
+    134 => Some(936),
+    136 => Some(950),
+    161 => Some(1253),
+    162 => Some(1254),
+    163 => Some(1258),
+    177 => Some(1255),
+    178 => Some(1256),
+    186 => Some(1257),
+    204 => Some(1251),
+    222 => Some(874),
+    238 => Some(1250),
+    _ => None,
+  }
+}
+
+fn code_page(code_page: i32) -> Option<&'static Encoding> {
+  match code_page {
+    437 | 850 => None,
+    65001 => Some(encoding_rs::UTF_8),
+    10000 => Some(encoding_rs::MACINTOSH),
+    874 => Some(encoding_rs::WINDOWS_874),
+    932 => Some(encoding_rs::SHIFT_JIS),
+    936 => Some(encoding_rs::GBK),
+    949 => Some(encoding_rs::EUC_KR),
+    950 => Some(encoding_rs::BIG5),
+    20866 => Some(encoding_rs::KOI8_R),
+    code_page => Encoding::for_label(format!("cp{code_page}").as_bytes()),
+  }
+}
+
+// Collapse spaces within the lines of a paragraph, which keeps `\line`
+// breaks
+fn paragraph_text(text: &str) -> String {
+  text
+    .lines()
+    .map(collapse_whitespace)
+    .filter(|line| !line.is_empty())
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+fn collapse_whitespace(text: &str) -> String {
+  text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn blocks(rtf: &str) -> Vec<Block> {
+    read(rtf.as_bytes()).blocks
+  }
+
+  fn paragraph(text: &str) -> Block {
+    Block::Paragraph(vec![Span::plain(text)])
+  }
+
+  fn table(header: bool, rows: &[&[&str]]) -> Block {
+    Block::Table {
+      header,
+      rows: rows
+        .iter()
+        .map(|row| row.iter().map(|cell| vec![Span::plain(cell)]).collect())
+        .collect(),
+    }
+  }
+
+  #[test]
+  fn test_unicode_and_code_page_bytes() {
+    let rtf = r"{\rtf1\ansi\ansicpg1252\uc1 Caf\'e9 \u8364? and {\uc2\u26085\'93\'fa}, \u-10179?\u-8704? smile\par}";
+    assert_eq!(blocks(rtf), [paragraph("Café € and 日, 😀 smile")]);
+
+    let rtf = r"{\rtf1\ansi\ansicpg1251 \'cf\'f0\'e8\'e2\'e5\'f2\par}";
+    assert_eq!(blocks(rtf), [paragraph("Привет")]);
+
+    // Fonts set their own code page
+    let rtf = r"{\rtf1\ansi\deff0{\fonttbl{\f0\fcharset0 Arial;}{\f1\fcharset128 MS Mincho;}}\f0 a\'e9 {\f1\'93\'fa\'96\'7b}\par}";
+    assert_eq!(blocks(rtf), [paragraph("aé 日本")]);
+  }
+
+  #[test]
+  fn test_destinations_are_skipped() {
+    let rtf = r#"{\rtf1{\colortbl;\red0\green0\blue0;}{\header Page header\par}{\*\generator Writer;}{\pict\pngblip\bin4 }}{x}}Text{\*\bkmkstart a}{\field{\*\fldinst HYPERLINK "x"}{\fldrslt  link}} \{braces\}\\{\v hidden}\par}"#;
+    // The picture's data is skipped, the picture follows its paragraph
+    assert_eq!(
+      blocks(rtf),
+      [paragraph("Text link {braces}\\"), Block::Image { alt: String::new() }]
+    );
+  }
+
+  #[test]
+  fn test_tables_and_line_breaks() {
+    let rtf = r"{\rtf1\trowd\cellx1000\cellx2000\pard\intbl Name\cell Value\cell\row\trowd\cellx1000\cellx2000\pard\intbl a\par b\cell\tab c\cell\row\pard One\line two\par}";
+    assert_eq!(
+      blocks(rtf),
+      [
+        table(false, &[&["Name", "Value"], &["a b", "c"]]),
+        paragraph("One\ntwo")
+      ]
+    );
+
+    let rtf = r"{\rtf1\trowd\trhdr\cellx1000\pard\intbl Bird\cell\row\trowd\cellx1000\pard\intbl Robin\cell\row}";
+    assert_eq!(blocks(rtf), [table(true, &[&["Bird"], &["Robin"]])]);
+  }
+
+  #[test]
+  fn test_lists_quotes_code_and_pictures() {
+    let rtf = r"{\rtf1{\stylesheet{\s0 Normal;}{\s1 Quote;}{\s2 HTML Preformatted;}}\pard\ls1\ilvl1{\listtext 1.\tab}Robins\par\pard\s1 Quoted\par\pard\s2 let x = 1;\par\pard{\*\shppict{\pict\pngblip 89504e47}}{\nonshppict{\pict\wmetafile8 0100}}\par}";
+    assert_eq!(
+      blocks(rtf),
+      [
+        Block::ListItem {
+          depth: 1,
+          marker: "1.".to_string(),
+          spans: vec![Span::plain("Robins")]
+        },
+        Block::Quote(vec![Span::plain("Quoted")]),
+        Block::CodeBlock("let x = 1;".to_string()),
+        Block::Image { alt: String::new() },
+      ]
+    );
+  }
+}
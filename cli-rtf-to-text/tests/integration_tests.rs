@@ -0,0 +1,94 @@
+use cli_rtf_to_text::{RtfError, rtf_metadata, rtf_sections, rtf_to_text};
+use std::path::PathBuf;
+
+const FIXTURE: &str = "../test-data/rtf/test.rtf";
+
+// Write an RTF document to the temp dir
+fn write_rtf(name: &str, rtf: &str) -> PathBuf {
+  let path = std::env::temp_dir()
+    .join(format!("cli-rtf-to-text-{}-{name}.rtf", std::process::id()));
+  std::fs::write(&path, rtf).unwrap();
+  path
+}
+
+#[test]
+fn test_fixture_sections() {
+  let sections = rtf_sections(FIXTURE).unwrap();
+  let titles: Vec<_> = sections
+    .iter()
+    .map(|section| (section.title.as_deref(), section.level))
+    .collect();
+  assert_eq!(
+    titles,
+    [(None, 0), (Some("Scope"), 0), (Some("Prices"), 1), (Some("Notes"), 0)]
+  );
+  assert_eq!(
+    sections[0].text,
+    "Between Acme GmbH and Widget Ltd, signed in München."
+  );
+
+  let text = rtf_to_text(FIXTURE).unwrap();
+  let joined: Vec<String> = sections.into_iter().map(|s| s.text).collect();
+  assert_eq!(text, joined.join("\n\n"));
+}
+
+#[test]
+fn test_fixture_text_lists_tables_and_footnotes() {
+  let text = rtf_to_text(FIXTURE).unwrap();
+  assert!(text.contains(
+    "The supplier delivers the goods listed below “as is”[1], priced in € and signed by Иван Петров."
+  ));
+  assert!(text.contains("\n\n1. Steel beams\n\n2. Bolts\n\n"));
+  assert!(text.contains(
+    "Prices\n\nItem | Price\n\nSteel beams | 1,200 €\n\nPrices include delivery."
+  ));
+  assert!(text.ends_with("Notes\n\n[1] See the general terms of 2023."));
+
+  // Headers, footers, pictures and the theme aren't part of the text
+  assert!(!text.contains("Confidential"));
+  assert!(!text.contains("89504e47"));
+  assert!(!text.contains("Times New Roman"));
+}
+
+#[test]
+fn test_fixture_metadata() {
+  let metadata = rtf_metadata(FIXTURE).unwrap();
+  assert_eq!(metadata.title.as_deref(), Some("Supply Agreement"));
+  assert_eq!(metadata.author.as_deref(), Some("Jane Doe"));
+  assert_eq!(metadata.subject.as_deref(), Some("Terms of supply"));
+  assert_eq!(metadata.keywords.as_deref(), Some("contract, supply"));
+  assert_eq!(metadata.comment.as_deref(), Some("Draft for review"));
+  assert_eq!(metadata.company.as_deref(), Some("Acme GmbH"));
+  assert_eq!(metadata.created.as_deref(), Some("2024-03-07"));
+}
+
+#[test]
+fn test_outline_levels_and_hidden_text() {
+  let path = write_rtf(
+    "outline",
+    r"{\rtf1\ansi{\stylesheet{\s1 Kapitel;}}
+\pard\outlinelevel1 Einleitung\par
+\pard Text{\v  versteckt} bleibt.\par
+\pard\outlinelevel2 Details\par
+\pard\s1 Kein Titel\par}",
+  );
+  let sections = rtf_sections(path.to_str().unwrap()).unwrap();
+  assert_eq!(sections.len(), 1);
+  assert_eq!(sections[0].title.as_deref(), Some("Einleitung"));
+  assert_eq!(sections[0].level, 0);
+  assert_eq!(
+    sections[0].text,
+    "Einleitung\n\nText bleibt.\n\nDetails\n\nKein Titel"
+  );
+
+  std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_not_an_rtf() {
+  let result = rtf_to_text("../test-data/sample.txt");
+  assert!(matches!(result, Err(RtfError::InvalidRtf(_))));
+
+  let result = rtf_to_text("../test-data/rtf/missing.rtf");
+  assert!(matches!(result, Err(RtfError::FileNotFound(_))));
+}
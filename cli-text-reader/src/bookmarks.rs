@@ -7,6 +7,9 @@ use std::path::PathBuf;
 #[derive(Serialize, Deserialize, Default)]
 pub struct BookmarkData {
   pub marks: HashMap<char, (usize, usize)>, // mark -> (line, col)
+  /// Page of each mark, for paged documents such as PDFs
+  #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+  pub pages: HashMap<char, u32>,
 }
 
 fn get_bookmarks_path(
@@ -32,10 +35,12 @@ pub fn load_bookmarks(
 pub fn save_bookmarks(
   document_hash: u64,
   bookmarks: &HashMap<char, (usize, usize)>,
+  pages: &HashMap<char, u32>,
 ) -> Result<(), Box<dyn std::error::Error>> {
   let bookmarks_path = get_bookmarks_path(document_hash)?;
 
-  let bookmark_data = BookmarkData { marks: bookmarks.clone() };
+  let bookmark_data =
+    BookmarkData { marks: bookmarks.clone(), pages: pages.clone() };
 
   let content = serde_json::to_string_pretty(&bookmark_data)?;
   fs::write(bookmarks_path, content)?;
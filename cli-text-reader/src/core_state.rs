@@ -5,6 +5,7 @@ use std::time::Instant;
 
 use super::core_types::{BufferState, EditorState, ViewMode};
 use crate::demo_script::DemoScript;
+use crate::document_info::DocumentInfo;
 use crate::highlights::HighlightData;
 use crate::interactive_tutorial_buffer::TutorialSuccessCondition;
 
@@ -17,10 +18,13 @@ pub struct Editor {
   pub show_highlighter: bool,
   pub editor_state: EditorState,
   pub document_hash: u64,
+  pub document_info: DocumentInfo,
   pub total_lines: usize,
   #[allow(dead_code)]
   pub progress_display_until: Option<Instant>,
   pub show_progress: bool,
+  // Message on the left of the status line, cleared by the next key press
+  pub notice: Option<String>,
   pub cursor_x: usize,
   pub cursor_y: usize,
   pub clipboard: Option<Clipboard>,
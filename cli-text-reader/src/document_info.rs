@@ -0,0 +1,61 @@
+// Document structure passed in by the caller, beyond the justified lines
+
+/// A titled position in the document, such as a chapter
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+  pub title: String,
+  /// Index of the first justified line of the section
+  pub line: usize,
+  /// Nesting depth, 0 for top level sections
+  pub level: usize,
+}
+
+/// Where a page of a paged document, such as a PDF, begins
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page {
+  /// Page number as shown to the user
+  pub number: u32,
+  /// Index of the first justified line of the page
+  pub line: usize,
+}
+
+/// Terminal attributes of a run of text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextStyle {
+  pub bold: bool,
+  pub italic: bool,
+  /// Inline code and code blocks, drawn in a different color
+  pub code: bool,
+}
+
+/// A styled run of text on one justified line, such as a bold heading
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyledSpan {
+  /// Index of the justified line
+  pub line: usize,
+  /// Byte range of the run in the line
+  pub start: usize,
+  pub end: usize,
+  pub style: TextStyle,
+}
+
+/// What the reader knows about the document besides its text
+#[derive(Debug, Clone, Default)]
+pub struct DocumentInfo {
+  /// Sections in document order
+  pub sections: Vec<Section>,
+  /// Page starts in document order, empty for documents without pages
+  pub pages: Vec<Page>,
+  /// Title from the document metadata, shown instead of the hash
+  pub title: Option<String>,
+  pub author: Option<String>,
+  /// Further labelled fields for the `:info` overlay, e.g. publisher
+  pub details: Vec<(String, String)>,
+  /// Problems met while converting the document, such as pages that could
+  /// not be extracted. The first is shown on the status line when the
+  /// document opens, all of them are listed by `:info`.
+  pub warnings: Vec<String>,
+  /// Styled runs in line order, drawn with terminal attributes instead of
+  /// the markup they were read from. Runs on a line don't overlap.
+  pub styles: Vec<StyledSpan>,
+}
@@ -187,6 +187,17 @@ impl Editor {
       "nohl" | "nohlsearch" => self.handle_nohl_command(),
       "credits" | "author" => self.handle_credits_command(),
       "about" => self.handle_about_command(),
+      "info" => self.handle_info_command(),
+      "toc" => self.handle_toc_command(None),
+      cmd if cmd.starts_with("toc ") => {
+        let entry = cmd["toc ".len()..].trim().parse::<usize>().ok();
+        self.handle_toc_command(entry)
+      }
+      cmd if cmd.starts_with("page ") => {
+        let page = cmd["page ".len()..].trim().parse::<u32>().ok();
+        self.handle_page_command(page)
+      }
+      "marks" => self.handle_marks_command(),
       _ => {
         let result = handle_command(&cmd, &mut self.show_highlighter);
         if cmd == "z" {
@@ -383,6 +383,95 @@ impl Editor {
     Ok(false)
   }
 
+  // Handle :info command - show the document title, author and details
+  pub fn handle_info_command(
+    &mut self,
+  ) -> Result<bool, Box<dyn std::error::Error>> {
+    let info_lines = self.info_lines();
+    self.create_overlay("info", info_lines);
+    self.set_active_mode(EditorMode::Normal);
+    self.editor_state.command_buffer.clear();
+    self.editor_state.command_cursor_pos = 0;
+    if let Some(buffer) = self.buffers.get_mut(self.active_buffer) {
+      buffer.command_buffer.clear();
+      buffer.command_cursor_pos = 0;
+    }
+    Ok(false)
+  }
+
+  // Handle :page {n} command - jump to a page of a paged document
+  pub fn handle_page_command(
+    &mut self,
+    page: Option<u32>,
+  ) -> Result<bool, Box<dyn std::error::Error>> {
+    if let Some(page) = page {
+      self.jump_to_page(page);
+    }
+    self.set_active_mode(EditorMode::Normal);
+    self.editor_state.command_buffer.clear();
+    self.editor_state.command_cursor_pos = 0;
+    if let Some(buffer) = self.buffers.get_mut(self.active_buffer) {
+      buffer.command_buffer.clear();
+      buffer.command_cursor_pos = 0;
+    }
+    Ok(false)
+  }
+
+  // Handle :marks command - list bookmarks with their line and page
+  pub fn handle_marks_command(
+    &mut self,
+  ) -> Result<bool, Box<dyn std::error::Error>> {
+    let mut marks: Vec<_> =
+      self.marks.iter().map(|(&c, &pos)| (c, pos)).collect();
+    marks.sort();
+
+    let mut lines = vec!["━━━ Marks ━━━".to_string(), String::new()];
+    if marks.is_empty() {
+      lines.push("No marks set, use m{a-z} to set one".to_string());
+    }
+    for (mark, (line, _)) in marks {
+      let mut entry = format!("  {mark}  line {}", line + 1);
+      if let Some(page) = self.page_at_line(line) {
+        entry.push_str(&format!("  page {page}"));
+      }
+      lines.push(entry);
+    }
+    lines.push(String::new());
+    lines.push("Type :q to close this screen".to_string());
+
+    self.create_overlay("marks", lines);
+    self.set_active_mode(EditorMode::Normal);
+    self.editor_state.command_buffer.clear();
+    self.editor_state.command_cursor_pos = 0;
+    if let Some(buffer) = self.buffers.get_mut(self.active_buffer) {
+      buffer.command_buffer.clear();
+      buffer.command_cursor_pos = 0;
+    }
+    Ok(false)
+  }
+
+  // Handle :toc command - show table of contents, or jump to entry n with
+  // :toc n
+  pub fn handle_toc_command(
+    &mut self,
+    entry: Option<usize>,
+  ) -> Result<bool, Box<dyn std::error::Error>> {
+    match entry {
+      Some(n) if n > 0 => {
+        self.jump_to_section(n - 1);
+      }
+      _ => self.open_toc(),
+    }
+    self.set_active_mode(EditorMode::Normal);
+    self.editor_state.command_buffer.clear();
+    self.editor_state.command_cursor_pos = 0;
+    if let Some(buffer) = self.buffers.get_mut(self.active_buffer) {
+      buffer.command_buffer.clear();
+      buffer.command_cursor_pos = 0;
+    }
+    Ok(false)
+  }
+
   // Handle :notutorial command - permanently disable tutorial
   pub fn handle_notutorial_command(
     &mut self,
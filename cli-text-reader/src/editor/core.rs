@@ -82,9 +82,11 @@ impl Editor {
       show_highlighter: true,
       editor_state: EditorState::new(),
       document_hash,
+      document_info: Default::default(),
       total_lines,
       progress_display_until: None,
       show_progress: false,
+      notice: None,
       cursor_x: 0,
       cursor_y: height / 2,
       clipboard,
@@ -234,7 +236,12 @@ impl Editor {
   // Save bookmarks to file
   pub fn save_bookmarks(&self) {
     use crate::bookmarks::save_bookmarks;
-    if let Err(e) = save_bookmarks(self.document_hash, &self.marks) {
+    let pages = self
+      .marks
+      .iter()
+      .filter_map(|(&mark, &(line, _))| Some((mark, self.page_at_line(line)?)))
+      .collect();
+    if let Err(e) = save_bookmarks(self.document_hash, &self.marks, &pages) {
       self.debug_log_error(&format!("Failed to save bookmarks: {e}"));
     }
   }
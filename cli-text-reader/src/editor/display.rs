@@ -26,6 +26,7 @@ impl Editor {
       if line_idx < self.lines.len() {
         // We have a real line to display
         let line = self.lines[line_idx].clone();
+        let styles = self.line_styles(self.active_buffer, line_idx);
 
         // Highlight the current line first
         let is_current_line =
@@ -91,11 +92,13 @@ impl Editor {
               crossterm::style::Color::White
             )
           )?;
-          write!(stdout, "{center_offset_string}{line}")?;
+          write!(stdout, "{center_offset_string}")?;
+          self.write_styled_line(stdout, &line, styles, Color::White)?;
           execute!(stdout, crossterm::style::ResetColor)?;
           // Don't clear the line since we want to keep the background color
         } else {
-          write!(stdout, "{center_offset_string}{line}")?;
+          write!(stdout, "{center_offset_string}")?;
+          self.write_styled_line(stdout, &line, styles, Color::Reset)?;
           // Clear to end of line to avoid artifacts
           execute!(
             stdout,
@@ -161,6 +164,7 @@ impl Editor {
       if line_idx < self.lines.len() {
         // We have a real line to display
         let line = self.lines[line_idx].clone();
+        let styles = self.line_styles(self.active_buffer, line_idx);
 
         // Highlight the current line first
         let is_current_line =
@@ -228,11 +232,13 @@ impl Editor {
           buffer.queue(crossterm::style::SetForegroundColor(
             crossterm::style::Color::White,
           ))?;
-          write!(buffer, "{center_offset_string}{line}")?;
+          write!(buffer, "{center_offset_string}")?;
+          self.write_styled_line(buffer, &line, styles, Color::White)?;
           buffer.queue(crossterm::style::ResetColor)?;
           // Don't clear the line since we want to keep the background color
         } else {
-          write!(buffer, "{center_offset_string}{line}")?;
+          write!(buffer, "{center_offset_string}")?;
+          self.write_styled_line(buffer, &line, styles, Color::Reset)?;
           // Clear to end of line to avoid artifacts
           buffer.queue(crossterm::terminal::Clear(
             crossterm::terminal::ClearType::UntilNewLine,
@@ -324,6 +324,7 @@ impl Editor {
                   EditorMode::Tutorial => "Tutorial",
                 }
               ));
+              self.notice = None;
               let exit = self.handle_event(key_event, stdout)?;
 
               if exit {
@@ -400,6 +401,8 @@ impl Editor {
           self.total_lines,
           Some(self.offset),
           Some(self.cursor_y),
+          self.document_info.title.as_deref(),
+          self.page_at_line(current_line),
         )?;
         self.last_offset = current_line;
         self.last_saved_viewport_offset = self.offset;
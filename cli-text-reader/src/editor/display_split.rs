@@ -274,7 +274,9 @@ impl Editor {
           write!(stdout, "{line_to_render}")?;
         }
       } else {
-        write!(stdout, "{line_to_render}")?;
+        let styles = self.line_styles(buffer_idx, offset + viewport_line_idx);
+        write!(stdout, "{center_offset_string}")?;
+        self.write_styled_line(stdout, line, styles, Color::Reset)?;
       }
     }
 
@@ -576,7 +578,9 @@ impl Editor {
           write!(buffer, "{line_to_render}")?;
         }
       } else {
-        write!(buffer, "{line_to_render}")?;
+        let styles = self.line_styles(buffer_idx, offset + viewport_line_idx);
+        write!(buffer, "{center_offset_string}")?;
+        self.write_styled_line(buffer, line, styles, Color::Reset)?;
       }
     }
 
@@ -37,13 +37,16 @@ mod normal_navigation_find;
 mod normal_navigation_jumps;
 mod normal_search_visual;
 mod page_navigation;
+mod pages;
 mod screen_position;
 mod search_mode;
+mod sections;
 mod selection;
 mod selection_basic;
 mod selection_text;
 mod selection_words;
 mod status_line;
+mod styled_text;
 mod text_objects;
 mod text_objects_delimiters;
 mod text_objects_paragraphs;
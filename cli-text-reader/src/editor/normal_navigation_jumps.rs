@@ -2,7 +2,7 @@ use super::core::Editor;
 use crossterm::event::{self, Event as CEvent, KeyCode};
 
 impl Editor {
-  // Handle jump/goto keys (g/G/0/$^/%)
+  // Handle jump/goto keys (g/G/0/$^/%/]]/[[/Enter in the table of contents)
   pub fn handle_jump_keys(
     &mut self,
     key_code: KeyCode,
@@ -92,6 +92,35 @@ impl Editor {
         }
         Ok(Some(false))
       }
+      KeyCode::Char(bracket @ (']' | '[')) => {
+        // ']]' / '[[' - next / previous chapter
+        let inner_key = if self.tutorial_demo_mode {
+          if let Some(next_key) = self.check_demo_progress() {
+            next_key
+          } else {
+            return Ok(None);
+          }
+        } else {
+          match event::read()? {
+            CEvent::Key(k) => k,
+            _ => return Ok(None),
+          }
+        };
+        let count = self.number_prefix.parse::<usize>().unwrap_or(1).max(1);
+        self.number_prefix.clear();
+        if inner_key.code == KeyCode::Char(bracket) {
+          if bracket == ']' {
+            self.jump_to_next_section(count);
+          } else {
+            self.jump_to_previous_section(count);
+          }
+        }
+        Ok(Some(false))
+      }
+      KeyCode::Enter if self.in_toc_overlay() => {
+        self.jump_to_toc_entry_under_cursor();
+        Ok(Some(false))
+      }
       _ => Ok(None),
     }
   }
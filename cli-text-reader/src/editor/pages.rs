@@ -0,0 +1,84 @@
+use super::core::{Editor, ViewMode};
+
+impl Editor {
+  // Page the given line of the main document is on, for paged documents
+  pub fn page_at_line(&self, line: usize) -> Option<u32> {
+    let pages = &self.document_info.pages;
+    let index = pages.iter().rposition(|page| page.line <= line).unwrap_or(0);
+    pages.get(index).map(|page| page.number)
+  }
+
+  // Page the cursor is on
+  pub fn current_page(&self) -> Option<u32> {
+    self.page_at_line(self.main_buffer_line())
+  }
+
+  // Status line text such as "Page 37/120"
+  pub fn page_indicator(&self) -> Option<String> {
+    let last = self.document_info.pages.last()?.number;
+    Some(format!("Page {}/{}", self.current_page()?, last))
+  }
+
+  // Jump to the start of a page, or the last page when `number` is past the
+  // end, remembering where we came from for ''
+  pub fn jump_to_page(&mut self, number: u32) -> bool {
+    let pages = &self.document_info.pages;
+    let Some(line) = pages
+      .iter()
+      .find(|page| page.number >= number)
+      .or(pages.last())
+      .map(|page| page.line)
+    else {
+      return false;
+    };
+
+    if self.view_mode == ViewMode::Overlay {
+      self.close_overlay();
+    }
+
+    self.previous_position = Some(self.get_cursor_position());
+    self.goto_line_with_overscroll(line);
+    true
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::document_info::{DocumentInfo, Page};
+  use crate::editor::core::Editor;
+
+  fn editor_with_pages() -> Editor {
+    let lines = (0..300).map(|i| format!("line {i}")).collect();
+    let mut editor = Editor::new(lines, 80);
+    editor.document_info = DocumentInfo {
+      pages: (1..=3)
+        .map(|number| Page { number, line: (number as usize - 1) * 100 })
+        .collect(),
+      ..Default::default()
+    };
+    editor
+  }
+
+  #[test]
+  fn test_page_jumps() {
+    let mut editor = editor_with_pages();
+    assert_eq!(editor.page_indicator().as_deref(), Some("Page 1/3"));
+
+    assert!(editor.jump_to_page(2));
+    assert_eq!(editor.get_cursor_position().0, 100);
+    assert_eq!(editor.page_indicator().as_deref(), Some("Page 2/3"));
+
+    editor.goto_line_with_overscroll(250);
+    assert_eq!(editor.current_page(), Some(3));
+
+    assert!(editor.jump_to_page(99));
+    assert_eq!(editor.get_cursor_position().0, 200);
+  }
+
+  #[test]
+  fn test_no_pages() {
+    let mut editor = Editor::new(vec!["text".to_string()], 80);
+    assert_eq!(editor.page_indicator(), None);
+    assert!(!editor.jump_to_page(1));
+  }
+}
@@ -0,0 +1,282 @@
+use super::core::{Editor, ViewMode};
+
+// Header lines above the first entry of the :toc overlay
+const TOC_HEADER_LINES: usize = 2;
+
+impl Editor {
+  // Line of the main document the cursor is on, even while an overlay is open
+  pub fn main_buffer_line(&self) -> usize {
+    if self.active_buffer == 0 {
+      self.offset + self.cursor_y
+    } else {
+      self.buffers.first().map_or(0, |buffer| buffer.offset + buffer.cursor_y)
+    }
+  }
+
+  // Index of the section the cursor is in, if the document has sections
+  pub fn current_section_index(&self) -> Option<usize> {
+    let line = self.main_buffer_line();
+    self.document_info.sections.iter().rposition(|section| section.line <= line)
+  }
+
+  // Status line text such as "Chapter 4/22", or "Section 4/22" when the
+  // table of contents is nested
+  pub fn section_indicator(&self) -> Option<String> {
+    let sections = &self.document_info.sections;
+    let index = self.current_section_index()?;
+    let label = if sections.iter().any(|section| section.level > 0) {
+      "Section"
+    } else {
+      "Chapter"
+    };
+    Some(format!("{label} {}/{}", index + 1, sections.len()))
+  }
+
+  // Jump to the start of a section, remembering where we came from for ''
+  pub fn jump_to_section(&mut self, index: usize) -> bool {
+    let Some(line) =
+      self.document_info.sections.get(index).map(|section| section.line)
+    else {
+      return false;
+    };
+
+    if self.view_mode == ViewMode::Overlay {
+      self.close_overlay();
+    }
+
+    self.previous_position = Some(self.get_cursor_position());
+    self.goto_line_with_overscroll(line);
+    true
+  }
+
+  // ]] - jump `count` sections forward
+  pub fn jump_to_next_section(&mut self, count: usize) {
+    let line = self.main_buffer_line();
+    let next = self
+      .document_info
+      .sections
+      .iter()
+      .position(|section| section.line > line);
+    if let Some(next) = next {
+      let last = self.document_info.sections.len() - 1;
+      self.jump_to_section((next + count.saturating_sub(1)).min(last));
+    }
+  }
+
+  // [[ - jump to the start of the current section, or `count` sections back
+  // when already there
+  pub fn jump_to_previous_section(&mut self, count: usize) {
+    let line = self.main_buffer_line();
+    let Some(current) = self.current_section_index() else {
+      return;
+    };
+    let at_start = self.document_info.sections[current].line == line;
+    let target = if at_start {
+      current.saturating_sub(count)
+    } else {
+      current.saturating_sub(count - 1)
+    };
+    self.jump_to_section(target);
+  }
+
+  // Lines for the :toc overlay
+  pub fn toc_lines(&self) -> Vec<String> {
+    let sections = &self.document_info.sections;
+    if sections.is_empty() {
+      return vec![
+        "No table of contents available for this document".to_string(),
+        "".to_string(),
+        "Type :q to close this screen".to_string(),
+      ];
+    }
+
+    let current = self.current_section_index();
+    let number_width = sections.len().to_string().len();
+    let mut lines =
+      vec!["━━━ Table of Contents ━━━".to_string(), String::new()];
+    debug_assert_eq!(lines.len(), TOC_HEADER_LINES);
+    for (i, section) in sections.iter().enumerate() {
+      let marker = if Some(i) == current { ">" } else { " " };
+      lines.push(format!(
+        "{marker} {:>number_width$}. {}{}",
+        i + 1,
+        "  ".repeat(section.level),
+        section.title
+      ));
+    }
+    lines.push(String::new());
+    lines.push(
+      "Press Enter or type :toc {n} to jump to an entry, :q to close"
+        .to_string(),
+    );
+    lines
+  }
+
+  // Whether the :toc overlay is the active buffer
+  pub fn in_toc_overlay(&self) -> bool {
+    self.active_buffer > 0
+      && self
+        .buffers
+        .get(self.active_buffer)
+        .and_then(|buffer| buffer.command.as_deref())
+        == Some("toc")
+  }
+
+  // Open the :toc overlay with the cursor on the current section, or the
+  // first one when the cursor is before all sections
+  pub fn open_toc(&mut self) {
+    let current = self.current_section_index();
+    let has_sections = !self.document_info.sections.is_empty();
+    let toc_lines = self.toc_lines();
+    self.create_overlay("toc", toc_lines);
+    if has_sections {
+      self.goto_line_with_overscroll(current.unwrap_or(0) + TOC_HEADER_LINES);
+    }
+  }
+
+  // Enter in the :toc overlay - jump to the entry under the cursor
+  pub fn jump_to_toc_entry_under_cursor(&mut self) -> bool {
+    let line = self.offset + self.cursor_y;
+    match line.checked_sub(TOC_HEADER_LINES) {
+      Some(index) => self.jump_to_section(index),
+      None => false,
+    }
+  }
+
+  // Lines for the :info overlay
+  pub fn info_lines(&self) -> Vec<String> {
+    let info = &self.document_info;
+    let mut fields = Vec::new();
+    if let Some(title) = &info.title {
+      fields.push(("Title".to_string(), title.clone()));
+    }
+    if let Some(author) = &info.author {
+      fields.push(("Author".to_string(), author.clone()));
+    }
+    fields.extend(info.details.iter().cloned());
+    let total_lines = if self.active_buffer == 0 {
+      self.total_lines
+    } else {
+      self.buffers.first().map_or(0, |buffer| buffer.lines.len())
+    };
+    fields.push(("Lines".to_string(), total_lines.to_string()));
+    if !info.sections.is_empty() {
+      fields.push(("Chapters".to_string(), info.sections.len().to_string()));
+    }
+    for warning in &info.warnings {
+      fields.push(("Warning".to_string(), warning.clone()));
+    }
+
+    // Labels are padded to line up the values, counting the colon
+    let label_width = fields
+      .iter()
+      .map(|(label, _)| label.chars().count() + 1)
+      .max()
+      .unwrap_or(0);
+    let mut lines =
+      vec!["━━━ Document Information ━━━".to_string(), String::new()];
+    for (label, value) in fields {
+      lines.push(format!("  {:<label_width$}  {value}", format!("{label}:")));
+    }
+    lines.push(String::new());
+    lines.push("Type :q to close this screen".to_string());
+    lines
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::document_info::{DocumentInfo, Section};
+  use crate::editor::core::Editor;
+
+  fn editor_with_sections() -> Editor {
+    let lines = (0..300).map(|i| format!("line {i}")).collect();
+    let mut editor = Editor::new(lines, 80);
+    editor.document_info = DocumentInfo {
+      sections: [(0, "One"), (100, "Two"), (200, "Three")]
+        .into_iter()
+        .map(|(line, title)| Section {
+          title: title.to_string(),
+          line,
+          level: 0,
+        })
+        .collect(),
+      ..Default::default()
+    };
+    editor
+  }
+
+  #[test]
+  fn test_section_jumps() {
+    let mut editor = editor_with_sections();
+    assert_eq!(editor.section_indicator().as_deref(), Some("Chapter 1/3"));
+
+    editor.jump_to_next_section(1);
+    assert_eq!(editor.get_cursor_position().0, 100);
+    assert_eq!(editor.section_indicator().as_deref(), Some("Chapter 2/3"));
+
+    editor.jump_to_next_section(5);
+    assert_eq!(editor.get_cursor_position().0, 200);
+
+    editor.jump_to_previous_section(1);
+    assert_eq!(editor.get_cursor_position().0, 100);
+
+    editor.goto_line_with_overscroll(150);
+    editor.jump_to_previous_section(1);
+    assert_eq!(editor.get_cursor_position().0, 100);
+  }
+
+  #[test]
+  fn test_toc_marks_current_section() {
+    let mut editor = editor_with_sections();
+    editor.goto_line_with_overscroll(120);
+    let toc = editor.toc_lines();
+    assert!(toc.contains(&"> 2. Two".to_string()));
+    assert!(toc.contains(&"  3. Three".to_string()));
+  }
+
+  #[test]
+  fn test_enter_in_toc_jumps_to_entry() {
+    let mut editor = editor_with_sections();
+    editor.open_toc();
+    assert!(editor.in_toc_overlay());
+    assert_eq!(editor.offset + editor.cursor_y, 2);
+
+    editor.goto_line_with_overscroll(4);
+    assert!(editor.jump_to_toc_entry_under_cursor());
+    assert!(!editor.in_toc_overlay());
+    assert_eq!(editor.get_cursor_position().0, 200);
+  }
+
+  #[test]
+  fn test_nested_toc_is_labelled_by_section() {
+    let mut editor = editor_with_sections();
+    editor.document_info.sections[1].level = 1;
+    assert_eq!(editor.section_indicator().as_deref(), Some("Section 1/3"));
+    assert!(editor.toc_lines().contains(&"  2.   Two".to_string()));
+  }
+
+  #[test]
+  fn test_info_shows_metadata() {
+    let mut editor = editor_with_sections();
+    editor.document_info.title = Some("A Book".to_string());
+    editor.document_info.author = Some("Some Author".to_string());
+    editor
+      .document_info
+      .details
+      .push(("Language".to_string(), "en".to_string()));
+    editor
+      .document_info
+      .warnings
+      .push("Page 4 could not be extracted".to_string());
+
+    let info = editor.info_lines();
+    assert!(info.contains(&"  Title:     A Book".to_string()));
+    assert!(info.contains(&"  Author:    Some Author".to_string()));
+    assert!(info.contains(&"  Language:  en".to_string()));
+    assert!(info.contains(&"  Chapters:  3".to_string()));
+    assert!(
+      info.contains(&"  Warning:   Page 4 could not be extracted".to_string())
+    );
+  }
+}
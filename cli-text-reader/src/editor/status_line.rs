@@ -14,6 +14,12 @@ impl Editor {
 
     // Position info is now always hidden per user request
 
+    if let Some(message) = self.status_section_message() {
+      let x = self.width.saturating_sub(message.chars().count() + 2) as u16;
+      execute!(stdout, MoveTo(x, (self.height - 1) as u16))?;
+      write!(stdout, "{message}")?;
+    }
+
     // Show progress indicator if enabled, in normal view mode, and not in demo
     if self.show_progress
       && self.view_mode == super::core::ViewMode::Normal
@@ -25,6 +31,50 @@ impl Editor {
     Ok(())
   }
 
+  // Title, chapter and page position shown on the right of the status line
+  // in normal mode
+  fn status_section_message(&self) -> Option<String> {
+    if self.view_mode != super::core::ViewMode::Normal
+      || self.get_active_mode() != EditorMode::Normal
+      || self.tutorial_demo_mode
+    {
+      return None;
+    }
+
+    // Leave the left half of the line to the mode indicator
+    let max_title = (self.width / 2).saturating_sub(12);
+    let title = self
+      .document_info
+      .title
+      .as_deref()
+      .filter(|_| max_title > 0)
+      .map(|title| truncate_title(title, max_title));
+
+    let parts: Vec<String> =
+      [title, self.section_indicator(), self.page_indicator()]
+        .into_iter()
+        .flatten()
+        .collect();
+    (!parts.is_empty()).then(|| parts.join(" · "))
+  }
+
+  // Notice shown on the left of the status line in normal mode, such as
+  // pages that could not be extracted
+  fn status_notice(&self) -> Option<String> {
+    if self.view_mode != super::core::ViewMode::Normal
+      || self.tutorial_demo_mode
+    {
+      return None;
+    }
+    // Leave the right half of the line to the section message
+    let max = (self.width / 2).saturating_sub(2);
+    self
+      .notice
+      .as_deref()
+      .filter(|_| max > 0)
+      .map(|notice| truncate_title(notice, max))
+  }
+
   // Draw mode indicator in the status line
   fn draw_mode_indicator(&mut self, stdout: &mut io::Stdout) -> io::Result<()> {
     // Always use the active buffer's mode - this ensures command line is shown
@@ -111,6 +161,9 @@ impl Editor {
             crossterm::terminal::ClearType::CurrentLine
           )
         )?;
+        if let Some(notice) = self.status_notice() {
+          write!(stdout, "{notice}")?;
+        }
       }
     }
     Ok(())
@@ -196,6 +249,12 @@ impl Editor {
     // Draw mode indicators in the status line
     self.draw_mode_indicator_buffered(buffer)?;
 
+    if let Some(message) = self.status_section_message() {
+      let x = self.width.saturating_sub(message.chars().count() + 2) as u16;
+      buffer.queue(MoveTo(x, (self.height - 1) as u16))?;
+      write!(buffer, "{message}")?;
+    }
+
     // Show progress indicator if enabled, in normal view mode, and not in demo
     if self.show_progress
       && self.view_mode == super::core::ViewMode::Normal
@@ -240,6 +299,9 @@ impl Editor {
       }
       _ => {
         // Normal mode - just clear the line
+        if let Some(notice) = self.status_notice() {
+          write!(buffer, "{notice}")?;
+        }
       }
     }
 
@@ -284,3 +346,24 @@ impl Editor {
     Ok(())
   }
 }
+
+// Shorten a title to at most `max` characters, marking the cut with '…'
+fn truncate_title(title: &str, max: usize) -> String {
+  if title.chars().count() <= max {
+    return title.to_string();
+  }
+  let mut short: String = title.chars().take(max.saturating_sub(1)).collect();
+  short.push('…');
+  short
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_truncate_title() {
+    assert_eq!(truncate_title("Dune", 10), "Dune");
+    assert_eq!(truncate_title("The Left Hand of Darkness", 10), "The Left …");
+  }
+}
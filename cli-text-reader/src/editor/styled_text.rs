@@ -0,0 +1,136 @@
+use crossterm::{
+  QueueableCommand,
+  style::{Attribute, Color, SetAttribute, SetForegroundColor},
+};
+use std::io::{Result as IoResult, Write};
+
+use super::core::Editor;
+use crate::document_info::{StyledSpan, TextStyle};
+
+/// Color of inline code and code blocks
+const CODE_COLOR: Color = Color::Cyan;
+
+impl Editor {
+  // Styled runs of a line of the document, empty for the lines of command
+  // output and other buffers
+  pub(super) fn line_styles(
+    &self,
+    buffer_idx: usize,
+    line_idx: usize,
+  ) -> &[StyledSpan] {
+    if buffer_idx != 0 {
+      return &[];
+    }
+    let styles = &self.document_info.styles;
+    let first = styles.partition_point(|span| span.line < line_idx);
+    let count = styles[first..].partition_point(|span| span.line == line_idx);
+    &styles[first..first + count]
+  }
+
+  // Write a line with the terminal attributes of its styled runs, going
+  // back to `foreground` after each run of code
+  pub(super) fn write_styled_line<W: Write>(
+    &self,
+    out: &mut W,
+    line: &str,
+    styles: &[StyledSpan],
+    foreground: Color,
+  ) -> IoResult<()> {
+    let mut written = 0;
+    for span in styles {
+      let (Some(before), Some(text)) =
+        (line.get(written..span.start), line.get(span.start..span.end))
+      else {
+        continue;
+      };
+      write!(out, "{before}")?;
+      set_style(out, span.style)?;
+      write!(out, "{text}")?;
+      reset_style(out, span.style, foreground)?;
+      written = span.end;
+    }
+    write!(out, "{}", line.get(written..).unwrap_or_default())
+  }
+}
+
+fn set_style<W: Write>(out: &mut W, style: TextStyle) -> IoResult<()> {
+  if style.bold {
+    out.queue(SetAttribute(Attribute::Bold))?;
+  }
+  if style.italic {
+    out.queue(SetAttribute(Attribute::Italic))?;
+  }
+  if style.code {
+    out.queue(SetForegroundColor(CODE_COLOR))?;
+  }
+  Ok(())
+}
+
+// Undo only what `set_style` changed, so a highlighted background stays
+fn reset_style<W: Write>(
+  out: &mut W,
+  style: TextStyle,
+  foreground: Color,
+) -> IoResult<()> {
+  if style.bold {
+    out.queue(SetAttribute(Attribute::NormalIntensity))?;
+  }
+  if style.italic {
+    out.queue(SetAttribute(Attribute::NoItalic))?;
+  }
+  if style.code {
+    out.queue(SetForegroundColor(foreground))?;
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::document_info::DocumentInfo;
+
+  fn span(line: usize, start: usize, end: usize, bold: bool) -> StyledSpan {
+    let style = TextStyle { bold, code: !bold, ..Default::default() };
+    StyledSpan { line, start, end, style }
+  }
+
+  #[test]
+  fn test_styled_line_wraps_runs_in_attributes() {
+    let mut editor = Editor::new(vec!["Use `cargo` here".to_string()], 80);
+    editor.document_info = DocumentInfo {
+      styles: vec![span(0, 4, 11, false)],
+      ..Default::default()
+    };
+
+    let styles = editor.line_styles(0, 0);
+    assert_eq!(styles.len(), 1);
+    assert!(editor.line_styles(1, 0).is_empty());
+
+    let mut out = Vec::new();
+    editor
+      .write_styled_line(&mut out, "Use `cargo` here", styles, Color::Reset)
+      .unwrap();
+    let out = String::from_utf8(out).unwrap();
+    let code = out.find("`cargo`").unwrap();
+    assert!(out[..code].starts_with("Use "));
+    assert!(out[..code].contains("\x1b[38;5;14m"));
+    assert!(out[code..].contains("\x1b[39m"));
+    assert!(out.ends_with(" here"));
+  }
+
+  #[test]
+  fn test_line_styles_finds_runs_of_a_line() {
+    let mut editor = Editor::new(vec![String::new(); 3], 80);
+    editor.document_info = DocumentInfo {
+      styles: vec![
+        span(0, 0, 2, true),
+        span(2, 0, 1, true),
+        span(2, 3, 5, false),
+      ],
+      ..Default::default()
+    };
+    assert_eq!(editor.line_styles(0, 0).len(), 1);
+    assert!(editor.line_styles(0, 1).is_empty());
+    assert_eq!(editor.line_styles(0, 2).len(), 2);
+  }
+}
@@ -44,6 +44,8 @@ pub fn get_help_text() -> Vec<String> {
     "    }           Next paragraph (blank line)".to_string(),
     "    (           Previous sentence".to_string(),
     "    )           Next sentence".to_string(),
+    "    ]]          Next chapter".to_string(),
+    "    [[          Start of chapter, or previous chapter".to_string(),
     "".to_string(),
     "  Screen Position:".to_string(),
     "    H           Move to top of screen".to_string(),
@@ -131,6 +133,14 @@ pub fn get_help_text() -> Vec<String> {
     "    :nohl, :nohlsearch     Clear search highlighting".to_string(),
     "    :credits, :author      Show credits".to_string(),
     "    :about                 Show about information".to_string(),
+    "    :info                  Show document title, author and details"
+      .to_string(),
+    "    :toc                   Show table of contents (Enter jumps to entry)"
+      .to_string(),
+    "    :toc {n}               Jump to table of contents entry n".to_string(),
+    "    :page {n}              Jump to page n (PDF documents)".to_string(),
+    "    :marks                 List marks with their line and page"
+      .to_string(),
     "    :!{cmd}                Execute shell command (opens in split view)"
       .to_string(),
     "".to_string(),
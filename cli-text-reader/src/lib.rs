@@ -8,6 +8,7 @@ mod demo_content;
 pub mod demo_registry;
 pub mod demo_script;
 mod demo_tutorial_test;
+mod document_info;
 mod editor;
 mod help;
 mod highlights;
@@ -18,11 +19,14 @@ mod interactive_tutorial_buffer;
 mod interactive_tutorial_steps;
 mod interactive_tutorial_tests;
 mod interactive_tutorial_utils;
+mod password;
 mod progress;
 mod tutorial;
 mod utils;
 
+pub use document_info::{DocumentInfo, Page, Section, StyledSpan, TextStyle};
 use editor::Editor;
+pub use password::prompt_password;
 
 pub fn run_cli_text_reader(
   lines: Vec<String>,
@@ -44,6 +48,22 @@ pub fn run_cli_text_reader_with_content(
   col: usize,
   raw_content: Option<String>,
   demo_mode: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+  run_cli_text_reader_with_info(
+    lines,
+    col,
+    raw_content,
+    DocumentInfo::default(),
+    demo_mode,
+  )
+}
+
+pub fn run_cli_text_reader_with_info(
+  lines: Vec<String>,
+  col: usize,
+  raw_content: Option<String>,
+  document_info: DocumentInfo,
+  demo_mode: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
   // Initialize debug logging
   debug::init_debug_logging()?;
@@ -54,6 +74,16 @@ pub fn run_cli_text_reader_with_content(
   if raw_content.is_some() {
     debug::debug_log("main", "Raw content provided for consistent hashing");
   }
+  debug::debug_log_state(
+    "main",
+    "sections_count",
+    &document_info.sections.len().to_string(),
+  );
+  debug::debug_log_state(
+    "main",
+    "pages_count",
+    &document_info.pages.len().to_string(),
+  );
 
   let mut editor = if let Some(content) = raw_content {
     Editor::new_with_content(lines, col, content)
@@ -61,6 +91,14 @@ pub fn run_cli_text_reader_with_content(
     Editor::new(lines, col)
   };
   editor.tutorial_demo_mode = demo_mode;
+  editor.notice = match document_info.warnings.as_slice() {
+    [] => None,
+    [warning] => Some(warning.clone()),
+    [warning, rest @ ..] => {
+      Some(format!("{warning} (+{} more, see :info)", rest.len()))
+    }
+  };
+  editor.document_info = document_info;
   let result = editor.run();
 
   debug::debug_log("main", "Editor run completed");
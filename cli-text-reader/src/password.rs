@@ -0,0 +1,46 @@
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+use std::io::{self, Write};
+
+/// Ask for a password on the terminal without echoing it
+///
+/// Meant to run before the reader takes over the screen, e.g. to unlock an
+/// encrypted document. Returns `None` when the prompt is cancelled with Esc
+/// or Ctrl-C.
+pub fn prompt_password(prompt: &str) -> io::Result<Option<String>> {
+  let mut stderr = io::stderr();
+  write!(stderr, "{prompt}")?;
+  stderr.flush()?;
+
+  terminal::enable_raw_mode()?;
+  let password = read_password();
+  terminal::disable_raw_mode()?;
+  writeln!(stderr)?;
+
+  password
+}
+
+fn read_password() -> io::Result<Option<String>> {
+  let mut password = String::new();
+  loop {
+    let Event::Key(key) = event::read()? else {
+      continue;
+    };
+    // On Windows, crossterm sends both Press and Release events
+    if key.kind != KeyEventKind::Press {
+      continue;
+    }
+    match key.code {
+      KeyCode::Enter => return Ok(Some(password)),
+      KeyCode::Esc => return Ok(None),
+      KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+        return Ok(None);
+      }
+      KeyCode::Backspace => {
+        password.pop();
+      }
+      KeyCode::Char(c) => password.push(c),
+      _ => {}
+    }
+  }
+}
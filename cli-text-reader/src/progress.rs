@@ -18,6 +18,10 @@ pub struct Progress {
   pub viewport_offset: Option<usize>,
   #[serde(default)]
   pub cursor_y: Option<usize>,
+  #[serde(default)]
+  pub title: Option<String>,
+  #[serde(default)]
+  pub page: Option<u32>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -32,6 +36,12 @@ enum Event {
     viewport_offset: Option<usize>,
     #[serde(default)]
     cursor_y: Option<usize>,
+    /// Document title, so the log can be read without knowing the hashes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    /// Page the reader was on, for paged documents such as PDFs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    page: Option<u32>,
   },
 }
 
@@ -51,7 +61,15 @@ pub fn save_progress(
   offset: usize,
   total_lines: usize,
 ) -> Result<(), Box<dyn std::error::Error>> {
-  save_progress_with_viewport(document_hash, offset, total_lines, None, None)
+  save_progress_with_viewport(
+    document_hash,
+    offset,
+    total_lines,
+    None,
+    None,
+    None,
+    None,
+  )
 }
 
 pub fn save_progress_with_viewport(
@@ -60,6 +78,8 @@ pub fn save_progress_with_viewport(
   total_lines: usize,
   viewport_offset: Option<usize>,
   cursor_y: Option<usize>,
+  title: Option<&str>,
+  page: Option<u32>,
 ) -> Result<(), Box<dyn std::error::Error>> {
   let percentage = (offset as f64 / total_lines as f64) * 100.0;
   let event = Event::UpdateProgress {
@@ -70,6 +90,8 @@ pub fn save_progress_with_viewport(
     percentage,
     viewport_offset,
     cursor_y,
+    title: title.map(str::to_string),
+    page,
   };
   let serialized = serde_json::to_string(&event)?;
   let progress_file_path = get_progress_file_path()?;
@@ -99,6 +121,8 @@ pub fn load_progress(
       percentage,
       viewport_offset,
       cursor_y,
+      title,
+      page,
       ..
     } = event;
 
@@ -110,6 +134,8 @@ pub fn load_progress(
         percentage,
         viewport_offset,
         cursor_y,
+        title,
+        page,
       });
     }
   }
@@ -145,6 +171,8 @@ mod tests {
       percentage,
       viewport_offset: None,
       cursor_y: None,
+      title: Some("Test Document".to_string()),
+      page: Some(3),
     };
 
     let serialized = serde_json::to_string(&event).unwrap();
@@ -166,6 +194,8 @@ mod tests {
         percentage,
         viewport_offset,
         cursor_y,
+        title,
+        page,
         ..
       } = event;
 
@@ -177,6 +207,8 @@ mod tests {
           percentage,
           viewport_offset,
           cursor_y,
+          title,
+          page,
         });
       }
     }
@@ -187,5 +219,15 @@ mod tests {
     assert_eq!(progress.offset, test_offset);
     assert_eq!(progress.total_lines, test_total_lines);
     assert_eq!(progress.percentage, 50.0);
+    assert_eq!(progress.title.as_deref(), Some("Test Document"));
+    assert_eq!(progress.page, Some(3));
+  }
+
+  #[test]
+  fn test_progress_without_title_still_loads() {
+    let line = r#"{"UpdateProgress":{"timestamp":"2024-01-01T00:00:00Z","document_hash":1,"offset":2,"total_lines":4,"percentage":50.0}}"#;
+    let event: Event = serde_json::from_str(line).unwrap();
+    let Event::UpdateProgress { title, .. } = event;
+    assert_eq!(title, None);
   }
 }
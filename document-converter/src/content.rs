@@ -0,0 +1,287 @@
+use serde::{Deserialize, Serialize};
+
+/// Width of a tab in the indentation of plain text
+const TAB_WIDTH: usize = 4;
+
+/// Text produced by a converter
+///
+/// Converters that know where chapters begin return one part per chapter,
+/// paged formats return one part per page, and everything else returns a
+/// single untitled part.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Content {
+  pub parts: Vec<Part>,
+  pub metadata: Metadata,
+  /// The document's own table of contents, such as a PDF outline
+  pub toc: Vec<TocEntry>,
+  /// Problems that didn't stop the conversion, such as pages that could not
+  /// be extracted, one line each
+  pub warnings: Vec<String>,
+}
+
+/// A table of contents entry pointing at a page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TocEntry {
+  pub title: String,
+  /// Page the entry points to, matching [`Part::page`]
+  pub page: u32,
+  /// Nesting depth, 0 for top level entries
+  pub level: usize,
+}
+
+/// Bibliographic data a converter found in the document, if any
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Metadata {
+  pub title: Option<String>,
+  pub authors: Vec<String>,
+  pub language: Option<String>,
+  pub publisher: Option<String>,
+  /// ISBN or other identifier of the work
+  pub identifier: Option<String>,
+}
+
+/// A chapter, page or other stretch of text
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Part {
+  pub title: Option<String>,
+  /// Nesting depth of the title, 0 for top level parts
+  pub level: usize,
+  /// Page number, for formats with fixed pages such as PDF
+  pub page: Option<u32>,
+  /// Plain text with paragraphs separated by blank lines
+  pub text: String,
+  /// Structure of the text, which is what gets laid out for reading. Empty
+  /// when the text is justified as plain paragraphs.
+  pub blocks: Vec<Block>,
+}
+
+/// A heading, paragraph or other block of a part with known structure
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Block {
+  /// A heading, `level` 0 for top level headings
+  Heading {
+    level: usize,
+    spans: Vec<Span>,
+  },
+  Paragraph(Vec<Span>),
+  /// A list item, `marker` is its bullet or number, e.g. `•` or `3.`, and
+  /// empty for the item's later paragraphs
+  ListItem {
+    depth: usize,
+    marker: String,
+    spans: Vec<Span>,
+  },
+  /// Preformatted text that is shown as it is
+  CodeBlock(String),
+  Quote(Vec<Span>),
+  /// Rows of cells, the first row is the header row if `header` is set
+  Table {
+    header: bool,
+    rows: Vec<Vec<Vec<Span>>>,
+  },
+  /// An image that can't be shown, `alt` is its description, if any
+  Image {
+    alt: String,
+  },
+  /// The page with this number starts here, for paged text that isn't split
+  /// into a part per page
+  PageBreak(u32),
+}
+
+/// A run of text and how it is emphasized
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Span {
+  pub text: String,
+  pub style: SpanStyle,
+}
+
+#[derive(
+  Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub struct SpanStyle {
+  pub strong: bool,
+  pub emphasis: bool,
+  pub code: bool,
+}
+
+impl Content {
+  /// The whole text, with parts separated by blank lines
+  pub fn text(&self) -> String {
+    self
+      .parts
+      .iter()
+      .map(|part| part.text.as_str())
+      .collect::<Vec<_>>()
+      .join("\n\n")
+  }
+}
+
+impl Part {
+  /// A part of plain text with paragraphs separated by blank lines
+  ///
+  /// The first paragraph is read as the heading when it is the title, as
+  /// converters that split documents into sections start their text with
+  /// it. Form feeds start a new page, as in printed manuals and RFCs.
+  /// Paragraphs keep the indentation all their lines share, as the leading
+  /// spaces of their text.
+  pub fn from_text(title: Option<String>, level: usize, text: String) -> Self {
+    let mut blocks = Vec::new();
+    let mut first = true;
+    let pages: Vec<&str> = text.split('\x0c').collect();
+    for (number, page) in (1..).zip(&pages) {
+      if pages.len() > 1 {
+        blocks.push(Block::PageBreak(number));
+      }
+      for paragraph in page.split("\n\n") {
+        let words: Vec<&str> = paragraph.split_whitespace().collect();
+        if words.is_empty() {
+          continue;
+        }
+        let indent = paragraph
+          .lines()
+          .filter(|line| !line.trim().is_empty())
+          .map(|line| {
+            line
+              .chars()
+              .take_while(|c| matches!(c, ' ' | '\t'))
+              .map(|c| if c == '\t' { TAB_WIDTH } else { 1 })
+              .sum::<usize>()
+          })
+          .min()
+          .unwrap_or(0);
+        let text = format!("{}{}", " ".repeat(indent), words.join(" "));
+        let spans = vec![Span::plain(&text)];
+        let is_title = first
+          && title.as_ref().is_some_and(|title| {
+            title.split_whitespace().eq(words.iter().copied())
+          });
+        first = false;
+        blocks.push(if is_title {
+          Block::Heading { level, spans }
+        } else {
+          Block::Paragraph(spans)
+        });
+      }
+    }
+    Self { title, level, page: None, text, blocks }
+  }
+}
+
+impl Block {
+  /// The block as plain text, with list markers and indentation
+  ///
+  /// Table rows are separated by blank lines like paragraphs, and page
+  /// breaks have no text.
+  pub fn text(&self) -> String {
+    let text = |spans: &[Span]| {
+      spans.iter().map(|span| span.text.as_str()).collect::<String>()
+    };
+    match self {
+      Block::Heading { spans, .. }
+      | Block::Paragraph(spans)
+      | Block::Quote(spans) => text(spans),
+      Block::ListItem { depth, marker, spans } if marker.is_empty() => {
+        format!("{}{}", "  ".repeat(depth + 1), text(spans))
+      }
+      Block::ListItem { depth, marker, spans } => {
+        format!("{}{marker} {}", "  ".repeat(*depth), text(spans))
+      }
+      Block::CodeBlock(code) => code.clone(),
+      Block::Table { rows, .. } => rows
+        .iter()
+        .map(|row| {
+          row.iter().map(|cell| text(cell)).collect::<Vec<_>>().join(" | ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n"),
+      Block::Image { alt } if alt.is_empty() => "[image]".to_string(),
+      Block::Image { alt } => format!("[{alt}]"),
+      Block::PageBreak(_) => String::new(),
+    }
+  }
+}
+
+impl Span {
+  pub fn plain(text: &str) -> Self {
+    Self { text: text.to_string(), style: SpanStyle::default() }
+  }
+}
+
+impl From<String> for Content {
+  fn from(text: String) -> Self {
+    Self { parts: vec![Part::from_text(None, 0, text)], ..Default::default() }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_text_joins_parts_with_blank_lines() {
+    let content = Content {
+      parts: vec![
+        Part {
+          title: Some("One".to_string()),
+          level: 0,
+          page: None,
+          text: "first".to_string(),
+          blocks: Vec::new(),
+        },
+        Part {
+          title: None,
+          level: 0,
+          page: None,
+          text: "second".to_string(),
+          blocks: Vec::new(),
+        },
+      ],
+      ..Default::default()
+    };
+    assert_eq!(content.text(), "first\n\nsecond");
+    assert_eq!(Content::from("plain\n".to_string()).text(), "plain\n");
+  }
+
+  #[test]
+  fn test_part_from_text() {
+    let text = "Terms\n\nThe  supplier\ndelivers.\n\n\n\x0cPage two.\n\n  \
+                Quoted\n\tand indented.";
+    let part = Part::from_text(Some("Terms".to_string()), 1, text.to_string());
+    assert_eq!(part.text, text);
+    assert_eq!(
+      part.blocks,
+      [
+        Block::PageBreak(1),
+        Block::Heading { level: 1, spans: vec![Span::plain("Terms")] },
+        Block::Paragraph(vec![Span::plain("The supplier delivers.")]),
+        Block::PageBreak(2),
+        Block::Paragraph(vec![Span::plain("Page two.")]),
+        Block::Paragraph(vec![Span::plain("  Quoted and indented.")]),
+      ]
+    );
+
+    // Only a first paragraph that is the title is a heading
+    let part = Part::from_text(Some("Notes".to_string()), 0, "Terms".into());
+    assert_eq!(part.blocks, [Block::Paragraph(vec![Span::plain("Terms")])]);
+  }
+
+  #[test]
+  fn test_block_text() {
+    let table = Block::Table {
+      header: true,
+      rows: vec![
+        vec![vec![Span::plain("Item")], vec![Span::plain("Price")]],
+        vec![vec![Span::plain("Bolts")], vec![Span::plain("3 €")]],
+      ],
+    };
+    assert_eq!(table.text(), "Item | Price\n\nBolts | 3 €");
+    assert_eq!(Block::Image { alt: String::new() }.text(), "[image]");
+    assert_eq!(Block::Image { alt: "Chart".to_string() }.text(), "[Chart]");
+    let item = Block::ListItem {
+      depth: 1,
+      marker: String::new(),
+      spans: vec![Span::plain("more")],
+    };
+    assert_eq!(item.text(), "    more");
+  }
+}
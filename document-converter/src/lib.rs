@@ -0,0 +1,257 @@
+//! # Document Converter
+//!
+//! A small extension point for turning input files into plain text.
+//!
+//! ## Features
+//! - `DocumentConverter` trait for implementing new input formats
+//! - `ConverterRegistry` that picks converters by how well they match an input
+//! - Chapter aware output for converters that know the document structure
+//! - A shared document model of headings, paragraphs, lists, code, tables,
+//!   images and page breaks, so structure survives until it is laid out
+//! - Magic-byte sniffing, so misnamed or extensionless files still find their
+//!   converter
+//! - Fallback chains with a record of every attempt and why it was made
+//!
+//! ## Usage
+//! ```rust
+//! use document_converter::{
+//!   Capabilities, Content, ConverterRegistry, DocumentConverter, Input,
+//! };
+//!
+//! struct UpperCase;
+//!
+//! impl DocumentConverter for UpperCase {
+//!   fn name(&self) -> &'static str {
+//!     "upper"
+//!   }
+//!
+//!   fn version(&self) -> &'static str {
+//!     env!("CARGO_PKG_VERSION")
+//!   }
+//!
+//!   fn capabilities(&self) -> Capabilities {
+//!     Capabilities {
+//!       description: "Shouting text files",
+//!       extensions: &["upper"],
+//!       formats: &[],
+//!       requires: None,
+//!     }
+//!   }
+//!
+//!   fn convert(
+//!     &self,
+//!     input: &Input,
+//!   ) -> Result<Content, Box<dyn std::error::Error>> {
+//!     Ok(std::fs::read_to_string(input.path())?.to_uppercase().into())
+//!   }
+//! }
+//!
+//! let mut registry = ConverterRegistry::new();
+//! registry.register(UpperCase);
+//!
+//! match registry.convert(&Input::new("path/to/notes.upper")) {
+//!   Ok(conversion) => println!("{}", conversion.content.text()),
+//!   Err(e) => eprintln!("Error: {e}"),
+//! }
+//! ```
+//!
+//! ## Registering converters
+//! Each program that opens files builds its own registry, as hygg does with
+//! the converters it ships. cli-text-reader only displays the text it is
+//! handed and never opens documents itself, so it has no registry to add
+//! to: a program embedding it converts files with its own registry and
+//! passes the text on. hygg is a binary, so its converters are fixed when
+//! it is built, and a third-party crate adds its converter to the registry
+//! of the program using it.
+
+mod content;
+mod registry;
+mod sniff;
+
+pub use content::{Block, Content, Metadata, Part, Span, SpanStyle, TocEntry};
+pub use registry::{
+  Attempt, Candidate, Conversion, ConversionError, ConverterRegistry,
+};
+pub use sniff::{Format, sniff_bytes, sniff_path};
+
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// How strongly a converter claims an input
+///
+/// Variants are ordered from weakest to strongest, so candidates can be
+/// sorted by their detection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Detection {
+  /// The converter cannot handle the input
+  Unsupported,
+  /// Nothing identifies the input, but the converter is worth a try
+  Fallback,
+  /// The file extension is one the converter handles
+  Extension,
+  /// The file content is in a format the converter handles
+  Content,
+}
+
+impl fmt::Display for Detection {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Detection::Unsupported => write!(f, "unsupported"),
+      Detection::Fallback => write!(f, "fallback"),
+      Detection::Extension => write!(f, "extension match"),
+      Detection::Content => write!(f, "content match"),
+    }
+  }
+}
+
+/// Static description of what a converter can read
+#[derive(Debug, Clone)]
+pub struct Capabilities {
+  /// Human readable summary shown in converter listings
+  pub description: &'static str,
+  /// Lowercase file extensions the converter handles, without the dot
+  pub extensions: &'static [&'static str],
+  /// Sniffed formats the converter handles regardless of the file name
+  pub formats: &'static [Format],
+  /// External program the converter shells out to, if any
+  pub requires: Option<&'static str>,
+}
+
+impl Capabilities {
+  /// Whether the external program this converter needs can be found
+  pub fn is_available(&self) -> bool {
+    self.requires.is_none_or(|binary| which(binary).is_some())
+  }
+}
+
+/// A file to be converted
+#[derive(Debug, Clone)]
+pub struct Input {
+  path: PathBuf,
+  extension: Option<String>,
+  format: Format,
+}
+
+impl Input {
+  pub fn new(path: impl AsRef<Path>) -> Self {
+    let path = path.as_ref().to_path_buf();
+    let extension = path
+      .extension()
+      .and_then(|ext| ext.to_str())
+      .map(|ext| ext.to_lowercase());
+    // Unreadable files are left for the converters to report on
+    let format = sniff_path(&path).unwrap_or(Format::Unknown);
+
+    Self { path, extension, format }
+  }
+
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// The path as a string, for converters that take `&str` paths
+  pub fn path_str(&self) -> String {
+    self.path.to_string_lossy().into_owned()
+  }
+
+  /// The lowercase file extension, without the dot
+  pub fn extension(&self) -> Option<&str> {
+    self.extension.as_deref()
+  }
+
+  /// The format identified from the leading bytes of the file
+  pub fn format(&self) -> Format {
+    self.format
+  }
+}
+
+/// A converter from some input format to plain text
+///
+/// Implementors only need to describe themselves and convert; the default
+/// `detect` claims inputs whose sniffed format or extension is listed in
+/// `capabilities` and declines everything else.
+pub trait DocumentConverter: Send + Sync {
+  /// Short unique name, e.g. `"pdf"`
+  fn name(&self) -> &'static str;
+
+  /// Version of the code doing the conversion, e.g. of the library it
+  /// wraps, so stored conversions can tell when they are out of date
+  fn version(&self) -> &'static str;
+
+  /// What this converter can read
+  fn capabilities(&self) -> Capabilities;
+
+  /// How strongly this converter claims `input`
+  fn detect(&self, input: &Input) -> Detection {
+    let capabilities = self.capabilities();
+    if capabilities.formats.contains(&input.format()) {
+      return Detection::Content;
+    }
+    match input.extension() {
+      Some(ext) if capabilities.extensions.contains(&ext) => {
+        Detection::Extension
+      }
+      _ => Detection::Unsupported,
+    }
+  }
+
+  /// Convert `input` to plain text with paragraphs separated by blank lines
+  fn convert(&self, input: &Input) -> Result<Content, Box<dyn Error>>;
+}
+
+/// Find `binary` on the `PATH`
+pub fn which(binary: &str) -> Option<PathBuf> {
+  if let Ok(paths) = env::var("PATH") {
+    for path in env::split_paths(&paths) {
+      let full_path = path.join(binary);
+      if full_path.is_file() {
+        return Some(full_path);
+      }
+    }
+  }
+  None
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_input_extension_is_lowercase() {
+    let input = Input::new("books/Manual.PDF");
+    assert_eq!(input.extension(), Some("pdf"));
+    assert_eq!(input.path_str(), "books/Manual.PDF");
+
+    let input = Input::new("README");
+    assert_eq!(input.extension(), None);
+    assert_eq!(input.format(), Format::Unknown);
+  }
+
+  #[test]
+  fn test_detection_ordering() {
+    assert!(Detection::Content > Detection::Extension);
+    assert!(Detection::Extension > Detection::Fallback);
+    assert!(Detection::Fallback > Detection::Unsupported);
+  }
+
+  #[test]
+  fn test_capabilities_without_requirement_are_available() {
+    let capabilities = Capabilities {
+      description: "test",
+      extensions: &[],
+      formats: &[],
+      requires: None,
+    };
+    assert!(capabilities.is_available());
+
+    let capabilities = Capabilities {
+      description: "test",
+      extensions: &[],
+      formats: &[],
+      requires: Some("definitely-not-an-installed-binary"),
+    };
+    assert!(!capabilities.is_available());
+  }
+}
@@ -0,0 +1,321 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::{Content, Detection, DocumentConverter, Format, Input};
+
+/// A converter that claimed an input, and how strongly
+pub struct Candidate<'a> {
+  pub converter: &'a dyn DocumentConverter,
+  pub detection: Detection,
+}
+
+/// A converter that was tried and failed
+#[derive(Debug, Clone)]
+pub struct Attempt {
+  pub converter: &'static str,
+  pub detection: Detection,
+  pub error: String,
+}
+
+impl fmt::Display for Attempt {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} ({}): {}", self.converter, self.detection, self.error)
+  }
+}
+
+/// The result of a successful conversion
+#[derive(Debug)]
+pub struct Conversion {
+  pub content: Content,
+  /// Name of the converter that produced `content`
+  pub converter: &'static str,
+  /// Why that converter was picked
+  pub detection: Detection,
+  /// Converters that were tried first and failed
+  pub failed: Vec<Attempt>,
+}
+
+/// Error returned when no registered converter could read an input
+#[derive(Debug)]
+pub enum ConversionError {
+  NoConverter { path: String, format: Format },
+  AllFailed { format: Format, attempts: Vec<Attempt> },
+}
+
+impl ConversionError {
+  /// The sniffed format of the input that could not be converted
+  pub fn format(&self) -> Format {
+    match self {
+      ConversionError::NoConverter { format, .. }
+      | ConversionError::AllFailed { format, .. } => *format,
+    }
+  }
+}
+
+impl fmt::Display for ConversionError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ConversionError::NoConverter { path, format } => {
+        write!(f, "No converter available for {format}: {path}")
+      }
+      ConversionError::AllFailed { attempts, .. } => {
+        write!(f, "All converters failed:")?;
+        for attempt in attempts {
+          write!(f, "\n  {attempt}")?;
+        }
+        Ok(())
+      }
+    }
+  }
+}
+
+impl Error for ConversionError {}
+
+/// An ordered collection of converters
+///
+/// Registration order matters: converters with the same detection are tried
+/// in the order they were registered.
+#[derive(Default)]
+pub struct ConverterRegistry {
+  converters: Vec<Box<dyn DocumentConverter>>,
+}
+
+impl ConverterRegistry {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Add a converter, replacing any registered converter with the same name
+  pub fn register<C: DocumentConverter + 'static>(
+    &mut self,
+    converter: C,
+  ) -> &mut Self {
+    self.converters.retain(|existing| existing.name() != converter.name());
+    self.converters.push(Box::new(converter));
+    self
+  }
+
+  pub fn converters(&self) -> impl Iterator<Item = &dyn DocumentConverter> {
+    self.converters.iter().map(|converter| converter.as_ref())
+  }
+
+  pub fn get(&self, name: &str) -> Option<&dyn DocumentConverter> {
+    self.converters().find(|converter| converter.name() == name)
+  }
+
+  /// Converters that claim `input`, in the order they will be tried
+  ///
+  /// When any converter positively identifies the input, or its content has
+  /// a signature no fallback could make sense of, converters that would only
+  /// be tried as a fallback are left out.
+  pub fn candidates(&self, input: &Input) -> Vec<Candidate<'_>> {
+    let mut candidates: Vec<Candidate<'_>> = self
+      .converters()
+      .map(|converter| Candidate {
+        converter,
+        detection: converter.detect(input),
+      })
+      .filter(|candidate| candidate.detection > Detection::Unsupported)
+      .collect();
+
+    // Stable sort keeps registration order within the same detection
+    candidates.sort_by_key(|candidate| std::cmp::Reverse(candidate.detection));
+
+    if !input.format().allows_fallback()
+      || candidates
+        .first()
+        .is_some_and(|best| best.detection > Detection::Fallback)
+    {
+      candidates.retain(|candidate| candidate.detection > Detection::Fallback);
+    }
+
+    candidates
+  }
+
+  /// Convert `input` with the first candidate that succeeds
+  pub fn convert(&self, input: &Input) -> Result<Conversion, ConversionError> {
+    let candidates = self.candidates(input);
+    if candidates.is_empty() {
+      return Err(ConversionError::NoConverter {
+        path: input.path_str(),
+        format: input.format(),
+      });
+    }
+
+    let mut failed = Vec::new();
+    for candidate in candidates {
+      match candidate.converter.convert(input) {
+        Ok(content) => {
+          return Ok(Conversion {
+            content,
+            converter: candidate.converter.name(),
+            detection: candidate.detection,
+            failed,
+          });
+        }
+        Err(e) => failed.push(Attempt {
+          converter: candidate.converter.name(),
+          detection: candidate.detection,
+          error: e.to_string(),
+        }),
+      }
+    }
+
+    Err(ConversionError::AllFailed { format: input.format(), attempts: failed })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Capabilities;
+
+  struct Fixed {
+    name: &'static str,
+    extensions: &'static [&'static str],
+    formats: &'static [Format],
+    fallback: bool,
+    result: Result<&'static str, &'static str>,
+  }
+
+  impl DocumentConverter for Fixed {
+    fn name(&self) -> &'static str {
+      self.name
+    }
+
+    fn version(&self) -> &'static str {
+      "1"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+      Capabilities {
+        description: "test converter",
+        extensions: self.extensions,
+        formats: self.formats,
+        requires: None,
+      }
+    }
+
+    fn detect(&self, input: &Input) -> Detection {
+      if self.formats.contains(&input.format()) {
+        return Detection::Content;
+      }
+      match input.extension() {
+        Some(ext) if self.extensions.contains(&ext) => Detection::Extension,
+        _ if self.fallback => Detection::Fallback,
+        _ => Detection::Unsupported,
+      }
+    }
+
+    fn convert(&self, _input: &Input) -> Result<Content, Box<dyn Error>> {
+      self.result.map(|text| text.to_string().into()).map_err(|e| e.into())
+    }
+  }
+
+  fn registry() -> ConverterRegistry {
+    let mut registry = ConverterRegistry::new();
+    registry
+      .register(Fixed {
+        name: "any",
+        extensions: &["txt"],
+        formats: &[],
+        fallback: true,
+        result: Err("any failed"),
+      })
+      .register(Fixed {
+        name: "book",
+        extensions: &["book"],
+        formats: &[],
+        fallback: true,
+        result: Ok("book text"),
+      })
+      .register(Fixed {
+        name: "strict",
+        extensions: &["strict"],
+        formats: &[Format::Pdf],
+        fallback: false,
+        result: Ok("strict text"),
+      });
+    registry
+  }
+
+  #[test]
+  fn test_extension_match_excludes_fallbacks() {
+    let registry = registry();
+    let candidates = registry.candidates(&Input::new("a.book"));
+    let names: Vec<_> = candidates.iter().map(|c| c.converter.name()).collect();
+    assert_eq!(names, vec!["book"]);
+  }
+
+  #[test]
+  fn test_unknown_extension_uses_fallbacks_in_order() {
+    let registry = registry();
+    let conversion = registry.convert(&Input::new("a.unknown")).unwrap();
+    assert_eq!(conversion.content.text(), "book text");
+    assert_eq!(conversion.converter, "book");
+    assert_eq!(conversion.detection, Detection::Fallback);
+    assert_eq!(conversion.failed.len(), 1);
+    assert_eq!(conversion.failed[0].converter, "any");
+  }
+
+  #[test]
+  fn test_all_failed_reports_every_attempt() {
+    let registry = registry();
+    match registry.convert(&Input::new("a.txt")) {
+      Err(ConversionError::AllFailed { attempts, .. }) => {
+        assert_eq!(attempts.len(), 1);
+        assert_eq!(
+          attempts[0].to_string(),
+          "any (extension match): any failed"
+        );
+      }
+      other => panic!("Expected AllFailed, got {other:?}"),
+    }
+  }
+
+  #[test]
+  fn test_content_match_overrides_extension() {
+    let path = std::env::temp_dir()
+      .join(format!("document-converter-{}.book", std::process::id()));
+    std::fs::write(&path, b"%PDF-1.4\n").unwrap();
+    let input = Input::new(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    let registry = registry();
+    let candidates = registry.candidates(&input);
+    let names: Vec<_> = candidates.iter().map(|c| c.converter.name()).collect();
+    assert_eq!(names, vec!["strict", "book"]);
+    assert_eq!(candidates[0].detection, Detection::Content);
+  }
+
+  #[test]
+  fn test_signature_without_converter_skips_fallbacks() {
+    let path = std::env::temp_dir()
+      .join(format!("document-converter-{}.gz", std::process::id()));
+    std::fs::write(&path, [0x1f, 0x8b, 8, 0]).unwrap();
+    let input = Input::new(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    match registry().convert(&input) {
+      Err(e @ ConversionError::NoConverter { .. }) => {
+        assert_eq!(e.format(), Format::Gzip);
+        assert!(e.to_string().contains("gzip compressed data"));
+      }
+      other => panic!("Expected NoConverter, got {other:?}"),
+    }
+  }
+
+  #[test]
+  fn test_register_replaces_same_name() {
+    let mut registry = registry();
+    registry.register(Fixed {
+      name: "any",
+      extensions: &[],
+      formats: &[],
+      fallback: false,
+      result: Ok(""),
+    });
+    assert_eq!(registry.converters().count(), 3);
+    assert!(registry.get("any").unwrap().capabilities().extensions.is_empty());
+  }
+}
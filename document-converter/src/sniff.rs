@@ -0,0 +1,424 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// How many leading bytes are inspected for signatures
+const HEAD_LEN: usize = 4096;
+
+/// PDF allows binary junk before the header, readers look within the first
+/// 1 KiB
+const PDF_HEADER_WINDOW: usize = 1024;
+
+/// End of central directory record plus the longest possible zip comment
+const ZIP_TAIL_LEN: u64 = 22 + u16::MAX as u64;
+
+/// Input format identified from file content rather than the file name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+  Pdf,
+  Epub,
+  Docx,
+  Xlsx,
+  Pptx,
+  Odt,
+  Ods,
+  Odp,
+  /// FictionBook e-book, as XML or zipped
+  Fb2,
+  /// Kindle e-book, MOBI 6 or KF8
+  Mobi,
+  Rtf,
+  /// A zip archive that is none of the known zip based formats
+  Zip,
+  Gzip,
+  Bzip2,
+  Xz,
+  Zstd,
+  Utf8Bom,
+  Utf16Le,
+  Utf16Be,
+  /// No signature and no control characters, most likely plain text in an
+  /// 8-bit encoding
+  Text,
+  Unknown,
+}
+
+impl Format {
+  /// Human readable name, used in error messages
+  pub fn description(&self) -> &'static str {
+    match self {
+      Format::Pdf => "PDF document",
+      Format::Epub => "EPUB e-book",
+      Format::Docx => "Word document",
+      Format::Xlsx => "Excel spreadsheet",
+      Format::Pptx => "PowerPoint presentation",
+      Format::Odt => "OpenDocument text",
+      Format::Ods => "OpenDocument spreadsheet",
+      Format::Odp => "OpenDocument presentation",
+      Format::Fb2 => "FictionBook e-book",
+      Format::Mobi => "MOBI e-book",
+      Format::Rtf => "RTF document",
+      Format::Zip => "zip archive",
+      Format::Gzip => "gzip compressed data",
+      Format::Bzip2 => "bzip2 compressed data",
+      Format::Xz => "xz compressed data",
+      Format::Zstd => "zstd compressed data",
+      Format::Utf8Bom => "UTF-8 text",
+      Format::Utf16Le => "UTF-16LE text",
+      Format::Utf16Be => "UTF-16BE text",
+      Format::Text => "text",
+      Format::Unknown => "file",
+    }
+  }
+
+  /// Whether converters that only guess should still be tried
+  ///
+  /// Formats with an unambiguous signature are not handed to fallback
+  /// converters, since those would only fail with a less helpful error.
+  pub fn allows_fallback(&self) -> bool {
+    matches!(
+      self,
+      Format::Zip
+        | Format::Utf8Bom
+        | Format::Utf16Le
+        | Format::Utf16Be
+        | Format::Text
+        | Format::Unknown
+    )
+  }
+
+  /// Whether this is a compressed stream that holds another file
+  pub fn is_compressed(&self) -> bool {
+    matches!(self, Format::Gzip | Format::Bzip2 | Format::Xz | Format::Zstd)
+  }
+}
+
+impl fmt::Display for Format {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.description())
+  }
+}
+
+/// Identify the format of the file at `path` from its content
+pub fn sniff_path(path: &Path) -> io::Result<Format> {
+  let mut file = File::open(path)?;
+  let mut head = Vec::with_capacity(HEAD_LEN);
+  (&mut file).take(HEAD_LEN as u64).read_to_end(&mut head)?;
+
+  let format = sniff_bytes(&head);
+  if format != Format::Zip {
+    return Ok(format);
+  }
+
+  // Office formats don't fix the order of their entries, so look through
+  // the central directory at the end of the archive
+  Ok(zip_central_directory_names(&mut file)?.map_or(Format::Zip, |names| {
+    sniff_zip_entry_names(names.iter().map(String::as_str))
+  }))
+}
+
+/// Identify a format from the leading bytes of a file
+///
+/// Zip archives are recognized from their first entry only, which is where
+/// EPUB and OpenDocument store their `mimetype`.
+pub fn sniff_bytes(head: &[u8]) -> Format {
+  if is_pdf(head) {
+    return Format::Pdf;
+  }
+
+  if head.starts_with(b"PK\x03\x04") {
+    return sniff_zip_mimetype(head).unwrap_or(Format::Zip);
+  }
+
+  if head.starts_with(&[0x1f, 0x8b]) {
+    return Format::Gzip;
+  }
+
+  // The block size digit is followed by the magic of the first block, or of
+  // the end of stream for empty input, so text starting with "BZh" isn't
+  // mistaken for bzip2
+  if let Some(header) = head.strip_prefix(b"BZh")
+    && header.first().is_some_and(|digit| (b'1'..=b'9').contains(digit))
+    && matches!(
+      header.get(1..7),
+      Some(b"\x31\x41\x59\x26\x53\x59" | b"\x17\x72\x45\x38\x50\x90")
+    )
+  {
+    return Format::Bzip2;
+  }
+
+  if head.starts_with(b"\xfd7zXZ\0") {
+    return Format::Xz;
+  }
+
+  if head.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+    return Format::Zstd;
+  }
+
+  // Type and creator of the PalmDB header, for MOBI, AZW and AZW3 alike
+  if head.get(60..68) == Some(b"BOOKMOBI") {
+    return Format::Mobi;
+  }
+
+  // Only the root element counts, so text mentioning it isn't taken for a
+  // book
+  if xml_root(head).starts_with(b"<FictionBook") {
+    return Format::Fb2;
+  }
+
+  // RTF is plain ASCII, so it's told apart from text by its header
+  if head.trim_ascii_start().starts_with(b"{\\rtf") {
+    return Format::Rtf;
+  }
+
+  if head.starts_with(&[0xef, 0xbb, 0xbf]) {
+    return Format::Utf8Bom;
+  }
+
+  if head.starts_with(&[0xff, 0xfe]) {
+    return Format::Utf16Le;
+  }
+
+  if head.starts_with(&[0xfe, 0xff]) {
+    return Format::Utf16Be;
+  }
+
+  if !head.is_empty() && head.iter().all(|&b| b >= 0x20 || is_text_control(b)) {
+    return Format::Text;
+  }
+
+  Format::Unknown
+}
+
+// Whether the PDF header starts the file, or follows a preamble that isn't
+// text, so text that only mentions `%PDF-` isn't taken for a PDF
+fn is_pdf(head: &[u8]) -> bool {
+  let window = &head[..head.len().min(PDF_HEADER_WINDOW)];
+  window.windows(5).position(|window| window == b"%PDF-").is_some_and(|at| {
+    let preamble = &window[..at];
+    preamble.is_empty()
+      || preamble.iter().any(|&byte| byte < 0x20 && !is_text_control(byte))
+  })
+}
+
+// The XML from its root element on, after the byte order mark, the XML
+// declaration, comments, a doctype and whitespace
+fn xml_root(head: &[u8]) -> &[u8] {
+  let mut rest = head.strip_prefix(b"\xef\xbb\xbf").unwrap_or(head);
+  loop {
+    rest = rest.trim_ascii_start();
+    let end: &[u8] = if rest.starts_with(b"<?") {
+      b"?>"
+    } else if rest.starts_with(b"<!--") {
+      b"-->"
+    } else if rest.starts_with(b"<!DOCTYPE") {
+      b">"
+    } else {
+      return rest;
+    };
+    let Some(at) = rest.windows(end.len()).position(|window| window == end)
+    else {
+      return &[];
+    };
+    rest = &rest[at + end.len()..];
+  }
+}
+
+fn is_text_control(byte: u8) -> bool {
+  matches!(byte, b'\t' | b'\n' | b'\r' | 0x0c | 0x1b)
+}
+
+// Read the uncompressed `mimetype` entry EPUB and ODF put first in the zip
+fn sniff_zip_mimetype(head: &[u8]) -> Option<Format> {
+  let method = read_u16(head, 8)?;
+  let compressed_size = read_u32(head, 18)? as usize;
+  let name_len = read_u16(head, 26)? as usize;
+  let extra_len = read_u16(head, 28)? as usize;
+
+  let name = head.get(30..30 + name_len)?;
+  if name != b"mimetype" || method != 0 {
+    return None;
+  }
+
+  let start = 30 + name_len + extra_len;
+  let mimetype = head.get(start..start + compressed_size)?;
+  let mimetype = std::str::from_utf8(mimetype).ok()?.trim();
+
+  match mimetype {
+    "application/epub+zip" => Some(Format::Epub),
+    m if m.starts_with("application/vnd.oasis.opendocument.text") => {
+      Some(Format::Odt)
+    }
+    m if m.starts_with("application/vnd.oasis.opendocument.spreadsheet") => {
+      Some(Format::Ods)
+    }
+    m if m.starts_with("application/vnd.oasis.opendocument.presentation") => {
+      Some(Format::Odp)
+    }
+    _ => None,
+  }
+}
+
+fn sniff_zip_entry_names<'a>(names: impl Iterator<Item = &'a str>) -> Format {
+  for name in names {
+    match name {
+      "word/document.xml" => return Format::Docx,
+      "xl/workbook.xml" => return Format::Xlsx,
+      "ppt/presentation.xml" => return Format::Pptx,
+      // Zipped books hold a single `.fb2` file
+      name if name.to_lowercase().ends_with(".fb2") => return Format::Fb2,
+      _ => {}
+    }
+  }
+  Format::Zip
+}
+
+// List entry names from the zip central directory, or `None` if the archive
+// doesn't have a readable one
+fn zip_central_directory_names(
+  file: &mut File,
+) -> io::Result<Option<Vec<String>>> {
+  let file_len = file.seek(SeekFrom::End(0))?;
+  let tail_start = file_len.saturating_sub(ZIP_TAIL_LEN);
+  file.seek(SeekFrom::Start(tail_start))?;
+  let mut tail = Vec::new();
+  file.read_to_end(&mut tail)?;
+
+  let Some(eocd) = tail.windows(4).rposition(|window| window == b"PK\x05\x06")
+  else {
+    return Ok(None);
+  };
+
+  let (Some(directory_size), Some(directory_offset)) =
+    (read_u32(&tail, eocd + 12), read_u32(&tail, eocd + 16))
+  else {
+    return Ok(None);
+  };
+
+  if directory_offset as u64 + directory_size as u64 > file_len {
+    return Ok(None);
+  }
+
+  file.seek(SeekFrom::Start(directory_offset as u64))?;
+  let mut directory = vec![0; directory_size as usize];
+  file.read_exact(&mut directory)?;
+
+  let mut names = Vec::new();
+  let mut pos = 0;
+  while directory.get(pos..pos + 4) == Some(b"PK\x01\x02") {
+    let (Some(name_len), Some(extra_len), Some(comment_len)) = (
+      read_u16(&directory, pos + 28),
+      read_u16(&directory, pos + 30),
+      read_u16(&directory, pos + 32),
+    ) else {
+      break;
+    };
+
+    let name_start = pos + 46;
+    let Some(name) = directory.get(name_start..name_start + name_len as usize)
+    else {
+      break;
+    };
+    names.push(String::from_utf8_lossy(name).into_owned());
+
+    pos = name_start
+      + name_len as usize
+      + extra_len as usize
+      + comment_len as usize;
+  }
+
+  Ok(Some(names))
+}
+
+fn read_u16(bytes: &[u8], at: usize) -> Option<u16> {
+  Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> Option<u32> {
+  Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn repo_file(path: &str) -> std::path::PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap().join(path)
+  }
+
+  #[test]
+  fn test_sniff_signatures() {
+    assert_eq!(sniff_bytes(b"%PDF-1.7\n"), Format::Pdf);
+    assert_eq!(sniff_bytes(b"\0\x81\x02junk%PDF-1.4"), Format::Pdf);
+    assert_eq!(
+      sniff_bytes(b"Notes\n\nThe header is %PDF-1.4 or later.\n"),
+      Format::Text
+    );
+    assert_eq!(sniff_bytes(&[0x1f, 0x8b, 8, 0]), Format::Gzip);
+    assert_eq!(sniff_bytes(b"BZh91AY&SY\0"), Format::Bzip2);
+    assert_eq!(sniff_bytes(b"BZh9\x17rE8P\x90\0"), Format::Bzip2);
+    assert_eq!(sniff_bytes(b"BZh, that's a text"), Format::Text);
+    assert_eq!(sniff_bytes(b"\xfd7zXZ\0\0\x04"), Format::Xz);
+    assert_eq!(sniff_bytes(&[0x28, 0xb5, 0x2f, 0xfd, 4, 0]), Format::Zstd);
+    assert_eq!(sniff_bytes(b"\xef\xbb\xbfhello"), Format::Utf8Bom);
+    assert_eq!(
+      sniff_bytes(b"\xef\xbb\xbf<?xml version=\"1.0\"?>\n<FictionBook>"),
+      Format::Fb2
+    );
+    assert_eq!(
+      sniff_bytes(b"<?xml version=\"1.0\"?>\n<!-- A book -->\n<FictionBook>"),
+      Format::Fb2
+    );
+    assert_eq!(
+      sniff_bytes(b"Converted <FictionBook> files from the archive\n"),
+      Format::Text
+    );
+    let mut palmdb = vec![0; 78];
+    palmdb[60..68].copy_from_slice(b"BOOKMOBI");
+    assert_eq!(sniff_bytes(&palmdb), Format::Mobi);
+    assert_eq!(sniff_bytes(b"{\\rtf1\\ansi Hello\\par}"), Format::Rtf);
+    assert_eq!(sniff_bytes(b"\xff\xfeh\0i\0"), Format::Utf16Le);
+    assert_eq!(sniff_bytes(b"\xfe\xff\0h\0i"), Format::Utf16Be);
+    assert_eq!(sniff_bytes(b"plain text\r\n"), Format::Text);
+    assert_eq!(sniff_bytes(b"caf\xe9"), Format::Text);
+    assert_eq!(sniff_bytes(b"\x7fELF\x02\x01\x01\0"), Format::Unknown);
+    assert_eq!(sniff_bytes(b""), Format::Unknown);
+  }
+
+  #[test]
+  fn test_truncated_zip_header_is_plain_zip() {
+    assert_eq!(sniff_bytes(b"PK\x03\x04\x14\0"), Format::Zip);
+  }
+
+  #[test]
+  fn test_zip_entry_names() {
+    let names = ["[Content_Types].xml", "word/document.xml"];
+    assert_eq!(sniff_zip_entry_names(names.into_iter()), Format::Docx);
+    let names = ["The Lighthouse.FB2"];
+    assert_eq!(sniff_zip_entry_names(names.into_iter()), Format::Fb2);
+    let names = ["notes.txt"];
+    assert_eq!(sniff_zip_entry_names(names.into_iter()), Format::Zip);
+  }
+
+  #[test]
+  fn test_sniff_test_data() {
+    let cases = [
+      ("test-data/pdf/pdfreference1.7old-1-50.pdf", Format::Pdf),
+      ("test-data/epub/test-standard.epub", Format::Epub),
+      ("test-data/docx/test-standard.docx", Format::Docx),
+      ("test-data/odf/test.odt", Format::Odt),
+      ("test-data/fb2/test.fb2", Format::Fb2),
+      ("test-data/mobi/test.mobi", Format::Mobi),
+      ("test-data/mobi/test.azw3", Format::Mobi),
+      ("test-data/rtf/test.rtf", Format::Rtf),
+      ("test-data/sample.txt", Format::Text),
+    ];
+
+    for (path, expected) in cases {
+      let path = repo_file(path);
+      assert!(path.exists(), "Missing fixture {path:?}");
+      assert_eq!(sniff_path(&path).unwrap(), expected, "{path:?}");
+    }
+  }
+}
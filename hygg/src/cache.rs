@@ -0,0 +1,372 @@
+use document_converter::{
+  Content, ConversionError, ConverterRegistry, Format, Input, which,
+};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::compression;
+use crate::converters::Options;
+use crate::man;
+
+/// Size limit of the cache in MiB, unless `HYGG_CACHE_SIZE` sets another
+const DEFAULT_SIZE_MIB: u64 = 512;
+
+/// Bumped when the way entries are stored changes, so old entries are
+/// converted again instead of misread
+const ENTRY_VERSION: u32 = 1;
+
+const ENTRY_SUFFIX: &str = ".json.gz";
+
+/// Converted documents on disk, keyed by the hash of the file they were
+/// converted from
+///
+/// Entries are gzipped JSON of the converted content. Reading an entry
+/// marks it as used, and the least recently used entries are removed when
+/// the cache grows over its size limit.
+pub struct Cache {
+  dir: PathBuf,
+  limit: u64,
+}
+
+/// Number and total size of the entries in the cache
+#[derive(Debug, Default, PartialEq)]
+pub struct Stats {
+  pub entries: usize,
+  pub bytes: u64,
+}
+
+impl Cache {
+  /// The cache in `HYGG_CACHE_DIR`, or else in the user's cache dir, e.g.
+  /// `~/.cache/hygg/conversions`
+  pub fn open() -> Option<Self> {
+    let dir = match std::env::var_os("HYGG_CACHE_DIR") {
+      Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+      _ => dirs::cache_dir()?.join("hygg").join("conversions"),
+    };
+    let size = std::env::var("HYGG_CACHE_SIZE")
+      .ok()
+      .and_then(|size| size.trim().parse::<u64>().ok())
+      .unwrap_or(DEFAULT_SIZE_MIB);
+    Some(Self::new(dir, size << 20))
+  }
+
+  pub fn new(dir: PathBuf, limit: u64) -> Self {
+    Self { dir, limit }
+  }
+
+  pub fn dir(&self) -> &Path {
+    &self.dir
+  }
+
+  /// Size limit in bytes
+  pub fn limit(&self) -> u64 {
+    self.limit
+  }
+
+  /// The cached content for `key`, marking it as recently used
+  pub fn get(&self, key: &str) -> Option<Content> {
+    let path = self.entry_path(key);
+    let file = File::open(&path).ok()?;
+    let content =
+      serde_json::from_reader(flate2::read::GzDecoder::new(file)).ok()?;
+    if let Ok(file) = File::options().write(true).open(&path) {
+      let _ = file.set_modified(SystemTime::now());
+    }
+    Some(content)
+  }
+
+  /// Store `content` under `key` and evict the least recently used
+  /// entries if the cache is over its limit
+  pub fn put(&self, key: &str, content: &Content) -> io::Result<()> {
+    fs::create_dir_all(&self.dir)?;
+    let json = serde_json::to_vec(content)?;
+    let mut encoder =
+      flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+    encoder.write_all(&json)?;
+    let data = encoder.finish()?;
+    if data.len() as u64 > self.limit {
+      return Ok(());
+    }
+
+    // Written aside and renamed, so a reader never sees half an entry
+    let partial =
+      self.dir.join(format!("{key}.{}.partial", std::process::id()));
+    fs::write(&partial, data)?;
+    fs::rename(&partial, self.entry_path(key))?;
+    self.evict()
+  }
+
+  pub fn stats(&self) -> io::Result<Stats> {
+    let entries = self.entries()?;
+    Ok(Stats {
+      entries: entries.len(),
+      bytes: entries.iter().map(|(_, len, _)| len).sum(),
+    })
+  }
+
+  /// Remove every entry, returning what was removed
+  pub fn clear(&self) -> io::Result<Stats> {
+    let stats = self.stats()?;
+    for (path, _, _) in self.entries()? {
+      fs::remove_file(path)?;
+    }
+    Ok(stats)
+  }
+
+  // Remove the least recently used entries until the cache fits its limit
+  fn evict(&self) -> io::Result<()> {
+    let mut entries = self.entries()?;
+    let mut total: u64 = entries.iter().map(|(_, len, _)| len).sum();
+    entries.sort_by_key(|(_, _, used)| *used);
+    for (path, len, _) in entries {
+      if total <= self.limit {
+        break;
+      }
+      fs::remove_file(path)?;
+      total -= len;
+    }
+    Ok(())
+  }
+
+  // Path, size and last use of each entry
+  fn entries(&self) -> io::Result<Vec<(PathBuf, u64, SystemTime)>> {
+    let dir = match fs::read_dir(&self.dir) {
+      Ok(dir) => dir,
+      Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+      Err(e) => return Err(e),
+    };
+    let mut entries = Vec::new();
+    for entry in dir {
+      let entry = entry?;
+      let name = entry.file_name();
+      if !name.to_string_lossy().ends_with(ENTRY_SUFFIX) {
+        continue;
+      }
+      let metadata = entry.metadata()?;
+      let used = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+      entries.push((entry.path(), metadata.len(), used));
+    }
+    Ok(entries)
+  }
+
+  fn entry_path(&self, key: &str) -> PathBuf {
+    self.dir.join(format!("{key}{ENTRY_SUFFIX}"))
+  }
+}
+
+/// The cache key of a file's bytes, when converted by the converters of
+/// `registry` with `options` and picked by `extension`
+///
+/// The extension is part of the key since it decides the converter, e.g.
+/// the same bytes are read as Markdown or as plain text. Every converter
+/// counts, not only the one picked, as a converter that fails hands the
+/// file on to the next, and compressed files are read by whichever fits the
+/// file inside.
+pub fn key(
+  bytes: &[u8],
+  extension: Option<&str>,
+  options: &Options,
+  registry: &ConverterRegistry,
+) -> String {
+  let mut hasher = Sha256::new();
+  let settings = format!(
+    "{ENTRY_VERSION}\n{}\n{}\n{:?}\n{}\n",
+    extension.unwrap_or_default(),
+    options.encoding.map_or("", |encoding| encoding.name()),
+    options.ocr,
+    options.ocr_lang.as_deref().unwrap_or_default(),
+  );
+  hasher.update(settings.as_bytes());
+  for converter in registry.converters() {
+    hasher.update(format!("{} {}\n", converter.name(), converter.version()));
+    // Programs such as pandoc are told apart by when they were installed
+    if let Some(program) = converter.capabilities().requires
+      && let Some(path) = which(program)
+    {
+      let installed = fs::metadata(&path).and_then(|meta| meta.modified());
+      let installed = installed.ok();
+      hasher.update(format!("{} {installed:?}\n", path.display()));
+    }
+  }
+  hasher.update(bytes);
+  hasher.finalize().iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Convert `input`, or take its content from the cache when the same bytes
+/// were converted with the same options and converters before
+///
+/// Conversions with warnings, such as pages that weren't OCRed, are left
+/// out so they are tried again. So are encrypted PDFs, whose text shouldn't
+/// be stored unencrypted, and aliases of manual pages, whose text is the
+/// page they include, which can change while the alias doesn't.
+pub fn convert(
+  registry: &ConverterRegistry,
+  input: &Input,
+  options: &Options,
+  cache: Option<&Cache>,
+) -> Result<Content, ConversionError> {
+  let bytes = cache.and_then(|_| fs::read(input.path()).ok());
+  let key = bytes
+    .as_deref()
+    .map(|bytes| key(bytes, input.extension(), options, registry));
+  let cached = cache.zip(key.as_deref());
+
+  if let Some((cache, key)) = cached
+    && let Some(content) = cache.get(key)
+  {
+    return Ok(content);
+  }
+
+  let conversion = registry.convert(input)?;
+  if let Some((cache, key)) = cached
+    && let Some(bytes) = &bytes
+    && conversion.content.warnings.is_empty()
+    && is_cacheable(conversion.converter, bytes)
+  {
+    // Not being able to cache only makes the next start slower
+    let _ = cache.put(key, &conversion.content);
+  }
+  Ok(conversion.content)
+}
+
+// Whether the conversion of the bytes may be stored, checked once they are
+// converted so reading from the cache doesn't decompress them
+fn is_cacheable(converter: &str, bytes: &[u8]) -> bool {
+  match converter {
+    "man" => !man::is_alias(bytes),
+    _ => !is_encrypted_pdf(bytes),
+  }
+}
+
+// Whether the bytes are a PDF, maybe compressed, with an encryption
+// dictionary
+fn is_encrypted_pdf(bytes: &[u8]) -> bool {
+  let decompressed = compression::decompress(bytes).ok().flatten();
+  let bytes = decompressed.as_deref().unwrap_or(bytes);
+  document_converter::sniff_bytes(bytes) == Format::Pdf
+    && bytes.windows(8).any(|window| window == b"/Encrypt")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use document_converter::{Capabilities, DocumentConverter};
+  use std::error::Error;
+
+  fn temp_cache(name: &str, limit: u64) -> Cache {
+    let dir = std::env::temp_dir()
+      .join(format!("hygg-cache-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    Cache::new(dir, limit)
+  }
+
+  #[test]
+  fn test_entries_round_trip_and_clear() {
+    let cache = temp_cache("round-trip", 1 << 20);
+    let content =
+      Content::from("Cached text.\n\nSecond paragraph.".to_string());
+    assert_eq!(cache.get("a"), None);
+
+    cache.put("a", &content).unwrap();
+    assert_eq!(cache.get("a"), Some(content));
+    assert_eq!(cache.stats().unwrap().entries, 1);
+
+    let removed = cache.clear().unwrap();
+    assert_eq!(removed.entries, 1);
+    assert_eq!(cache.stats().unwrap(), Stats::default());
+    fs::remove_dir_all(cache.dir()).unwrap();
+  }
+
+  #[test]
+  fn test_least_recently_used_entries_are_evicted() {
+    let content = Content::from("Some text to cache. ".repeat(20));
+    let probe = temp_cache("probe", 1 << 20);
+    probe.put("probe", &content).unwrap();
+    let entry_len = probe.stats().unwrap().bytes;
+    fs::remove_dir_all(probe.dir()).unwrap();
+
+    // Room for two entries
+    let cache = temp_cache("evict", entry_len * 2);
+    cache.put("first", &content).unwrap();
+    cache.put("second", &content).unwrap();
+    let hour_ago = SystemTime::now() - std::time::Duration::from_secs(3600);
+    for (key, used) in [("first", hour_ago), ("second", SystemTime::UNIX_EPOCH)]
+    {
+      let file = File::options().write(true).open(cache.entry_path(key));
+      file.unwrap().set_modified(used).unwrap();
+    }
+    // Reading marks "second" as the most recently used
+    assert!(cache.get("second").is_some());
+
+    cache.put("third", &content).unwrap();
+    assert!(cache.get("first").is_none());
+    assert!(cache.get("second").is_some());
+    assert!(cache.get("third").is_some());
+    fs::remove_dir_all(cache.dir()).unwrap();
+  }
+
+  struct Versioned(&'static str);
+
+  impl DocumentConverter for Versioned {
+    fn name(&self) -> &'static str {
+      "versioned"
+    }
+
+    fn version(&self) -> &'static str {
+      self.0
+    }
+
+    fn capabilities(&self) -> Capabilities {
+      Capabilities {
+        description: "Text",
+        extensions: &["txt"],
+        formats: &[],
+        requires: None,
+      }
+    }
+
+    fn convert(&self, _input: &Input) -> Result<Content, Box<dyn Error>> {
+      Ok(Content::default())
+    }
+  }
+
+  fn versioned(version: &'static str) -> ConverterRegistry {
+    let mut registry = ConverterRegistry::new();
+    registry.register(Versioned(version));
+    registry
+  }
+
+  #[test]
+  fn test_key_depends_on_bytes_extension_options_and_converters() {
+    let options = Options::default();
+    let registry = versioned("1.0");
+    let base = key(b"text", Some("txt"), &options, &registry);
+    assert_eq!(base.len(), 64);
+    assert_eq!(base, key(b"text", Some("txt"), &options, &registry));
+    assert_ne!(base, key(b"text!", Some("txt"), &options, &registry));
+    assert_ne!(base, key(b"text", Some("md"), &options, &registry));
+
+    let ocr =
+      Options { ocr: crate::ocr::OcrMode::Always, ..Default::default() };
+    assert_ne!(base, key(b"text", Some("txt"), &ocr, &registry));
+    assert_ne!(base, key(b"text", Some("txt"), &options, &versioned("1.1")));
+  }
+
+  #[test]
+  fn test_encrypted_pdfs_are_recognized() {
+    assert!(is_encrypted_pdf(b"%PDF-1.7\ntrailer << /Encrypt 5 0 R >>"));
+    assert!(!is_encrypted_pdf(b"%PDF-1.7\ntrailer << /Root 1 0 R >>"));
+    assert!(!is_encrypted_pdf(b"Notes on /Encrypt dictionaries"));
+  }
+
+  #[test]
+  fn test_manual_page_aliases_are_not_cached() {
+    assert!(!is_cacheable("man", b".so man1/ls.1\n"));
+    assert!(is_cacheable("man", b".TH LS 1\n.SH NAME\nls"));
+    assert!(!is_cacheable("pdf", b"%PDF-1.7\ntrailer << /Encrypt 5 0 R >>"));
+    assert!(is_cacheable("text", b".so man1/ls.1\n"));
+  }
+}
@@ -0,0 +1,146 @@
+use document_converter::{Format, sniff_bytes};
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+
+/// Decompressed input larger than this is refused, so a small archive that
+/// expands to gigabytes can't exhaust memory
+const MAX_DECOMPRESSED_LEN: u64 = 1 << 30;
+
+/// File name suffixes of compressed files
+pub const SUFFIXES: &[&str] = &[".gz", ".bz2", ".xz", ".zst", ".zstd"];
+
+/// The name of the file inside a compressed file, e.g. `report.pdf` for
+/// `report.pdf.xz`, or `name` itself if it has no compression suffix
+pub fn inner_name(name: &str) -> &str {
+  SUFFIXES
+    .iter()
+    .find_map(|suffix| {
+      let stem = name.len().checked_sub(suffix.len()).filter(|&len| len > 0)?;
+      name
+        .get(stem..)
+        .is_some_and(|end| end.eq_ignore_ascii_case(suffix))
+        .then(|| &name[..stem])
+    })
+    .unwrap_or(name)
+}
+
+/// Decompress `bytes` if they start with the signature of gzip, bzip2, xz
+/// or zstd, otherwise return `None`
+///
+/// Streams that were compressed twice, as in `notes.txt.gz.xz`, are
+/// decompressed until what's left isn't compressed.
+pub fn decompress(bytes: &[u8]) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+  let mut format = sniff_bytes(bytes);
+  if !format.is_compressed() {
+    return Ok(None);
+  }
+
+  let mut data = bytes.to_vec();
+  while format.is_compressed() {
+    data = decompress_stream(format, &data)?;
+    format = sniff_bytes(&data);
+  }
+  Ok(Some(data))
+}
+
+fn decompress_stream(
+  format: Format,
+  bytes: &[u8],
+) -> Result<Vec<u8>, Box<dyn Error>> {
+  let reader = decoder(format, bytes)?;
+
+  let mut data = Vec::new();
+  reader
+    .take(MAX_DECOMPRESSED_LEN + 1)
+    .read_to_end(&mut data)
+    .map_err(|e| format!("Invalid {format}: {e}"))?;
+  if data.len() as u64 > MAX_DECOMPRESSED_LEN {
+    return Err(
+      format!(
+        "The {format} expands to more than {} MiB",
+        MAX_DECOMPRESSED_LEN >> 20
+      )
+      .into(),
+    );
+  }
+  Ok(data)
+}
+
+/// Up to `len` bytes from the start of the file at `path`, decompressed if
+/// it's compressed, to tell what kind of file is inside without reading all
+/// of it
+pub fn read_head(path: &Path, len: u64) -> Result<Vec<u8>, Box<dyn Error>> {
+  let mut file = BufReader::new(File::open(path)?);
+  let format = sniff_bytes(file.fill_buf()?);
+  let reader: Box<dyn Read> = if format.is_compressed() {
+    decoder(format, file)?
+  } else {
+    Box::new(file)
+  };
+
+  let mut data = Vec::new();
+  reader.take(len).read_to_end(&mut data)?;
+  Ok(data)
+}
+
+fn decoder<'a, R: Read + 'a>(
+  format: Format,
+  reader: R,
+) -> Result<Box<dyn Read + 'a>, Box<dyn Error>> {
+  Ok(match format {
+    Format::Gzip => Box::new(flate2::read::MultiGzDecoder::new(reader)),
+    Format::Bzip2 => Box::new(bzip2::read::MultiBzDecoder::new(reader)),
+    Format::Xz => Box::new(liblzma::read::XzDecoder::new_multi_decoder(reader)),
+    Format::Zstd => Box::new(zstd::stream::read::Decoder::new(reader)?),
+    _ => return Err(format!("{format} is not compressed").into()),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::Write;
+
+  const TEXT: &[u8] = b"Compressed logs are read like any other file.\n";
+
+  #[test]
+  fn test_decompress_every_format() {
+    let mut gzip =
+      flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+    gzip.write_all(TEXT).unwrap();
+    let gzip = gzip.finish().unwrap();
+
+    let mut bzip2 =
+      bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::fast());
+    bzip2.write_all(TEXT).unwrap();
+    let bzip2 = bzip2.finish().unwrap();
+
+    let mut xz = liblzma::write::XzEncoder::new(Vec::new(), 1);
+    xz.write_all(TEXT).unwrap();
+    let xz = xz.finish().unwrap();
+
+    let zstd = zstd::encode_all(TEXT, 1).unwrap();
+
+    for compressed in [&gzip, &bzip2, &xz, &zstd] {
+      assert_eq!(decompress(compressed).unwrap().as_deref(), Some(TEXT));
+    }
+
+    // Compressed twice
+    let zstd_gzip = zstd::encode_all(gzip.as_slice(), 1).unwrap();
+    assert_eq!(decompress(&zstd_gzip).unwrap().as_deref(), Some(TEXT));
+
+    assert_eq!(decompress(TEXT).unwrap(), None);
+    assert!(decompress(&gzip[..gzip.len() / 2]).is_err());
+  }
+
+  #[test]
+  fn test_inner_name() {
+    assert_eq!(inner_name("report.pdf.xz"), "report.pdf");
+    assert_eq!(inner_name("syslog.1.GZ"), "syslog.1");
+    assert_eq!(inner_name("rfc2616.txt.zst"), "rfc2616.txt");
+    assert_eq!(inner_name("notes.txt"), "notes.txt");
+    assert_eq!(inner_name(".gz"), ".gz");
+  }
+}
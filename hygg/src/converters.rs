@@ -0,0 +1,971 @@
+use cli_pdf_to_text::{Page, PdfError};
+use document_converter::{
+  Block, Capabilities, Content, ConverterRegistry, Detection,
+  DocumentConverter, Format, Input, Metadata, Part, TocEntry, which,
+};
+use encoding_rs::Encoding;
+use std::error::Error;
+use std::path::Path;
+
+use crate::compression;
+use crate::man;
+use crate::markdown;
+use crate::notebook;
+use crate::ocr::{self, OcrMode};
+use crate::text::decode_text;
+use crate::validate_file_path;
+
+/// Times the password of an encrypted document is asked for
+const PASSWORD_ATTEMPTS: usize = 3;
+
+/// Command line settings that change how converters read their input
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+  /// Text encoding to use instead of detecting one
+  pub encoding: Option<&'static Encoding>,
+  /// Password for encrypted documents
+  pub password: Option<String>,
+  /// Ask for the password on the terminal when a document needs one
+  pub prompt_password: bool,
+  /// When to OCR PDF pages
+  pub ocr: OcrMode,
+  /// Tesseract languages for OCR, e.g. `eng+deu`
+  pub ocr_lang: Option<String>,
+}
+
+/// The converters hygg ships with
+///
+/// Plain text is registered first so text without a known extension is read
+/// natively, with pandoc as the next fallback. Native converters for formats
+/// pandoc also reads are registered before it, so pandoc is only tried when
+/// they fail.
+pub fn default_registry(options: &Options) -> ConverterRegistry {
+  let mut registry = ConverterRegistry::new();
+  registry
+    .register(TextConverter { encoding: options.encoding })
+    .register(DocxConverter)
+    .register(OdtConverter)
+    .register(RtfConverter)
+    .register(HtmlConverter)
+    .register(Fb2Converter)
+    .register(MarkdownConverter { encoding: options.encoding })
+    .register(NotebookConverter)
+    .register(ManConverter { encoding: options.encoding })
+    .register(CompressedConverter { options: options.clone() })
+    .register(PandocConverter)
+    .register(EpubConverter)
+    .register(MobiConverter)
+    .register(PdfConverter {
+      password: options.password.clone(),
+      prompt_password: options.prompt_password,
+      ocr: options.ocr,
+      ocr_lang: options.ocr_lang.clone(),
+    });
+  registry
+}
+
+pub struct TextConverter {
+  encoding: Option<&'static Encoding>,
+}
+
+impl DocumentConverter for TextConverter {
+  fn name(&self) -> &'static str {
+    "text"
+  }
+
+  fn version(&self) -> &'static str {
+    env!("CARGO_PKG_VERSION")
+  }
+
+  fn capabilities(&self) -> Capabilities {
+    Capabilities {
+      description: "Plain text, UTF-8, UTF-16 and Latin-1/Windows-1252",
+      extensions: &["txt", "text", "log", "asc", "nfo"],
+      formats: &[Format::Utf8Bom, Format::Utf16Le, Format::Utf16Be],
+      requires: None,
+    }
+  }
+
+  fn detect(&self, input: &Input) -> Detection {
+    let capabilities = self.capabilities();
+    if capabilities.formats.contains(&input.format()) {
+      return Detection::Content;
+    }
+    match input.extension() {
+      Some(ext) if capabilities.extensions.contains(&ext) => {
+        Detection::Extension
+      }
+      _ if input.format() == Format::Text => Detection::Fallback,
+      _ => Detection::Unsupported,
+    }
+  }
+
+  fn convert(&self, input: &Input) -> Result<Content, Box<dyn Error>> {
+    let bytes = std::fs::read(input.path())?;
+    Ok(decode_text(&bytes, self.encoding).0.into())
+  }
+}
+
+pub struct MarkdownConverter {
+  encoding: Option<&'static Encoding>,
+}
+
+impl DocumentConverter for MarkdownConverter {
+  fn name(&self) -> &'static str {
+    "markdown"
+  }
+
+  fn version(&self) -> &'static str {
+    env!("CARGO_PKG_VERSION")
+  }
+
+  fn capabilities(&self) -> Capabilities {
+    Capabilities {
+      description: "Markdown, shown with bold headings, lists and code",
+      extensions: &["md", "markdown", "mdown", "mkd"],
+      formats: &[],
+      requires: None,
+    }
+  }
+
+  fn detect(&self, input: &Input) -> Detection {
+    match input.extension() {
+      Some(ext) if self.capabilities().extensions.contains(&ext) => {
+        Detection::Extension
+      }
+      _ => Detection::Unsupported,
+    }
+  }
+
+  fn convert(&self, input: &Input) -> Result<Content, Box<dyn Error>> {
+    let bytes = std::fs::read(input.path())?;
+    Ok(markdown::markdown_content(&decode_text(&bytes, self.encoding).0))
+  }
+}
+
+pub struct NotebookConverter;
+
+impl DocumentConverter for NotebookConverter {
+  fn name(&self) -> &'static str {
+    "ipynb"
+  }
+
+  fn version(&self) -> &'static str {
+    env!("CARGO_PKG_VERSION")
+  }
+
+  fn capabilities(&self) -> Capabilities {
+    Capabilities {
+      description: "Jupyter notebooks, with code cells and their output",
+      extensions: &["ipynb"],
+      formats: &[],
+      requires: None,
+    }
+  }
+
+  fn detect(&self, input: &Input) -> Detection {
+    match input.extension() {
+      Some("ipynb") => Detection::Extension,
+      _ => Detection::Unsupported,
+    }
+  }
+
+  fn convert(&self, input: &Input) -> Result<Content, Box<dyn Error>> {
+    let bytes = std::fs::read(input.path())?;
+    notebook::notebook_content(&decode_text(&bytes, None).0)
+  }
+}
+
+pub struct ManConverter {
+  encoding: Option<&'static Encoding>,
+}
+
+impl DocumentConverter for ManConverter {
+  fn name(&self) -> &'static str {
+    "man"
+  }
+
+  fn version(&self) -> &'static str {
+    env!("CARGO_PKG_VERSION")
+  }
+
+  fn capabilities(&self) -> Capabilities {
+    Capabilities {
+      description: "Manual pages, plain or compressed, with their sections",
+      extensions: &["1", "2", "3", "4", "5", "6", "7", "8", "9", "man"],
+      formats: &[],
+      requires: None,
+    }
+  }
+
+  // Sections can have a suffix, as in `printf.3p`, and pages are usually
+  // gzipped, as in `ls.1.gz`
+  fn detect(&self, input: &Input) -> Detection {
+    if man::is_man_page(input.path()) {
+      Detection::Extension
+    } else {
+      Detection::Unsupported
+    }
+  }
+
+  fn convert(&self, input: &Input) -> Result<Content, Box<dyn Error>> {
+    let mut source = read_man_page(input.path(), self.encoding)?;
+    // Pages for aliases only include another page, relative to the root of
+    // the manual, e.g. `.so man1/ls.1` in `man1/dir.1`
+    if let Some(target) = man::include_target(&source) {
+      let root = input.path().parent().and_then(Path::parent);
+      let path = root.unwrap_or(Path::new("")).join(target);
+      let name = path.file_name().unwrap_or_default().to_string_lossy();
+      let path = std::iter::once("")
+        .chain(compression::SUFFIXES.iter().copied())
+        .map(|suffix| path.with_file_name(format!("{name}{suffix}")))
+        .find(|path| path.exists())
+        .ok_or_else(|| {
+          format!("The manual page includes {target}, which was not found")
+        })?;
+      source = read_man_page(&path, self.encoding)?;
+    }
+    Ok(man::man_content(&source))
+  }
+}
+
+/// Reads the file inside a compressed file with the converter for it
+///
+/// The file is decompressed into a temp file named like the file inside, so
+/// it's picked up by its extension as well as its content, and converted
+/// with a registry of its own.
+pub struct CompressedConverter {
+  options: Options,
+}
+
+impl DocumentConverter for CompressedConverter {
+  fn name(&self) -> &'static str {
+    "compressed"
+  }
+
+  fn version(&self) -> &'static str {
+    env!("CARGO_PKG_VERSION")
+  }
+
+  fn capabilities(&self) -> Capabilities {
+    Capabilities {
+      description: "gzip, bzip2, xz and zstd compressed files, read as the file inside",
+      extensions: &["gz", "bz2", "xz", "zst", "zstd"],
+      formats: &[Format::Gzip, Format::Bzip2, Format::Xz, Format::Zstd],
+      requires: None,
+    }
+  }
+
+  fn convert(&self, input: &Input) -> Result<Content, Box<dyn Error>> {
+    let bytes = std::fs::read(input.path())?;
+    let Some(data) = compression::decompress(&bytes)? else {
+      return Err(format!("{} is not compressed", input.format()).into());
+    };
+
+    let name = input.path().file_name().unwrap_or_default().to_string_lossy();
+    let extension = Path::new(compression::inner_name(&name))
+      .extension()
+      .map(|ext| ext.to_string_lossy().into_owned())
+      .unwrap_or_default();
+    let file = ocr::TempFile::new(&extension);
+    std::fs::write(file.path(), data)?;
+
+    let conversion = default_registry(&self.options)
+      .convert(&Input::new(file.path()))
+      .map_err(|e| format!("Unable to read the decompressed file: {e}"))?;
+    Ok(conversion.content)
+  }
+}
+
+// Read a manual page, decompressing it if it's compressed
+fn read_man_page(
+  path: &Path,
+  encoding: Option<&'static Encoding>,
+) -> Result<String, Box<dyn Error>> {
+  let bytes = std::fs::read(path)?;
+  let page = compression::decompress(&bytes)?;
+  Ok(decode_text(page.as_deref().unwrap_or(&bytes), encoding).0)
+}
+
+pub struct EpubConverter;
+
+impl DocumentConverter for EpubConverter {
+  fn name(&self) -> &'static str {
+    "epub"
+  }
+
+  fn version(&self) -> &'static str {
+    cli_epub_to_text::VERSION
+  }
+
+  fn capabilities(&self) -> Capabilities {
+    Capabilities {
+      description: "EPUB e-books",
+      extensions: &["epub"],
+      formats: &[Format::Epub],
+      requires: None,
+    }
+  }
+
+  fn detect(&self, input: &Input) -> Detection {
+    if input.format() == Format::Epub {
+      return Detection::Content;
+    }
+    match input.extension() {
+      Some("epub") => Detection::Extension,
+      _ => Detection::Fallback,
+    }
+  }
+
+  fn convert(&self, input: &Input) -> Result<Content, Box<dyn Error>> {
+    let path = input.path_str();
+    let chapters = cli_epub_to_text::epub_chapters(&path)?;
+    // Missing metadata shouldn't keep the book from opening
+    let metadata = cli_epub_to_text::epub_metadata(&path).unwrap_or_default();
+    Ok(Content {
+      parts: chapters
+        .into_iter()
+        .map(|chapter| Part {
+          title: Some(chapter.title),
+          level: 0,
+          page: None,
+          text: chapter.text,
+          blocks: chapter.blocks,
+        })
+        .collect(),
+      metadata: Metadata {
+        identifier: metadata
+          .isbn()
+          .or_else(|| metadata.identifiers.first().cloned()),
+        title: metadata.title,
+        authors: metadata.creators,
+        language: metadata.language,
+        publisher: metadata.publisher,
+      },
+      ..Default::default()
+    })
+  }
+}
+
+pub struct MobiConverter;
+
+impl DocumentConverter for MobiConverter {
+  fn name(&self) -> &'static str {
+    "mobi"
+  }
+
+  fn version(&self) -> &'static str {
+    cli_mobi_to_text::VERSION
+  }
+
+  fn capabilities(&self) -> Capabilities {
+    Capabilities {
+      description: "Kindle e-books without DRM",
+      extensions: &["mobi", "azw", "azw3"],
+      formats: &[Format::Mobi],
+      requires: None,
+    }
+  }
+
+  fn detect(&self, input: &Input) -> Detection {
+    if input.format() == Format::Mobi {
+      return Detection::Content;
+    }
+    match input.extension() {
+      Some("mobi" | "azw" | "azw3") => Detection::Extension,
+      _ => Detection::Fallback,
+    }
+  }
+
+  fn convert(&self, input: &Input) -> Result<Content, Box<dyn Error>> {
+    let path = input.path_str();
+    let chapters = cli_mobi_to_text::mobi_chapters(&path)?;
+    // Missing metadata shouldn't keep the book from opening
+    let metadata = cli_mobi_to_text::mobi_metadata(&path).unwrap_or_default();
+    Ok(Content {
+      parts: chapters
+        .into_iter()
+        .map(|chapter| Part {
+          title: Some(chapter.title),
+          level: 0,
+          page: None,
+          text: chapter.text,
+          blocks: chapter.blocks,
+        })
+        .collect(),
+      metadata: Metadata {
+        identifier: metadata.isbn.or(metadata.asin),
+        title: metadata.title,
+        authors: metadata.authors,
+        language: metadata.language,
+        publisher: metadata.publisher,
+      },
+      ..Default::default()
+    })
+  }
+}
+
+pub struct Fb2Converter;
+
+impl DocumentConverter for Fb2Converter {
+  fn name(&self) -> &'static str {
+    "fb2"
+  }
+
+  fn version(&self) -> &'static str {
+    cli_fb2_to_text::VERSION
+  }
+
+  fn capabilities(&self) -> Capabilities {
+    Capabilities {
+      description: "FictionBook e-books, plain or zipped",
+      extensions: &["fb2"],
+      formats: &[Format::Fb2],
+      requires: None,
+    }
+  }
+
+  fn detect(&self, input: &Input) -> Detection {
+    if input.format() == Format::Fb2 {
+      return Detection::Content;
+    }
+    let zipped_book = input.path_str().to_lowercase().ends_with(".fb2.zip");
+    match input.extension() {
+      Some("fb2") => Detection::Extension,
+      Some("zip") if zipped_book => Detection::Extension,
+      _ => Detection::Fallback,
+    }
+  }
+
+  fn convert(&self, input: &Input) -> Result<Content, Box<dyn Error>> {
+    let path = input.path_str();
+    let chapters = cli_fb2_to_text::fb2_chapters(&path)?;
+    // Missing metadata shouldn't keep the book from opening
+    let metadata = cli_fb2_to_text::fb2_metadata(&path).unwrap_or_default();
+    Ok(Content {
+      parts: chapters
+        .into_iter()
+        .map(|chapter| Part {
+          title: Some(chapter.title),
+          level: chapter.level,
+          page: None,
+          text: chapter.text,
+          blocks: chapter.blocks,
+        })
+        .collect(),
+      metadata: Metadata {
+        identifier: metadata.isbn.or(metadata.id),
+        title: metadata.title,
+        authors: metadata.authors,
+        language: metadata.language,
+        publisher: metadata.publisher,
+      },
+      ..Default::default()
+    })
+  }
+}
+
+pub struct DocxConverter;
+
+impl DocumentConverter for DocxConverter {
+  fn name(&self) -> &'static str {
+    "docx"
+  }
+
+  fn version(&self) -> &'static str {
+    cli_docx_to_text::VERSION
+  }
+
+  fn capabilities(&self) -> Capabilities {
+    Capabilities {
+      description: "Word documents",
+      extensions: &["docx"],
+      formats: &[Format::Docx],
+      requires: None,
+    }
+  }
+
+  fn detect(&self, input: &Input) -> Detection {
+    if input.format() == Format::Docx {
+      return Detection::Content;
+    }
+    match input.extension() {
+      Some("docx") => Detection::Extension,
+      _ => Detection::Fallback,
+    }
+  }
+
+  fn convert(&self, input: &Input) -> Result<Content, Box<dyn Error>> {
+    let path = input.path_str();
+    let sections = cli_docx_to_text::docx_sections(&path)?;
+    // Missing properties shouldn't keep the document from opening
+    let metadata = cli_docx_to_text::docx_metadata(&path).unwrap_or_default();
+    Ok(Content {
+      parts: sections
+        .into_iter()
+        .map(|section| Part {
+          title: section.title,
+          level: section.level,
+          page: None,
+          text: section.text,
+          blocks: section.blocks,
+        })
+        .collect(),
+      metadata: Metadata {
+        title: metadata.title,
+        authors: metadata.creator.into_iter().collect(),
+        language: metadata.language,
+        ..Default::default()
+      },
+      ..Default::default()
+    })
+  }
+}
+
+pub struct OdtConverter;
+
+impl DocumentConverter for OdtConverter {
+  fn name(&self) -> &'static str {
+    "odt"
+  }
+
+  fn version(&self) -> &'static str {
+    cli_odt_to_text::VERSION
+  }
+
+  fn capabilities(&self) -> Capabilities {
+    Capabilities {
+      description: "OpenDocument text",
+      extensions: &["odt"],
+      formats: &[Format::Odt],
+      requires: None,
+    }
+  }
+
+  fn detect(&self, input: &Input) -> Detection {
+    if input.format() == Format::Odt {
+      return Detection::Content;
+    }
+    match input.extension() {
+      Some("odt") => Detection::Extension,
+      _ => Detection::Fallback,
+    }
+  }
+
+  fn convert(&self, input: &Input) -> Result<Content, Box<dyn Error>> {
+    let path = input.path_str();
+    let sections = cli_odt_to_text::odt_sections(&path)?;
+    // Missing properties shouldn't keep the document from opening
+    let metadata = cli_odt_to_text::odt_metadata(&path).unwrap_or_default();
+    Ok(Content {
+      parts: sections
+        .into_iter()
+        .map(|section| Part {
+          title: section.title,
+          level: section.level,
+          page: None,
+          text: section.text,
+          blocks: section.blocks,
+        })
+        .collect(),
+      metadata: Metadata {
+        title: metadata.title,
+        authors: metadata.creator.into_iter().collect(),
+        language: metadata.language,
+        ..Default::default()
+      },
+      ..Default::default()
+    })
+  }
+}
+
+pub struct RtfConverter;
+
+impl DocumentConverter for RtfConverter {
+  fn name(&self) -> &'static str {
+    "rtf"
+  }
+
+  fn version(&self) -> &'static str {
+    cli_rtf_to_text::VERSION
+  }
+
+  fn capabilities(&self) -> Capabilities {
+    Capabilities {
+      description: "Rich Text Format documents",
+      extensions: &["rtf"],
+      formats: &[Format::Rtf],
+      requires: None,
+    }
+  }
+
+  fn detect(&self, input: &Input) -> Detection {
+    if input.format() == Format::Rtf {
+      return Detection::Content;
+    }
+    match input.extension() {
+      Some("rtf") => Detection::Extension,
+      _ => Detection::Fallback,
+    }
+  }
+
+  fn convert(&self, input: &Input) -> Result<Content, Box<dyn Error>> {
+    let path = input.path_str();
+    let sections = cli_rtf_to_text::rtf_sections(&path)?;
+    let metadata = cli_rtf_to_text::rtf_metadata(&path).unwrap_or_default();
+    Ok(Content {
+      parts: sections
+        .into_iter()
+        .map(|section| Part {
+          title: section.title,
+          level: section.level,
+          page: None,
+          text: section.text,
+          blocks: section.blocks,
+        })
+        .collect(),
+      metadata: Metadata {
+        title: metadata.title,
+        authors: metadata.author.into_iter().collect(),
+        ..Default::default()
+      },
+      ..Default::default()
+    })
+  }
+}
+
+pub struct HtmlConverter;
+
+impl DocumentConverter for HtmlConverter {
+  fn name(&self) -> &'static str {
+    "html"
+  }
+
+  fn version(&self) -> &'static str {
+    cli_html_to_text::VERSION
+  }
+
+  fn capabilities(&self) -> Capabilities {
+    Capabilities {
+      description: "Saved web pages and MHTML archives, article text only",
+      extensions: &["html", "htm", "xhtml", "mhtml", "mht"],
+      formats: &[],
+      requires: None,
+    }
+  }
+
+  fn detect(&self, input: &Input) -> Detection {
+    match input.extension() {
+      Some(ext) if self.capabilities().extensions.contains(&ext) => {
+        Detection::Extension
+      }
+      _ => Detection::Unsupported,
+    }
+  }
+
+  fn convert(&self, input: &Input) -> Result<Content, Box<dyn Error>> {
+    let path = input.path_str();
+    let sections = cli_html_to_text::html_sections(&path)?;
+    let metadata = cli_html_to_text::html_metadata(&path).unwrap_or_default();
+    Ok(Content {
+      parts: sections
+        .into_iter()
+        .map(|section| Part {
+          title: section.title,
+          level: section.level,
+          page: None,
+          text: section.text,
+          blocks: section.blocks,
+        })
+        .collect(),
+      metadata: Metadata {
+        title: metadata.title,
+        authors: metadata.author.into_iter().collect(),
+        language: metadata.language,
+        ..Default::default()
+      },
+      ..Default::default()
+    })
+  }
+}
+
+pub struct PdfConverter {
+  password: Option<String>,
+  prompt_password: bool,
+  ocr: OcrMode,
+  ocr_lang: Option<String>,
+}
+
+impl PdfConverter {
+  // Replace the text of the given pages, or of every page, by what OCR
+  // reads on them
+  fn ocr_pages(
+    &self,
+    path: &str,
+    numbers: Option<&[u32]>,
+    pages: &mut [Page],
+  ) -> Result<(), Box<dyn Error>> {
+    let count = numbers.map_or(pages.len(), <[u32]>::len);
+    eprintln!("Running OCR on {count} page(s) of '{path}'...");
+    let output = ocr::ocr_pdf(path, numbers, self.ocr_lang.as_deref())?;
+    let (ocr_pages, _) = cli_pdf_to_text::pdf_to_pages_with_report(
+      &output.path().to_string_lossy(),
+      None,
+    )?;
+    for (page, ocr_page) in pages.iter_mut().zip(ocr_pages) {
+      if numbers.is_none_or(|numbers| numbers.contains(&page.number)) {
+        page.text = ocr_page.text;
+      }
+    }
+    Ok(())
+  }
+}
+
+impl DocumentConverter for PdfConverter {
+  fn name(&self) -> &'static str {
+    "pdf"
+  }
+
+  fn version(&self) -> &'static str {
+    cli_pdf_to_text::VERSION
+  }
+
+  fn capabilities(&self) -> Capabilities {
+    Capabilities {
+      description: "PDF documents",
+      extensions: &["pdf"],
+      formats: &[Format::Pdf],
+      requires: None,
+    }
+  }
+
+  fn detect(&self, input: &Input) -> Detection {
+    if input.format() == Format::Pdf {
+      return Detection::Content;
+    }
+    match input.extension() {
+      Some("pdf") => Detection::Extension,
+      _ => Detection::Fallback,
+    }
+  }
+
+  fn convert(&self, input: &Input) -> Result<Content, Box<dyn Error>> {
+    let path = input.path_str();
+    let mut password = self.password.clone();
+    let mut prompts = 0;
+    let (mut pages, report) = loop {
+      match cli_pdf_to_text::pdf_to_pages_with_report(
+        &path,
+        password.as_deref(),
+      ) {
+        Err(e)
+          if self.prompt_password
+            && prompts < PASSWORD_ATTEMPTS
+            && e.downcast_ref::<PdfError>().is_some() =>
+        {
+          let prompt = if password.is_none() {
+            format!("Password for '{path}': ")
+          } else {
+            "Incorrect password, try again: ".to_string()
+          };
+          match cli_text_reader::prompt_password(&prompt)? {
+            Some(entered) => password = Some(entered),
+            None => return Err(e),
+          }
+          prompts += 1;
+        }
+        result => break result?,
+      }
+    };
+    // The placeholders in the text mark which pages failed
+    let mut warnings: Vec<String> = report.summary().into_iter().collect();
+    let scanned = &report.pages_without_text;
+    match self.ocr {
+      OcrMode::Always => self.ocr_pages(&path, None, &mut pages)?,
+      OcrMode::Auto if !scanned.is_empty() => {
+        if which("ocrmypdf").is_none() {
+          warnings.push(format!(
+            "{} page(s) need OCR, install ocrmypdf",
+            scanned.len()
+          ));
+        } else if let Err(e) = self.ocr_pages(&path, Some(scanned), &mut pages)
+        {
+          warnings.push(e.to_string());
+        }
+      }
+      OcrMode::Never if !scanned.is_empty() => {
+        warnings.push(format!(
+          "{} page(s) have no text, --ocr=auto reads them with OCR",
+          scanned.len()
+        ));
+      }
+      OcrMode::Auto | OcrMode::Never => {}
+    }
+
+    // A broken outline shouldn't keep the document from opening
+    let outline =
+      cli_pdf_to_text::pdf_outline_with_password(&path, password.as_deref())
+        .unwrap_or_default();
+    Ok(Content {
+      parts: pages
+        .into_iter()
+        .map(|page| {
+          // Keeping the indentation of the first paragraph
+          let text = page.text.trim_start_matches('\n').trim_end();
+          let mut part = Part::from_text(None, 0, text.to_string());
+          part.page = Some(page.number);
+          part.blocks.insert(0, Block::PageBreak(page.number));
+          part
+        })
+        .collect(),
+      toc: outline
+        .into_iter()
+        .map(|entry| TocEntry {
+          title: entry.title,
+          page: entry.page,
+          level: entry.level,
+        })
+        .collect(),
+      warnings,
+      ..Default::default()
+    })
+  }
+}
+
+pub struct PandocConverter;
+
+impl DocumentConverter for PandocConverter {
+  fn name(&self) -> &'static str {
+    "pandoc"
+  }
+
+  fn version(&self) -> &'static str {
+    env!("CARGO_PKG_VERSION")
+  }
+
+  fn capabilities(&self) -> Capabilities {
+    Capabilities {
+      description: "Office, markup and other formats via pandoc",
+      extensions: &[
+        "docx", "odt", "rtf", "html", "htm", "md", "markdown", "rst", "tex",
+        "org", "textile", "ipynb", "fb2", "opml",
+      ],
+      formats: &[Format::Docx, Format::Odt],
+      requires: Some("pandoc"),
+    }
+  }
+
+  fn detect(&self, input: &Input) -> Detection {
+    let capabilities = self.capabilities();
+    if capabilities.formats.contains(&input.format()) {
+      return Detection::Content;
+    }
+    match input.extension() {
+      Some(ext) if capabilities.extensions.contains(&ext) => {
+        Detection::Extension
+      }
+      _ => Detection::Fallback,
+    }
+  }
+
+  fn convert(&self, input: &Input) -> Result<Content, Box<dyn Error>> {
+    Ok(pandoc_to_text(&input.path_str())?.into())
+  }
+}
+
+// Convert document to text using pandoc
+fn pandoc_to_text(file_path: &str) -> Result<String, Box<dyn Error>> {
+  // Check if pandoc is available
+  if which("pandoc").is_none() {
+    return Err(
+      "pandoc not found. Install with: sudo apt install pandoc".into(),
+    );
+  }
+
+  // Validate file path
+  validate_file_path(file_path)?;
+
+  // Run pandoc with plain text output
+  let mut cmd = std::process::Command::new("pandoc");
+  cmd
+    .arg("--to=plain")
+    .arg("--wrap=none")
+    .arg("--")
+    .arg(file_path)
+    .stdin(std::process::Stdio::null())
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped());
+
+  let output = cmd.output()?;
+
+  if !output.status.success() {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    return Err(format!("pandoc failed: {stderr}").into());
+  }
+
+  Ok(String::from_utf8(output.stdout)?)
+}
+
+// Print the registered converters, and the order they would be tried in for
+// `file` if one is given
+pub fn list_converters(registry: &ConverterRegistry, file: Option<&str>) {
+  println!("Available converters:");
+  for converter in registry.converters() {
+    let capabilities = converter.capabilities();
+    let extensions = capabilities
+      .extensions
+      .iter()
+      .map(|ext| format!(".{ext}"))
+      .collect::<Vec<_>>()
+      .join(" ");
+    let availability = match capabilities.requires {
+      Some(binary) if !capabilities.is_available() => {
+        format!(" [requires {binary}, not installed]")
+      }
+      Some(binary) => format!(" [requires {binary}]"),
+      None => String::new(),
+    };
+    println!(
+      "  {} - {} : {}{}",
+      converter.name(),
+      capabilities.description,
+      extensions,
+      availability
+    );
+  }
+
+  if let Some(file) = file {
+    let input = Input::new(file);
+    println!();
+    if input.format() != Format::Unknown {
+      println!("Detected content: {}", input.format());
+    }
+    println!("Converters for '{file}', in the order they are tried:");
+    let candidates = registry.candidates(&input);
+    if candidates.is_empty() {
+      println!("  none");
+    }
+    for (i, candidate) in candidates.iter().enumerate() {
+      println!(
+        "  {}. {} ({})",
+        i + 1,
+        candidate.converter.name(),
+        describe_detection(candidate.detection, &input)
+      );
+    }
+  }
+}
+
+fn describe_detection(detection: Detection, input: &Input) -> String {
+  match (detection, input.extension()) {
+    (Detection::Content, _) => format!("content is {}", input.format()),
+    (Detection::Extension, Some(ext)) => format!("handles .{ext} files"),
+    (Detection::Fallback, Some(ext)) => {
+      format!("fallback, no converter handles .{ext} files")
+    }
+    (Detection::Fallback, None) => {
+      "fallback, the file has no extension".to_string()
+    }
+    (detection, _) => detection.to_string(),
+  }
+}
@@ -0,0 +1,571 @@
+use cli_text_reader::{DocumentInfo, Page, Section, StyledSpan, TextStyle};
+use document_converter::{Block, Content, Span, SpanStyle};
+
+/// Width of a tab in code blocks
+const TAB_WIDTH: usize = 4;
+
+/// Justify converted content and record where each titled part and each
+/// page starts
+///
+/// Table of contents entries become sections starting at their page. The
+/// content metadata and warnings are passed on for the status line and
+/// `:info`.
+/// Parts are justified one at a time, which gives the same lines as
+/// justifying `content.text()` since parts are separated by blank lines.
+/// Parts with blocks are laid out by `layout_blocks` instead.
+pub fn justify_content(
+  content: &Content,
+  col: usize,
+) -> (Vec<String>, DocumentInfo) {
+  let mut lines = Vec::new();
+  let metadata = &content.metadata;
+  let mut info = DocumentInfo {
+    title: metadata.title.clone(),
+    author: (!metadata.authors.is_empty()).then(|| metadata.authors.join(", ")),
+    warnings: content.warnings.clone(),
+    ..Default::default()
+  };
+  let details = [
+    ("Language", &metadata.language),
+    ("Publisher", &metadata.publisher),
+    ("Identifier", &metadata.identifier),
+  ];
+  for (label, value) in details {
+    if let Some(value) = value {
+      info.details.push((label.to_string(), value.clone()));
+    }
+  }
+
+  for part in &content.parts {
+    if let Some(title) = &part.title {
+      info.sections.push(Section {
+        title: title.clone(),
+        line: lines.len(),
+        level: part.level,
+      });
+    }
+    if let Some(number) = part.page {
+      info.pages.push(Page { number, line: lines.len() });
+    }
+    if part.blocks.is_empty() {
+      lines.extend(cli_justify::justify(&part.text, col));
+    } else {
+      layout_blocks(&part.blocks, col, &mut lines, &mut info);
+    }
+  }
+
+  for entry in &content.toc {
+    let Some(page) = info.pages.iter().find(|page| page.number == entry.page)
+    else {
+      continue;
+    };
+    info.sections.push(Section {
+      title: entry.title.clone(),
+      line: page.line,
+      level: entry.level,
+    });
+  }
+  // Outlines don't have to follow the page order, the reader expects it
+  info.sections.sort_by_key(|section| section.line);
+
+  (lines, info)
+}
+
+/// Lay out headings, paragraphs, lists, code blocks, tables and images,
+/// recording where the bold, italic and code runs and the pages end up
+///
+/// Paragraphs are justified like plain text, keeping their indentation. List
+/// items hang their lines after the marker and follow each other without
+/// blank lines. Code is kept as it is, only long lines are wrapped. Tables
+/// are aligned in columns when they fit the width, otherwise each row is a
+/// paragraph.
+pub fn layout_blocks(
+  blocks: &[Block],
+  col: usize,
+  lines: &mut Vec<String>,
+  info: &mut DocumentInfo,
+) {
+  let styles = &mut info.styles;
+  let mut blocks = blocks.iter().peekable();
+  while let Some(block) = blocks.next() {
+    match block {
+      Block::Heading { spans, .. } => {
+        let bold = TextStyle { bold: true, ..Default::default() };
+        push_spans(spans, col, "", "", bold, lines, styles);
+      }
+      Block::Paragraph(spans) => {
+        // Paragraphs of plain text keep their indentation, up to half the
+        // width
+        let indent = spans.first().map_or(0, |span| {
+          span.text.len() - span.text.trim_start_matches(' ').len()
+        });
+        let indent = " ".repeat(indent.min(col / 2));
+        let plain = TextStyle::default();
+        push_spans(spans, col, &indent, &indent, plain, lines, styles);
+      }
+      Block::Quote(spans) => {
+        push_spans(spans, col, "│ ", "│ ", TextStyle::default(), lines, styles);
+      }
+      Block::ListItem { depth, marker, spans } => {
+        let indent = "  ".repeat(*depth);
+        let first = if marker.is_empty() {
+          format!("{indent}  ")
+        } else {
+          format!("{indent}{marker} ")
+        };
+        let rest = " ".repeat(first.chars().count());
+        let plain = TextStyle::default();
+        push_spans(spans, col, &first, &rest, plain, lines, styles);
+        // Items of a list follow each other like in a bulleted list
+        if matches!(blocks.peek(), Some(Block::ListItem { .. })) {
+          continue;
+        }
+      }
+      Block::CodeBlock(code) => {
+        let style = TextStyle { code: true, ..Default::default() };
+        for line in code.lines() {
+          let line = line.replace('\t', &" ".repeat(TAB_WIDTH));
+          let chars: Vec<char> = line.trim_end().chars().collect();
+          for chunk in chars.chunks(col.max(1)) {
+            let text: String = chunk.iter().collect();
+            if !text.trim().is_empty() {
+              let start = text.len() - text.trim_start().len();
+              let line = lines.len();
+              styles.push(StyledSpan { line, start, end: text.len(), style });
+            }
+            lines.push(text);
+          }
+          if chars.is_empty() {
+            lines.push(String::new());
+          }
+        }
+      }
+      Block::Table { header, rows } => {
+        layout_table(rows, *header, col, lines, styles);
+      }
+      Block::Image { .. } => {
+        let span = Span {
+          text: block.text(),
+          style: SpanStyle { emphasis: true, ..Default::default() },
+        };
+        let plain = TextStyle::default();
+        push_spans(&[span], col, "", "", plain, lines, styles);
+      }
+      Block::PageBreak(number) => {
+        // The part starting at the page may have recorded it already
+        let page = Page { number: *number, line: lines.len() };
+        if info.pages.last() != Some(&page) {
+          info.pages.push(page);
+        }
+        continue;
+      }
+    }
+    lines.push(String::new());
+  }
+}
+
+// Lay out a table in columns separated by `│`, with a rule under the header
+// row, or as one paragraph per row if the columns don't fit in `col`
+fn layout_table(
+  rows: &[Vec<Vec<Span>>],
+  header: bool,
+  col: usize,
+  lines: &mut Vec<String>,
+  styles: &mut Vec<StyledSpan>,
+) {
+  let cell_chars = |cell: &[Span], base: TextStyle| {
+    let mut chars: Vec<(char, TextStyle)> = Vec::new();
+    for span in cell {
+      let style = text_style(base, span.style);
+      chars.extend(
+        span
+          .text
+          .chars()
+          .map(|c| (if c.is_whitespace() { ' ' } else { c }, style)),
+      );
+    }
+    chars
+  };
+  let row_style = |index: usize| TextStyle {
+    bold: header && index == 0,
+    ..Default::default()
+  };
+
+  let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+  let mut widths = vec![0; columns];
+  for row in rows {
+    for (width, cell) in widths.iter_mut().zip(row) {
+      let len: usize = cell.iter().map(|span| span.text.chars().count()).sum();
+      *width = (*width).max(len);
+    }
+  }
+  let total = widths.iter().sum::<usize>() + 3 * columns.saturating_sub(1);
+
+  if total > col {
+    for (index, row) in rows.iter().enumerate() {
+      let mut spans = Vec::new();
+      for (i, cell) in row.iter().enumerate() {
+        if i > 0 {
+          spans.push(Span::plain(" | "));
+        }
+        spans.extend(cell.iter().cloned());
+      }
+      push_spans(&spans, col, "", "", row_style(index), lines, styles);
+      if index + 1 < rows.len() {
+        lines.push(String::new());
+      }
+    }
+    return;
+  }
+
+  let plain = TextStyle::default();
+  for (index, row) in rows.iter().enumerate() {
+    let mut chars = Vec::new();
+    for (i, width) in widths.iter().enumerate() {
+      if i > 0 {
+        chars.extend([(' ', plain), ('│', plain), (' ', plain)]);
+      }
+      let cell = row
+        .get(i)
+        .map_or_else(Vec::new, |cell| cell_chars(cell, row_style(index)));
+      let padding = width - cell.len();
+      chars.extend(cell);
+      chars.extend(std::iter::repeat_n((' ', plain), padding));
+    }
+    push_line(&chars, lines, styles);
+
+    if header && index == 0 && rows.len() > 1 {
+      let rule: Vec<String> = widths.iter().map(|w| "─".repeat(*w)).collect();
+      lines.push(rule.join("─┼─"));
+    }
+  }
+}
+
+// Push a line of styled characters, recording its styled runs, with the
+// trailing whitespace trimmed
+fn push_line(
+  chars: &[(char, TextStyle)],
+  lines: &mut Vec<String>,
+  styles: &mut Vec<StyledSpan>,
+) {
+  let end = chars.iter().rposition(|(c, _)| *c != ' ').map_or(0, |i| i + 1);
+  let line = lines.len();
+  let mut text = String::new();
+  let mut run: Option<StyledSpan> = None;
+  for &(c, style) in &chars[..end] {
+    let start = text.len();
+    text.push(c);
+    if c == ' ' {
+      continue;
+    }
+    match &mut run {
+      Some(current) if current.style == style => current.end = text.len(),
+      _ => {
+        styles.extend(run.take());
+        if style != TextStyle::default() {
+          run = Some(StyledSpan { line, start, end: text.len(), style });
+        }
+      }
+    }
+  }
+  styles.extend(run);
+  lines.push(text);
+}
+
+// Justify inline text to the width left after `first` and `rest`, the
+// prefixes of its first and later lines, and record its styled runs
+fn push_spans(
+  spans: &[Span],
+  col: usize,
+  first: &str,
+  rest: &str,
+  base: TextStyle,
+  lines: &mut Vec<String>,
+  styles: &mut Vec<StyledSpan>,
+) {
+  let width = col.saturating_sub(first.chars().count()).max(1);
+  let mut prefix = first;
+
+  // Hard line breaks end a line without justifying it, the same as the end
+  // of a paragraph does
+  let mut segments: Vec<Vec<(char, SpanStyle)>> = vec![Vec::new()];
+  for span in spans {
+    for c in span.text.chars() {
+      if c == '\n' {
+        segments.push(Vec::new());
+      } else if let Some(segment) = segments.last_mut() {
+        segment.push((c, span.style));
+      }
+    }
+  }
+
+  for segment in segments {
+    let text: String = segment.iter().map(|(c, _)| c).collect();
+    // Justifying only moves whitespace, so the other characters keep their
+    // order and their style
+    let mut char_styles = segment
+      .iter()
+      .filter(|(c, _)| !c.is_whitespace())
+      .map(|&(_, style)| text_style(base, style));
+
+    let justified = cli_justify::justify(&text, width);
+    for body in justified.iter().filter(|line| !line.is_empty()) {
+      let line = lines.len();
+      let mut run: Option<StyledSpan> = None;
+      for (index, c) in body.char_indices() {
+        if c.is_whitespace() {
+          continue;
+        }
+        let style = char_styles.next().unwrap_or_default();
+        let start = prefix.len() + index;
+        let end = start + c.len_utf8();
+        match &mut run {
+          Some(current) if current.style == style => current.end = end,
+          _ => {
+            styles.extend(run.take());
+            if style != TextStyle::default() {
+              run = Some(StyledSpan { line, start, end, style });
+            }
+          }
+        }
+      }
+      styles.extend(run);
+      lines.push(format!("{prefix}{body}"));
+      prefix = rest;
+    }
+  }
+}
+
+fn text_style(base: TextStyle, style: SpanStyle) -> TextStyle {
+  TextStyle {
+    bold: base.bold || style.strong,
+    italic: base.italic || style.emphasis,
+    code: base.code || style.code,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use document_converter::{Part, TocEntry};
+
+  #[test]
+  fn test_parts_justify_like_joined_text() {
+    let content = Content {
+      parts: vec![
+        Part {
+          title: Some("One".to_string()),
+          level: 0,
+          page: Some(1),
+          text: "First chapter.\n\nSecond paragraph.".to_string(),
+          blocks: Vec::new(),
+        },
+        Part {
+          title: Some("Two".to_string()),
+          level: 1,
+          page: Some(2),
+          text: "Another chapter with a longer line of text.".to_string(),
+          blocks: Vec::new(),
+        },
+      ],
+      ..Default::default()
+    };
+
+    let (lines, info) = justify_content(&content, 20);
+    assert_eq!(lines, cli_justify::justify(&content.text(), 20));
+    assert_eq!(info.sections.len(), 2);
+    assert_eq!(info.sections[0].line, 0);
+    assert_eq!(lines[info.sections[1].line], "Another chapter with");
+    assert_eq!(info.sections[1].level, 1);
+    assert_eq!(
+      info.pages,
+      vec![
+        Page { number: 1, line: 0 },
+        Page { number: 2, line: info.sections[1].line }
+      ]
+    );
+  }
+
+  #[test]
+  fn test_blocks_keep_structure_and_styles() {
+    let span = |text: &str, strong: bool| Span {
+      text: text.to_string(),
+      style: SpanStyle { strong, ..Default::default() },
+    };
+    let blocks = vec![
+      Block::Heading { level: 0, spans: vec![span("Usage", false)] },
+      Block::ListItem {
+        depth: 0,
+        marker: "1.".to_string(),
+        spans: vec![span("Open a ", false), span("very long", true)],
+      },
+      Block::ListItem {
+        depth: 1,
+        marker: "•".to_string(),
+        spans: vec![span("nested", false)],
+      },
+      Block::CodeBlock("fn main() {\n\tgo();\n}".to_string()),
+    ];
+    let mut lines = Vec::new();
+    let mut info = DocumentInfo::default();
+    layout_blocks(&blocks, 12, &mut lines, &mut info);
+    let styles = info.styles;
+
+    assert_eq!(
+      lines,
+      [
+        "Usage",
+        "",
+        "1. Open    a",
+        "   very long",
+        "  • nested",
+        "",
+        "fn main() {",
+        "    go();",
+        "}",
+        "",
+      ]
+    );
+    let runs: Vec<_> = styles
+      .iter()
+      .map(|span| (span.line, &lines[span.line][span.start..span.end]))
+      .collect();
+    assert_eq!(
+      runs,
+      [
+        (0, "Usage"),
+        (3, "very long"),
+        (6, "fn main() {"),
+        (7, "go();"),
+        (8, "}")
+      ]
+    );
+    assert!(styles[0].style.bold && styles[1].style.bold);
+    assert!(styles[2].style.code);
+  }
+
+  #[test]
+  fn test_tables_images_and_page_breaks() {
+    let cell = |text: &str| vec![Span::plain(text)];
+    let table = Block::Table {
+      header: true,
+      rows: vec![
+        vec![cell("Item"), cell("Price")],
+        vec![cell("Steel beams"), cell("1,200 €")],
+        vec![cell("Bolts")],
+      ],
+    };
+    let blocks = vec![
+      Block::PageBreak(1),
+      table.clone(),
+      Block::Image { alt: "Floor plan".to_string() },
+      Block::PageBreak(2),
+      Block::Paragraph(cell("Signed.")),
+    ];
+    let mut lines = Vec::new();
+    let mut info = DocumentInfo::default();
+    layout_blocks(&blocks, 30, &mut lines, &mut info);
+
+    assert_eq!(
+      lines,
+      [
+        "Item        │ Price",
+        "────────────┼────────",
+        "Steel beams │ 1,200 €",
+        "Bolts       │",
+        "",
+        "[Floor plan]",
+        "",
+        "Signed.",
+        "",
+      ]
+    );
+    assert_eq!(
+      info.pages,
+      [Page { number: 1, line: 0 }, Page { number: 2, line: 7 }]
+    );
+    let runs: Vec<_> = info
+      .styles
+      .iter()
+      .map(|span| (&lines[span.line][span.start..span.end], span.style))
+      .collect();
+    let bold = TextStyle { bold: true, ..Default::default() };
+    let italic = TextStyle { italic: true, ..Default::default() };
+    assert_eq!(
+      runs,
+      [("Item", bold), ("Price", bold), ("[Floor plan]", italic)]
+    );
+
+    // Rows are paragraphs when the columns don't fit
+    let mut lines = Vec::new();
+    layout_blocks(&[table], 12, &mut lines, &mut DocumentInfo::default());
+    assert_eq!(
+      lines,
+      ["Item | Price", "", "Steel  beams", "| 1,200 €", "", "Bolts", ""]
+    );
+  }
+
+  #[test]
+  fn test_paragraphs_keep_their_indentation() {
+    let text = "Body text.\n\n    An indented quote that wraps.";
+    let part = Part::from_text(None, 0, text.to_string());
+    let mut lines = Vec::new();
+    layout_blocks(&part.blocks, 20, &mut lines, &mut DocumentInfo::default());
+    assert_eq!(
+      lines,
+      [
+        "Body text.",
+        "",
+        "    An      indented",
+        "    quote       that",
+        "    wraps.",
+        "",
+      ]
+    );
+  }
+
+  #[test]
+  fn test_toc_entries_start_at_their_page() {
+    // Parts of pages like the PDF converter's, which start with a page break
+    let content = Content {
+      parts: (1..=3)
+        .map(|page| {
+          let text = format!("Page {page} text.");
+          Part {
+            title: None,
+            level: 0,
+            page: Some(page),
+            blocks: vec![
+              Block::PageBreak(page),
+              Block::Paragraph(vec![Span::plain(&text)]),
+            ],
+            text,
+          }
+        })
+        .collect(),
+      toc: vec![
+        TocEntry { title: "Appendix".to_string(), page: 3, level: 0 },
+        TocEntry { title: "Start".to_string(), page: 1, level: 0 },
+        TocEntry { title: "Detail".to_string(), page: 2, level: 1 },
+        TocEntry { title: "Missing".to_string(), page: 9, level: 0 },
+      ],
+      ..Default::default()
+    };
+
+    let (_, info) = justify_content(&content, 20);
+    assert_eq!(info.pages.len(), 3);
+    let sections: Vec<_> = info
+      .sections
+      .iter()
+      .map(|section| (section.title.as_str(), section.line, section.level))
+      .collect();
+    assert_eq!(
+      sections,
+      vec![
+        ("Start", info.pages[0].line, 0),
+        ("Detail", info.pages[1].line, 1),
+        ("Appendix", info.pages[2].line, 0),
+      ]
+    );
+  }
+}
@@ -1,5 +1,15 @@
-use clap::Parser;
-use std::env;
+mod cache;
+mod compression;
+mod converters;
+mod layout;
+mod man;
+mod markdown;
+mod notebook;
+mod ocr;
+mod text;
+
+use clap::{Parser, Subcommand};
+use document_converter::{Content, ConversionError, Input, which};
 use std::io::{self, Read};
 
 /// Simplifying the way you read
@@ -10,6 +20,7 @@ use std::io::{self, Read};
     author = env!("CARGO_PKG_AUTHORS"),
     about = env!("CARGO_PKG_DESCRIPTION"),
     long_about = None,
+    args_conflicts_with_subcommands = true,
     help_template = concat!(
         "{before-help}{name} {version}\n",
         "{author-with-newline}{about-with-newline}",
@@ -20,6 +31,9 @@ use std::io::{self, Read};
     )
 )]
 struct Args {
+  #[command(subcommand)]
+  command: Option<Command>,
+
   /// Input file to process
   file: Option<String>,
 
@@ -27,11 +41,24 @@ struct Args {
   #[arg(short, long, default_value = "80")]
   col: usize,
 
-  /// Use OCR to extract text from scanned PDF documents
+  /// Use OCR to extract text from scanned PDF documents, `auto` only OCRs
+  /// pages without text and `--ocr` alone OCRs every page.
   /// Depends on ocrmypdf and tesseract-ocr lang e.g.
   /// sudo apt install ocrmypdf tesseract-ocr-eng
-  #[arg(short, long, default_value = "false")]
-  ocr: bool,
+  #[arg(
+    short,
+    long,
+    value_enum,
+    default_value = "never",
+    num_args = 0..=1,
+    require_equals = true,
+    default_missing_value = "always"
+  )]
+  ocr: ocr::OcrMode,
+
+  /// Tesseract languages to OCR with, e.g. eng or eng+deu
+  #[arg(long, value_name = "LANG")]
+  ocr_lang: Option<String>,
 
   /// Use the hygg server upload
   #[arg(short, long)]
@@ -64,34 +91,77 @@ struct Args {
   /// Run custom demo from component list
   #[arg(long)]
   demo_compose: Option<String>,
+
+  /// List available document converters, and the order they are tried in
+  /// for the given file
+  #[arg(long)]
+  list_converters: bool,
+
+  /// Read text input in this encoding instead of detecting it,
+  /// e.g. latin1, windows-1252, utf-16le
+  #[arg(long, value_parser = text::parse_encoding)]
+  encoding: Option<&'static encoding_rs::Encoding>,
+
+  /// Password for encrypted PDFs, asked for on the terminal when it is
+  /// needed and not given
+  #[arg(long)]
+  password: Option<String>,
+
+  /// Convert the file again instead of reading it from the conversion cache
+  #[arg(long)]
+  no_cache: bool,
 }
 
-pub fn which(binary: &str) -> Option<std::path::PathBuf> {
-  if let Ok(paths) = env::var("PATH") {
-    for path in env::split_paths(&paths) {
-      let full_path = path.join(binary);
-      if full_path.is_file() {
-        return Some(full_path);
-      }
-    }
-  }
-  None
+#[derive(Subcommand)]
+enum Command {
+  /// Manage the cache of converted documents, limited to HYGG_CACHE_SIZE
+  /// MiB, 512 by default
+  Cache {
+    #[command(subcommand)]
+    action: CacheAction,
+  },
+}
+
+#[derive(Subcommand)]
+enum CacheAction {
+  /// Show where the cache is and how much it holds
+  Stats,
+  /// Remove every cached document
+  Clear,
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
   let args = Args::parse();
+  silence_pdf_page_panics();
+  let options = converters::Options {
+    encoding: args.encoding,
+    password: args.password.clone(),
+    prompt_password: atty::is(atty::Stream::Stdin)
+      && atty::is(atty::Stream::Stderr),
+    ocr: args.ocr,
+    ocr_lang: args.ocr_lang.clone(),
+  };
 
   // Check if stdin has content
   let stdin_content = if atty::is(atty::Stream::Stdin) {
     None
   } else {
-    let mut buffer = String::new();
-    match io::stdin().read_to_string(&mut buffer) {
+    let mut buffer = Vec::new();
+    match io::stdin().read_to_end(&mut buffer) {
       Ok(_) => {
         if buffer.is_empty() {
           None
         } else {
-          Some(buffer)
+          // Compressed text such as `curl …/notes.txt.gz | hygg`
+          match compression::decompress(&buffer) {
+            Ok(Some(data)) => buffer = data,
+            Ok(None) => {}
+            Err(e) => {
+              eprintln!("Error:\nUnable to decompress stdin\n\nDetails:\n{e}");
+              std::process::exit(1);
+            }
+          }
+          Some(text::decode_text(&buffer, args.encoding).0)
         }
       }
       Err(_) => None,
@@ -141,6 +211,41 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     return Ok(());
   }
 
+  // Handle cache management
+  if let Some(Command::Cache { action }) = &args.command {
+    let cache = cache::Cache::open().ok_or("Unable to find cache directory")?;
+    match action {
+      CacheAction::Stats => {
+        let stats = cache.stats()?;
+        println!("Cache directory: {}", cache.dir().display());
+        println!("Documents: {}", stats.entries);
+        println!(
+          "Size: {} of {}",
+          format_size(stats.bytes),
+          format_size(cache.limit())
+        );
+      }
+      CacheAction::Clear => {
+        let removed = cache.clear()?;
+        println!(
+          "Removed {} cached document(s), {}",
+          removed.entries,
+          format_size(removed.bytes)
+        );
+      }
+    }
+    return Ok(());
+  }
+
+  // Handle list converters
+  if args.list_converters {
+    converters::list_converters(
+      &converters::default_registry(&options),
+      args.file.as_deref(),
+    );
+    return Ok(());
+  }
+
   // Handle demo compose
   if let Some(component_list) = args.demo_compose {
     // For custom composed demos, we'll use the existing demo infrastructure
@@ -190,112 +295,64 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
   };
 
   // If stdin has content, use it directly
-  let (lines, temp_file, raw_content) = if let Some(content) = stdin_content {
-    let lines = cli_justify::justify(&content, args.col);
-    (lines, None, Some(content))
-  } else if let Some(file) = file {
-    let temp_file = format!("{file}-{}", uuid::Uuid::new_v4());
-
-    let content = if (args.ocr && which("ocrmypdf").is_some()) {
-      // Validate file path to prevent command injection
-      if let Err(e) = validate_file_path(&file) {
-        eprintln!("Error: Invalid file path: {e}");
-        std::process::exit(1);
-      }
-
-      // Additional validation for temp file path
-      if temp_file.contains("..")
-        || temp_file.contains(";")
-        || temp_file.contains("|")
-        || temp_file.contains("&")
-      {
-        eprintln!("Error: Invalid temporary file path");
-        std::process::exit(1);
-      }
-
-      // Use Command with explicit arguments to prevent shell injection
-      let mut cmd = std::process::Command::new("ocrmypdf");
-      cmd
-        .arg("--force-ocr")
-        .arg("--") // End of options marker
-        .arg(&file)
-        .arg(&temp_file)
-        .stdin(std::process::Stdio::null())
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped());
-
-      let output = cmd.output().map_err(|e| e.to_string())?;
-
-      if !output.status.success() {
-        eprintln!("OCR processing failed");
-        std::process::exit(1);
-      }
-
-      cli_pdf_to_text::pdf_to_text(&temp_file)?
+  let (lines, raw_content, document_info) = if let Some(content) = stdin_content
+  {
+    let document = if markdown::looks_like_markdown(&content) {
+      markdown::markdown_content(&content)
     } else {
-      // Check file extension first for better format routing
-      let extension = std::path::Path::new(&file)
-        .extension()
-        .and_then(|ext| ext.to_str())
-        .map(|ext| ext.to_lowercase());
-
-      match extension.as_deref() {
-        Some("epub") => match cli_epub_to_text::epub_to_text(&file) {
-          Ok(content) => content,
-          Err(e) => {
-            eprintln!("Error:\nUnable to read EPUB file '{file}'\n");
-            eprintln!("Details:\n{e}\n");
-            std::process::exit(1);
-          }
-        },
-        Some("pdf") => match cli_pdf_to_text::pdf_to_text(&file) {
-          Ok(content) => content,
-          Err(e) => {
-            eprintln!("Error:\nUnable to read PDF file '{file}'\n");
-            eprintln!("Details:\n{e}\n");
-            std::process::exit(1);
-          }
-        },
-        _ => {
-          // For other formats, try pandoc first, then fall back to other
-          // converters
-          match pandoc_to_text(&file)
-            .or_else(|_| cli_epub_to_text::epub_to_text(&file))
-            .or_else(|_| cli_pdf_to_text::pdf_to_text(&file))
-          {
-            Ok(content) => content,
-            Err(e) => {
-              eprintln!("Error:\nUnable to read file '{file}'\n");
-              eprintln!("Details:\n{e}\n");
-
-              if which("pandoc").is_none() {
-                eprintln!(
-                  "pandoc not installed!\nFor additional formats, install pandoc:\nsudo apt install pandoc"
-                );
-              }
-              std::process::exit(1);
-            }
-          }
+      Content::from(content.clone())
+    };
+    let (lines, document_info) = layout::justify_content(&document, args.col);
+    (lines, Some(content), document_info)
+  } else if let Some(file) = file {
+    let cache = if args.no_cache { None } else { cache::Cache::open() };
+    let content = match cache::convert(
+      &converters::default_registry(&options),
+      &Input::new(&file),
+      &options,
+      cache.as_ref(),
+    ) {
+      Ok(content) => content,
+      Err(e) => {
+        eprintln!("Error:\nUnable to read {} '{file}'\n", e.format());
+        eprintln!("Details:\n{e}\n");
+
+        let pandoc_attempted = matches!(
+          &e,
+          ConversionError::AllFailed { attempts, .. }
+            if attempts.iter().any(|attempt| attempt.converter == "pandoc")
+        );
+        if pandoc_attempted && which("pandoc").is_none() {
+          eprintln!(
+            "pandoc not installed!\nFor additional formats, install pandoc:\nsudo apt install pandoc"
+          );
         }
+        std::process::exit(1);
       }
     };
 
-    let lines = cli_justify::justify(&content, args.col);
+    let (lines, document_info) = layout::justify_content(&content, args.col);
 
     // Check if we have any content to display
     if lines.is_empty() || (lines.len() == 1 && lines[0].trim().is_empty()) {
       eprintln!("Error: No readable content found in file '{file}'");
-      eprintln!(
-        "The file may be empty, corrupted, or in an unsupported format."
-      );
+      if content.warnings.is_empty() {
+        eprintln!(
+          "The file may be empty, corrupted, or in an unsupported format."
+        );
+      }
+      // Such as scanned pages that weren't OCRed
+      for warning in &content.warnings {
+        eprintln!("{warning}");
+      }
       std::process::exit(1);
     }
 
-    (lines, Some(temp_file), Some(content))
+    (lines, Some(content.text()), document_info)
   } else {
     // No file provided - start with empty content
     // Users can access tutorial with :tutorial command
-    (vec![], None, None)
+    (vec![], None, Default::default())
   };
 
   // Now redirect stderr after file validation is complete
@@ -306,26 +363,38 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
   // Pass raw content for consistent hashing across different column widths
   if let Some(content) = raw_content {
-    cli_text_reader::run_cli_text_reader_with_content(
+    cli_text_reader::run_cli_text_reader_with_info(
       lines,
       args.col,
       Some(content),
+      document_info,
       false,
     )?;
   } else {
     cli_text_reader::run_cli_text_reader(lines, args.col)?;
   }
 
-  if let Some(temp_file) = temp_file
-    && std::path::Path::new(&temp_file).exists()
-  {
-    std::fs::remove_file(&temp_file)?;
-  }
-
   Ok(())
 }
 
-// Validate file path to prevent command injection
+// Pages of a PDF the extractor panics on are reported as warnings of the
+// conversion, keep the default hook from also printing over the reader
+fn silence_pdf_page_panics() {
+  let default_hook = std::panic::take_hook();
+  std::panic::set_hook(Box::new(move |info| {
+    if !cli_pdf_to_text::is_extracting_page() {
+      default_hook(info);
+    }
+  }));
+}
+
+// Format a size in bytes as MiB, e.g. `12.4 MiB`
+fn format_size(bytes: u64) -> String {
+  format!("{:.1} MiB", bytes as f64 / (1 << 20) as f64)
+}
+
+// Validate a file path before it is passed to pandoc or ocrmypdf, to prevent
+// command injection
 fn validate_file_path(file_path: &str) -> Result<(), String> {
   // Check for dangerous characters that could be used for command injection
   let dangerous_chars =
@@ -357,38 +426,3 @@ fn validate_file_path(file_path: &str) -> Result<(), String> {
 
   Ok(())
 }
-
-// Convert document to text using pandoc
-fn pandoc_to_text(
-  file_path: &str,
-) -> Result<String, Box<dyn std::error::Error>> {
-  // Check if pandoc is available
-  if which("pandoc").is_none() {
-    return Err(
-      "pandoc not found. Install with: sudo apt install pandoc".into(),
-    );
-  }
-
-  // Validate file path
-  validate_file_path(file_path)?;
-
-  // Run pandoc with plain text output
-  let mut cmd = std::process::Command::new("pandoc");
-  cmd
-    .arg("--to=plain")
-    .arg("--wrap=none")
-    .arg("--")
-    .arg(file_path)
-    .stdin(std::process::Stdio::null())
-    .stdout(std::process::Stdio::piped())
-    .stderr(std::process::Stdio::piped());
-
-  let output = cmd.output()?;
-
-  if !output.status.success() {
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    return Err(format!("pandoc failed: {stderr}").into());
-  }
-
-  Ok(String::from_utf8(output.stdout)?)
-}
@@ -0,0 +1,883 @@
+use document_converter::{Block, Content, Metadata, Span, SpanStyle};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::compression;
+use crate::markdown;
+
+/// Tags of `.IP` up to this long are shown as the item's marker, like
+/// bullets, longer ones and words on a line of their own
+const MARKER_LEN: usize = 4;
+
+/// How much of a file is read to see if it starts like a manual page
+const HEAD_LEN: u64 = 4096;
+
+/// Requests and comments manual pages start with
+const PAGE_STARTS: &[&str] = &[".TH", ".SH", ".Dd", ".so", ".\\\"", "'\\\""];
+
+/// Whether the file at `path` is a manual page
+///
+/// Rotated logs are named like pages, as in `app.log.1`, so the name alone
+/// isn't enough: the page also has to be in a section directory such as
+/// `man1`, or start with roff requests.
+pub fn is_man_page(path: &Path) -> bool {
+  let name = path.file_name().and_then(|name| name.to_str());
+  if !name.is_some_and(is_man_page_name) {
+    return false;
+  }
+  let in_section = path
+    .parent()
+    .and_then(Path::file_name)
+    .and_then(|dir| dir.to_str()?.strip_prefix("man"))
+    .is_some_and(is_section);
+  in_section
+    || compression::read_head(path, HEAD_LEN)
+      .is_ok_and(|head| starts_like_page(&String::from_utf8_lossy(&head)))
+}
+
+// Whether `name` is named like a manual page, such as `ls.1`, `CA.pl.1ssl`
+// or `ls.1.gz`
+fn is_man_page_name(name: &str) -> bool {
+  let name = compression::inner_name(name);
+  let Some((stem, section)) = name.rsplit_once('.') else {
+    return false;
+  };
+  !stem.is_empty() && (section == "man" || is_section(section))
+}
+
+// Whether `section` is a section of the manual, such as `1`, `3p` or `n`
+fn is_section(section: &str) -> bool {
+  let mut chars = section.chars();
+  chars.next().is_some_and(|c| matches!(c, '1'..='9' | 'n'))
+    && chars.all(|c| c.is_ascii_alphanumeric())
+    && section.len() <= 6
+}
+
+// Whether the first line with text is a request or comment pages start with
+fn starts_like_page(source: &str) -> bool {
+  source
+    .lines()
+    .map(str::trim_start)
+    .find(|line| !line.is_empty())
+    .is_some_and(|line| PAGE_STARTS.iter().any(|start| line.starts_with(start)))
+}
+
+/// The page a manual page only points to with `.so`, e.g. `man1/ls.1`
+pub fn include_target(source: &str) -> Option<&str> {
+  let line = source
+    .lines()
+    .map(str::trim)
+    .find(|line| !line.is_empty() && !line.starts_with(".\\\""))?;
+  let target = line.strip_prefix(".so")?.trim();
+  (!target.is_empty()).then_some(target)
+}
+
+/// Whether a page, maybe compressed, is an alias that only includes another
+/// page with `.so`
+pub fn is_alias(bytes: &[u8]) -> bool {
+  let page = compression::decompress(bytes).ok().flatten();
+  let source = String::from_utf8_lossy(page.as_deref().unwrap_or(bytes));
+  include_target(&source).is_some()
+}
+
+/// Read a roff manual page written with the man(7) macros into parts that
+/// start at its sections
+///
+/// `.SH` sections such as NAME, SYNOPSIS and DESCRIPTION are top level
+/// parts and `.SS` subsections are nested in them. Tagged paragraphs, the
+/// option lists of most pages, are shown as the tag with its description
+/// indented below it.
+pub fn man_content(source: &str) -> Content {
+  let mut reader = Reader::default();
+  for line in source.lines() {
+    reader.line(line);
+  }
+  reader.end_code();
+  reader.flush();
+  Content {
+    parts: markdown::split_parts(reader.blocks),
+    metadata: Metadata { title: reader.title, ..Default::default() },
+    ..Default::default()
+  }
+}
+
+/// What the next line of text is
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Next {
+  /// The title of a `.SH` or `.SS` given on the following line
+  Heading(usize),
+  /// The tag of a `.TP`
+  Tag,
+}
+
+// Reads the lines of a page into blocks
+#[derive(Default)]
+struct Reader {
+  blocks: Vec<Block>,
+  title: Option<String>,
+  // Inline text of the paragraph being read
+  spans: Vec<Span>,
+  // List depth and marker of the paragraph being read, `None` for a plain
+  // paragraph
+  item: Option<(usize, String)>,
+  // Indentation of `.RS`
+  depth: usize,
+  next: Option<Next>,
+  // Font set by `\f` escapes, and the one before it for `\fP`
+  font: SpanStyle,
+  previous_font: SpanStyle,
+  // Font of `.B` and the like without arguments, for the next line
+  line_font: Option<SpanStyle>,
+  // Text of `.nf` or `.EX`, which is shown as it is
+  code: Option<String>,
+  // The last line ended with `\c`, the next one continues it
+  continued: bool,
+  // Strings defined with `.ds`
+  strings: HashMap<String, String>,
+  // Link target of `.UR` or `.MT`
+  link: Option<String>,
+  // The line that ends a macro definition being skipped, e.g. `..`
+  definition: Option<String>,
+  // Nesting of `\{` blocks of a false condition
+  skipped_blocks: usize,
+  // Result of the last `.ie`, for its `.el`
+  condition: bool,
+}
+
+impl Reader {
+  fn line(&mut self, line: &str) {
+    if let Some(end) = &self.definition {
+      if line.trim_end() == end {
+        self.definition = None;
+      }
+      return;
+    }
+    if self.skipped_blocks > 0 {
+      self.skipped_blocks += line.matches("\\{").count();
+      self.skipped_blocks =
+        self.skipped_blocks.saturating_sub(line.matches("\\}").count());
+      return;
+    }
+
+    let line = strip_comment(line).replace("\\}", "");
+    if let Some(request) =
+      line.strip_prefix('.').or_else(|| line.strip_prefix('\''))
+    {
+      let request = request.trim_start();
+      let (name, arguments) =
+        request.split_once([' ', '\t']).unwrap_or((request, ""));
+      self.request(name, arguments);
+      return;
+    }
+
+    if let Some(code) = &mut self.code {
+      let text = render_plain(&mut self.font, &self.strings, &line);
+      code.push_str(&text);
+      code.push('\n');
+      return;
+    }
+
+    if line.trim().is_empty() {
+      self.flush();
+      return;
+    }
+    // Lines starting with a space start a new line of output
+    if line.starts_with([' ', '\t']) && self.next.is_none() {
+      self.push_break();
+    }
+    let (spans, continued) = match self.line_font.take() {
+      Some(font) => self.render_in(font, &line),
+      None => self.render(&line),
+    };
+    self.text(spans, continued);
+  }
+
+  fn request(&mut self, name: &str, arguments: &str) {
+    match name {
+      "TH" => {
+        let arguments = split_arguments(arguments);
+        if let Some(title) = arguments.first() {
+          self.title = Some(match arguments.get(1) {
+            Some(section) => format!("{title}({section})"),
+            None => title.clone(),
+          });
+        }
+      }
+      "SH" | "SS" | "Sh" | "Ss" => {
+        self.end_code();
+        self.flush();
+        self.depth = 0;
+        self.item = None;
+        let level = usize::from(matches!(name, "SS" | "Ss"));
+        let title = split_arguments(arguments).join(" ");
+        if title.is_empty() {
+          self.next = Some(Next::Heading(level));
+        } else {
+          let (spans, _) = self.render_in(SpanStyle::default(), &title);
+          self.text_as(Next::Heading(level), spans);
+        }
+      }
+      "PP" | "P" | "LP" | "HP" | "Pp" => {
+        self.flush();
+        self.item = None;
+      }
+      "TP" => {
+        self.flush();
+        self.next = Some(Next::Tag);
+      }
+      "TQ" => self.next = Some(Next::Tag),
+      "IP" => {
+        self.flush();
+        let tag = split_arguments(arguments).into_iter().next();
+        let (spans, _) = self.render(tag.as_deref().unwrap_or_default());
+        let tag = markdown::trim(spans);
+        let marker: String =
+          tag.iter().map(|span| span.text.as_str()).collect();
+        // Bullets and numbers, but not options such as `-crl`
+        let letters = marker.chars().filter(|c| c.is_alphabetic()).count();
+        if marker.chars().count() <= MARKER_LEN && letters <= 1 {
+          self.item = Some((self.depth, marker));
+        } else {
+          self.text_as(Next::Tag, tag);
+        }
+      }
+      "RS" => {
+        self.flush();
+        self.depth += 1;
+        self.item = None;
+      }
+      "RE" => {
+        self.flush();
+        self.depth = self.depth.saturating_sub(1);
+        self.item = None;
+      }
+      "br" => self.push_break(),
+      // pod2man defines `Sp`, `Vb` and `Ve` for pages written in Perl's POD
+      "sp" | "Sp" => match &mut self.code {
+        Some(code) => code.push('\n'),
+        None => self.flush(),
+      },
+      "nf" | "EX" | "Vb" => {
+        self.flush();
+        self.code.get_or_insert_with(String::new);
+      }
+      "fi" | "EE" | "Ve" => self.end_code(),
+
+      "B" | "I" | "SB" | "SM" => {
+        let font = match name {
+          "I" => SpanStyle { emphasis: true, ..Default::default() },
+          "SM" => SpanStyle::default(),
+          _ => SpanStyle { strong: true, ..Default::default() },
+        };
+        let text = split_arguments(arguments).join(" ");
+        if text.is_empty() {
+          self.line_font = Some(font);
+        } else {
+          self.font_line(&[(font, text)]);
+        }
+      }
+      "BR" | "BI" | "IB" | "IR" | "RB" | "RI" => {
+        // The fonts alternate between the arguments, without spaces
+        let fonts: Vec<SpanStyle> = name
+          .chars()
+          .map(|font| SpanStyle {
+            strong: font == 'B',
+            emphasis: font == 'I',
+            code: false,
+          })
+          .collect();
+        let runs: Vec<(SpanStyle, String)> = split_arguments(arguments)
+          .into_iter()
+          .enumerate()
+          .map(|(index, text)| (fonts[index % 2], text))
+          .collect();
+        self.font_line(&runs);
+      }
+
+      "SY" => {
+        self.flush();
+        self.item = None;
+        let bold = SpanStyle { strong: true, ..Default::default() };
+        self.font_line(&[(bold, split_arguments(arguments).join(" "))]);
+      }
+      "OP" => {
+        let mut arguments = split_arguments(arguments).into_iter();
+        let bold = SpanStyle { strong: true, ..Default::default() };
+        let italic = SpanStyle { emphasis: true, ..Default::default() };
+        let mut runs = vec![(SpanStyle::default(), "[".to_string())];
+        runs.extend(arguments.next().map(|option| (bold, option)));
+        for argument in arguments {
+          runs.push((SpanStyle::default(), " ".to_string()));
+          runs.push((italic, argument));
+        }
+        runs.push((SpanStyle::default(), "]".to_string()));
+        self.font_line(&runs);
+      }
+      "YS" => {
+        self.flush();
+      }
+      "UR" | "MT" => {
+        self.link = split_arguments(arguments).into_iter().next();
+      }
+      "UE" | "ME" => {
+        let mut text = String::new();
+        if let Some(link) = self.link.take() {
+          text = format!(" <{link}>");
+        }
+        text.push_str(&split_arguments(arguments).join(""));
+        self.continued = true;
+        let (spans, _) = self.render_in(SpanStyle::default(), &text);
+        self.text(spans, false);
+      }
+
+      "ds" => {
+        let (name, value) =
+          arguments.trim_start().split_once(' ').unwrap_or((arguments, ""));
+        let value = value.strip_prefix('"').unwrap_or(value);
+        self.strings.insert(name.trim().to_string(), value.to_string());
+      }
+      "de" | "de1" | "am" | "ig" => {
+        // `.ig` only takes the end macro, the others a name first
+        let arguments = split_arguments(arguments);
+        let end = arguments.get(usize::from(name != "ig"));
+        self.definition = Some(format!(".{}", end.map_or(".", String::as_str)));
+      }
+      "if" | "ie" => {
+        let (holds, body) = condition(arguments);
+        if name == "ie" {
+          self.condition = holds;
+        }
+        self.conditional(holds, body);
+      }
+      "el" => self.conditional(!self.condition, arguments),
+      _ => {}
+    }
+  }
+
+  // Read the body of a condition, which may open a `\{` block
+  fn conditional(&mut self, holds: bool, body: &str) {
+    let body = body.trim_start();
+    let opens = body.starts_with("\\{");
+    let body = body.trim_start_matches("\\{").trim_start();
+    if holds {
+      if !body.is_empty() {
+        self.line(body);
+      }
+    } else if opens && !body.contains("\\}") {
+      self.skipped_blocks = 1;
+    }
+  }
+
+  // Text of font macros, which is read like a line of text
+  fn font_line(&mut self, runs: &[(SpanStyle, String)]) {
+    let mut spans = Vec::new();
+    let mut continued = false;
+    for (font, text) in runs {
+      let (run, run_continued) = self.render_in(*font, text);
+      spans.extend(run);
+      continued = run_continued;
+    }
+    if let Some(code) = &mut self.code {
+      let text: String = spans.iter().map(|span| span.text.as_str()).collect();
+      code.push_str(&text);
+      code.push('\n');
+      return;
+    }
+    self.text(spans, continued);
+  }
+
+  fn text(&mut self, spans: Vec<Span>, continued: bool) {
+    // Lines of escapes that print nothing
+    if markdown::is_blank(&spans)
+      && !spans.iter().any(|s| s.text.contains('\n'))
+    {
+      self.continued |= continued;
+      return;
+    }
+    match self.next.take() {
+      Some(next) => self.text_as(next, spans),
+      None => {
+        if !self.continued
+          && self.spans.last().is_some_and(|span| !span.text.ends_with('\n'))
+        {
+          push_span(&mut self.spans, " ", SpanStyle::default());
+        }
+        for span in spans {
+          push_span(&mut self.spans, &span.text, span.style);
+        }
+      }
+    }
+    self.continued = continued;
+  }
+
+  fn text_as(&mut self, next: Next, spans: Vec<Span>) {
+    if markdown::is_blank(&spans) {
+      return;
+    }
+    let spans = markdown::trim(spans);
+    match next {
+      Next::Heading(level) => {
+        self.flush();
+        self.blocks.push(Block::Heading { level, spans });
+      }
+      Next::Tag => {
+        self.flush();
+        self.blocks.push(Block::ListItem {
+          depth: self.depth,
+          marker: String::new(),
+          spans,
+        });
+        self.item = Some((self.depth + 1, String::new()));
+      }
+    }
+  }
+
+  fn push_break(&mut self) {
+    if !markdown::is_blank(&self.spans) {
+      push_span(&mut self.spans, "\n", SpanStyle::default());
+    }
+  }
+
+  fn render(&mut self, text: &str) -> (Vec<Span>, bool) {
+    render(&mut self.font, &mut self.previous_font, &self.strings, text)
+  }
+
+  // Render text of a font macro, which leaves the font as it was
+  fn render_in(&mut self, font: SpanStyle, text: &str) -> (Vec<Span>, bool) {
+    let (mut current, mut previous) = (font, self.font);
+    render(&mut current, &mut previous, &self.strings, text)
+  }
+
+  fn end_code(&mut self) {
+    if let Some(code) = self.code.take() {
+      let code = code.trim_matches('\n');
+      if !code.trim().is_empty() {
+        self.blocks.push(Block::CodeBlock(code.to_string()));
+      }
+    }
+  }
+
+  // Turn the inline text read so far into a paragraph or list item
+  fn flush(&mut self) {
+    self.continued = false;
+    let spans = std::mem::take(&mut self.spans);
+    if markdown::is_blank(&spans) {
+      return;
+    }
+    let spans = markdown::trim(spans);
+    let block = match &mut self.item {
+      // Later paragraphs of an item don't repeat its marker
+      Some((depth, marker)) => {
+        Block::ListItem { depth: *depth, marker: std::mem::take(marker), spans }
+      }
+      None if self.depth > 0 => {
+        Block::ListItem { depth: self.depth - 1, marker: String::new(), spans }
+      }
+      None => Block::Paragraph(spans),
+    };
+    self.blocks.push(block);
+  }
+}
+
+// Whether the condition of `.if` or `.ie` holds, and the rest of the line
+//
+// Only the output device is known, this is a terminal and not a typesetter.
+// Conditions on registers and strings are taken as false.
+fn condition(arguments: &str) -> (bool, &str) {
+  let arguments = arguments.trim_start();
+  let (condition, body) =
+    arguments.split_once([' ', '\t']).unwrap_or((arguments, ""));
+  (matches!(condition, "n" | "!t"), body)
+}
+
+// Cut a line at its `\"` comment
+fn strip_comment(line: &str) -> &str {
+  let mut chars = line.char_indices();
+  while let Some((_, c)) = chars.next() {
+    if c == '\\'
+      && let Some((at, next)) = chars.next()
+      && (next == '"' || next == '#')
+    {
+      return &line[..at - 1];
+    }
+  }
+  line
+}
+
+// Split macro arguments at spaces, keeping quoted arguments together
+fn split_arguments(arguments: &str) -> Vec<String> {
+  let mut list = Vec::new();
+  let mut chars = arguments.chars().peekable();
+  loop {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+    let Some(first) = chars.next() else {
+      return list;
+    };
+    let mut argument = String::new();
+    if first == '"' {
+      while let Some(c) = chars.next() {
+        match c {
+          // Two quotes are a quote within the argument
+          '"' if chars.next_if_eq(&'"').is_some() => argument.push('"'),
+          '"' => break,
+          c => argument.push(c),
+        }
+      }
+    } else {
+      argument.push(first);
+      while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+        argument.push(c);
+        // Escaped spaces are part of the argument
+        if c == '\\'
+          && let Some(next) = chars.next()
+        {
+          argument.push(next);
+        }
+      }
+    }
+    list.push(argument);
+  }
+}
+
+// Render text with its escapes to spans, returning whether it ends with
+// `\c` to continue on the next line
+fn render(
+  font: &mut SpanStyle,
+  previous_font: &mut SpanStyle,
+  strings: &HashMap<String, String>,
+  text: &str,
+) -> (Vec<Span>, bool) {
+  let mut spans = Vec::new();
+  let mut continued = false;
+  let mut chars = text.chars().peekable();
+  while let Some(c) = chars.next() {
+    if c != '\\' {
+      push_span(&mut spans, c.encode_utf8(&mut [0; 4]), *font);
+      continue;
+    }
+    // A backslash at the end joins the next line
+    let Some(escape) = chars.next() else {
+      continued = true;
+      break;
+    };
+    match escape {
+      'f' => {
+        let name = escape_name(&mut chars);
+        let next = match name.as_str() {
+          "P" => *previous_font,
+          "B" | "3" => SpanStyle { strong: true, ..Default::default() },
+          "I" | "2" => SpanStyle { emphasis: true, ..Default::default() },
+          "BI" | "4" => SpanStyle { strong: true, emphasis: true, code: false },
+          "C" | "CW" | "CR" => SpanStyle { code: true, ..Default::default() },
+          "CB" => SpanStyle { strong: true, code: true, emphasis: false },
+          "CI" => SpanStyle { emphasis: true, code: true, strong: false },
+          _ => SpanStyle::default(),
+        };
+        *previous_font = std::mem::replace(font, next);
+      }
+      '(' | '[' | 'C' => {
+        let name = match escape {
+          '(' => chars.by_ref().take(2).collect(),
+          '[' => chars.by_ref().take_while(|&c| c != ']').collect(),
+          _ => quoted(&mut chars),
+        };
+        push_span(&mut spans, &glyph(&name), *font);
+      }
+      '*' => {
+        let name = escape_name(&mut chars);
+        let value = match name.as_str() {
+          "R" => "®",
+          "Tm" => "™",
+          "lq" => "“",
+          "rq" => "”",
+          name => strings.get(name).map_or("", String::as_str),
+        };
+        // Strings may hold escapes of their own
+        let (string, _) = render(font, previous_font, &HashMap::new(), value);
+        for span in string {
+          push_span(&mut spans, &span.text, span.style);
+        }
+      }
+      '-' => push_span(&mut spans, "-", *font),
+      'e' | '\\' => push_span(&mut spans, "\\", *font),
+      ' ' | '~' | '0' => push_span(&mut spans, " ", *font),
+      'c' if chars.peek().is_none() => continued = true,
+      // Sizes, registers, movements and other typesetting
+      's' => {
+        chars.next_if(|&c| c == '+' || c == '-');
+        if matches!(chars.peek(), Some('(' | '[')) {
+          escape_name(&mut chars);
+        } else if chars.next_if(|c| ('1'..='3').contains(c)).is_some() {
+          // Sizes from 10 to 39 take two digits
+          chars.next_if(char::is_ascii_digit);
+        } else {
+          chars.next_if(char::is_ascii_digit);
+        }
+      }
+      'n' | 'm' | 'M' | 'g' | 'k' | 'V' | 'Y' | '$' => {
+        chars.next_if(|&c| c == '+' || c == '-');
+        escape_name(&mut chars);
+      }
+      'h' | 'v' | 'w' | 'o' | 'b' | 'D' | 'l' | 'L' | 'N' | 'X' | 'x' | 'Z'
+      | 'A' | 'B' | 'R' | 'S' => {
+        quoted(&mut chars);
+      }
+      // Zero width characters and spaces that only matter to typesetting
+      _ => {}
+    }
+  }
+  (spans, continued)
+}
+
+// Render text without styles, for code blocks
+fn render_plain(
+  font: &mut SpanStyle,
+  strings: &HashMap<String, String>,
+  text: &str,
+) -> String {
+  let mut previous = *font;
+  let (spans, _) = render(font, &mut previous, strings, text);
+  spans.iter().map(|span| span.text.as_str()).collect()
+}
+
+// The name after an escape, one character, two after `(` or any within
+// brackets
+fn escape_name(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+  match chars.next() {
+    Some('(') => chars.by_ref().take(2).collect(),
+    Some('[') => chars.by_ref().take_while(|&c| c != ']').collect(),
+    Some(c) => c.to_string(),
+    None => String::new(),
+  }
+}
+
+// The argument of an escape, between quotes
+fn quoted(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+  let Some(delimiter) = chars.next() else {
+    return String::new();
+  };
+  chars.by_ref().take_while(|&c| c != delimiter).collect()
+}
+
+// Special characters of `\(xx` and `\[name]`
+fn glyph(name: &str) -> String {
+  let glyph = match name {
+    "em" => "—",
+    "en" => "–",
+    "hy" | "mi" | "-" => "-",
+    "bu" => "•",
+    "aq" => "'",
+    "dq" => "\"",
+    "lq" => "“",
+    "rq" => "”",
+    "oq" => "‘",
+    "cq" => "’",
+    "ga" => "`",
+    "ha" | "a^" => "^",
+    "ti" | "a~" => "~",
+    "rs" => "\\",
+    "sl" => "/",
+    "ba" | "or" => "|",
+    "co" => "©",
+    "rg" => "®",
+    "tm" => "™",
+    "de" => "°",
+    "sc" => "§",
+    "ps" => "¶",
+    "mu" => "×",
+    "di" => "÷",
+    "+-" => "±",
+    "<=" => "≤",
+    ">=" => "≥",
+    "!=" => "≠",
+    "->" | "ra" => "→",
+    "<-" | "la" => "←",
+    "ua" => "↑",
+    "da" => "↓",
+    "Fo" => "«",
+    "Fc" => "»",
+    "fo" => "‹",
+    "fc" => "›",
+    "pl" => "+",
+    "eq" => "=",
+    "lB" => "[",
+    "rB" => "]",
+    "lC" => "{",
+    "rC" => "}",
+    "at" => "@",
+    "sh" => "#",
+    "Do" => "$",
+    "ct" => "¢",
+    "Eu" | "eu" => "€",
+    "Po" => "£",
+    "Ye" => "¥",
+    "ss" => "ß",
+    "'e" => "é",
+    "`e" => "è",
+    "'a" => "á",
+    "`a" => "à",
+    ":a" => "ä",
+    ":o" => "ö",
+    ":u" => "ü",
+    ":A" => "Ä",
+    ":O" => "Ö",
+    ":U" => "Ü",
+    ",c" => "ç",
+    "~n" => "ñ",
+    _ => {
+      // Unicode characters, e.g. `\[u00E9]`
+      return name
+        .strip_prefix('u')
+        .and_then(|code| u32::from_str_radix(code, 16).ok())
+        .and_then(char::from_u32)
+        .map(String::from)
+        .unwrap_or_default();
+    }
+  };
+  glyph.to_string()
+}
+
+fn push_span(spans: &mut Vec<Span>, text: &str, style: SpanStyle) {
+  match spans.last_mut() {
+    Some(last) if last.style == style => last.text.push_str(text),
+    _ => spans.push(Span { text: text.to_string(), style }),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const PAGE: &str = r#".\" Generated by hand
+.TH LS "1" "September 2022" "GNU coreutils 9.1" "User Commands"
+.SH NAME
+ls \- list directory contents
+.SH SYNOPSIS
+.B ls
+[\fI\,OPTION\/\fR]... [\fI\,FILE\/\fR]...
+.SH DESCRIPTION
+List information about the FILEs.
+Sort entries alphabetically if none of \fB\-cftuvSUX\fR is given.
+.TP
+\fB\-a\fR, \fB\-\-all\fR
+do not ignore entries starting with .
+.TP
+.BR \-w ", " \-\-width =\fICOLS\fR
+set output width to COLS
+.SS "Exit status:"
+.IP \(bu 2
+0 if OK,
+.IP \(bu
+1 if minor problems.
+.PP
+Example:
+.PP
+.nf
+.RS
+ls \-l | sort
+.RE
+.fi
+.SH "SEE ALSO"
+Full documentation
+.UR https://www.gnu.org/software/coreutils/ls
+.UE ,
+or \*(lqinfo ls\*(rq.
+"#;
+
+  #[test]
+  fn test_man_page_sections_and_options() {
+    let content = man_content(PAGE);
+    assert_eq!(content.metadata.title.as_deref(), Some("LS(1)"));
+
+    let titles: Vec<_> = content
+      .parts
+      .iter()
+      .map(|part| (part.title.as_deref(), part.level))
+      .collect();
+    assert_eq!(
+      titles,
+      [
+        (Some("NAME"), 0),
+        (Some("SYNOPSIS"), 0),
+        (Some("DESCRIPTION"), 0),
+        (Some("Exit status:"), 1),
+        (Some("SEE ALSO"), 0),
+      ]
+    );
+    assert_eq!(content.parts[0].text, "NAME\n\nls - list directory contents");
+    assert_eq!(content.parts[1].text, "SYNOPSIS\n\nls [OPTION]... [FILE]...");
+    assert_eq!(
+      content.parts[2].text,
+      "DESCRIPTION\n\nList information about the FILEs. Sort entries alphabetically if none of -cftuvSUX is given.\n\n  -a, --all\n\n    do not ignore entries starting with .\n\n  -w, --width=COLS\n\n    set output width to COLS"
+    );
+    assert_eq!(
+      content.parts[3].text,
+      "Exit status:\n\n• 0 if OK,\n\n• 1 if minor problems.\n\nExample:\n\nls -l | sort"
+    );
+    assert_eq!(
+      content.parts[4].text,
+      "SEE ALSO\n\nFull documentation <https://www.gnu.org/software/coreutils/ls>, or “info ls”."
+    );
+
+    let bold = SpanStyle { strong: true, ..Default::default() };
+    let Block::ListItem { spans, .. } = &content.parts[2].blocks[2] else {
+      panic!("Expected the tag of an option");
+    };
+    assert_eq!(spans[0], Span { text: "-a".to_string(), style: bold });
+    assert!(matches!(content.parts[3].blocks[4], Block::CodeBlock(_)));
+  }
+
+  #[test]
+  fn test_definitions_and_conditions() {
+    let page = r#".de Vb
+.ft CW
+..
+.ie n .ds Aq '
+.el   .ds Aq \(aq
+.if t \{\
+.ds Aq typeset
+.\}
+.SH NAME
+perl\*(Aqs page
+"#;
+    let content = man_content(page);
+    assert_eq!(content.parts[0].text, "NAME\n\nperl's page");
+  }
+
+  #[test]
+  fn test_man_page_names() {
+    assert!(is_man_page_name("ls.1"));
+    assert!(is_man_page_name("ls.1.gz"));
+    assert!(is_man_page_name("CA.pl.1ssl.gz"));
+    assert!(is_man_page_name("tar.1.xz"));
+    assert!(is_man_page_name("printf.3p"));
+    assert!(is_man_page_name("page.man"));
+    assert!(!is_man_page_name("notes.txt"));
+    assert!(!is_man_page_name("archive.tar.gz"));
+    assert!(!is_man_page_name(".1"));
+    assert_eq!(include_target(".so man1/ls.1\n"), Some("man1/ls.1"));
+    assert_eq!(include_target(".TH LS 1\n"), None);
+  }
+
+  #[test]
+  fn test_man_pages_need_a_section_dir_or_roff() {
+    let dir =
+      std::env::temp_dir().join(format!("hygg-man-{}", std::process::id()));
+    let section = dir.join("man1");
+    std::fs::create_dir_all(&section).unwrap();
+    let files = [
+      (dir.join("ls.1"), ".\\\" Generated\n.TH LS 1\n"),
+      (dir.join("app.log.1"), ".started\nServer listening\n"),
+      (section.join("notes.1"), "Plain text\n"),
+    ];
+    for (path, text) in &files {
+      std::fs::write(path, text).unwrap();
+    }
+
+    assert!(is_man_page(&files[0].0));
+    assert!(!is_man_page(&files[1].0));
+    assert!(is_man_page(&files[2].0));
+    assert!(!is_man_page(&dir.join("missing.1")));
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+}
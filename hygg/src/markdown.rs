@@ -0,0 +1,437 @@
+use document_converter::{Block, Content, Part, Span, SpanStyle};
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+
+/// Headings of the first two levels start a new part, deeper ones stay in
+/// the text of their part
+const PART_LEVELS: usize = 2;
+
+/// Bullets of nested unordered lists, repeating after the last
+const BULLETS: [&str; 3] = ["•", "◦", "▪"];
+
+/// Whether text, such as text piped to stdin, looks like Markdown
+///
+/// A code fence is enough. Scripts and config files start comments with
+/// `# ` too, so a heading only counts when it stands between blank lines
+/// and comes with a list, a link or emphasis. Those are common in plain text
+/// on their own, so without a heading two kinds of them are needed.
+pub fn looks_like_markdown(text: &str) -> bool {
+  if text.starts_with("#!") {
+    return false;
+  }
+
+  let lines: Vec<&str> = text.lines().collect();
+  let blank = |at: Option<usize>| {
+    at.and_then(|at| lines.get(at)).is_none_or(|line| line.trim().is_empty())
+  };
+  let mut heading = false;
+  let mut list = false;
+  let mut link = false;
+  let mut emphasis = false;
+  for (at, line) in lines.iter().enumerate() {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+      return true;
+    }
+    let hashes = trimmed.chars().take_while(|&c| c == '#').count();
+    heading |= (1..=6).contains(&hashes)
+      && trimmed[hashes..].starts_with(' ')
+      && blank(at.checked_sub(1))
+      && blank(Some(at + 1));
+    let digits = trimmed.chars().take_while(char::is_ascii_digit).count();
+    list |= ["- ", "* ", "+ "].iter().any(|bullet| trimmed.starts_with(bullet))
+      || (digits > 0 && trimmed[digits..].starts_with(". "));
+    link |= line.contains("](");
+    emphasis |=
+      line.matches("**").count() >= 2 || line.matches('`').count() >= 2;
+  }
+  let others = [list, link, emphasis].into_iter().filter(|&kind| kind).count();
+  others >= 2 || (heading && others >= 1)
+}
+
+/// Read CommonMark, with tables, strikethrough and task lists, into parts
+/// that start at the top level headings
+pub fn markdown_content(text: &str) -> Content {
+  Content { parts: split_parts(markdown_blocks(text)), ..Default::default() }
+}
+
+/// Read CommonMark into headings, paragraphs, lists, code blocks, tables
+/// and images
+pub fn markdown_blocks(text: &str) -> Vec<Block> {
+  let options = Options::ENABLE_TABLES
+    | Options::ENABLE_STRIKETHROUGH
+    | Options::ENABLE_TASKLISTS;
+  let mut reader = Reader::default();
+  for event in Parser::new_ext(text, options) {
+    reader.event(event);
+  }
+  reader.flush();
+  reader.blocks
+}
+
+// Reads parser events into blocks
+#[derive(Default)]
+struct Reader {
+  blocks: Vec<Block>,
+  // Inline text of the block being read
+  spans: Vec<Span>,
+  strong: usize,
+  emphasis: usize,
+  // Next number of each open list, `None` for bullet lists
+  lists: Vec<Option<u64>>,
+  // Marker of the current list item, until its first block takes it
+  marker: Option<String>,
+  quotes: usize,
+  heading: Option<usize>,
+  code: Option<String>,
+  // Rows of the table being read, and whether it has a header row
+  table: Option<(bool, Vec<Vec<Vec<Span>>>)>,
+  row: Vec<Vec<Span>>,
+  // Alt text of the image being read
+  alt: Option<String>,
+  // Whether the image being read starts its block
+  image_first: bool,
+  // Alt text of an image that is the whole block so far
+  lone_image: Option<String>,
+}
+
+impl Reader {
+  fn event(&mut self, event: Event) {
+    match event {
+      Event::Start(tag) => self.start(tag),
+      Event::End(tag) => self.end(tag),
+      Event::Text(text) => match &mut self.code {
+        Some(code) => code.push_str(&text),
+        None => self.push(&text, false),
+      },
+      Event::Code(text) => self.push(&text, true),
+      Event::InlineMath(text) | Event::DisplayMath(text) => {
+        self.push(&text, false);
+      }
+      Event::SoftBreak => self.push(" ", false),
+      Event::HardBreak => self.push("\n", false),
+      Event::Rule => {
+        self.flush();
+        self.blocks.push(Block::Paragraph(vec![Span::plain("* * *")]));
+      }
+      Event::TaskListMarker(checked) => {
+        self.push(if checked { "[x] " } else { "[ ] " }, false);
+      }
+      Event::FootnoteReference(label) => {
+        self.push(&format!("[{label}]"), false);
+      }
+      // Raw HTML is markup the terminal can't show
+      Event::Html(_) | Event::InlineHtml(_) => {}
+    }
+  }
+
+  fn start(&mut self, tag: Tag) {
+    match tag {
+      Tag::Paragraph => self.flush(),
+      Tag::Table(_) => {
+        self.flush();
+        self.table = Some((false, Vec::new()));
+      }
+      Tag::Heading { level, .. } => {
+        self.flush();
+        self.heading = Some(level as usize - 1);
+      }
+      Tag::BlockQuote(_) => {
+        self.flush();
+        self.quotes += 1;
+      }
+      Tag::CodeBlock(_) => {
+        self.flush();
+        self.code = Some(String::new());
+      }
+      Tag::List(first) => {
+        self.flush();
+        self.lists.push(first);
+      }
+      Tag::Item => {
+        self.flush();
+        let depth = self.lists.len().saturating_sub(1);
+        self.marker = Some(match self.lists.last_mut() {
+          Some(Some(number)) => {
+            *number += 1;
+            format!("{}.", *number - 1)
+          }
+          _ => BULLETS[depth % BULLETS.len()].to_string(),
+        });
+      }
+      Tag::Emphasis => self.emphasis += 1,
+      Tag::Strong => self.strong += 1,
+      Tag::Image { .. } => {
+        self.image_first = is_blank(&self.spans);
+        self.push("[", false);
+        self.alt = Some(String::new());
+      }
+      _ => {}
+    }
+  }
+
+  fn end(&mut self, tag: TagEnd) {
+    match tag {
+      TagEnd::Paragraph | TagEnd::Item => self.flush(),
+      TagEnd::TableCell => {
+        let cell = trim(std::mem::take(&mut self.spans));
+        self.row.push(cell);
+        self.lone_image = None;
+      }
+      TagEnd::TableHead | TagEnd::TableRow => {
+        let row = std::mem::take(&mut self.row);
+        if let Some((header, rows)) = &mut self.table {
+          *header |= tag == TagEnd::TableHead;
+          rows.push(row);
+        }
+      }
+      TagEnd::Table => {
+        if let Some((header, rows)) = self.table.take()
+          && !rows.is_empty()
+        {
+          self.blocks.push(Block::Table { header, rows });
+        }
+      }
+      TagEnd::Heading(_) => {
+        let level = self.heading.take().unwrap_or(0);
+        let spans = std::mem::take(&mut self.spans);
+        if !is_blank(&spans) {
+          self.blocks.push(Block::Heading { level, spans: trim(spans) });
+        }
+      }
+      TagEnd::BlockQuote(_) => {
+        self.flush();
+        self.quotes = self.quotes.saturating_sub(1);
+      }
+      TagEnd::CodeBlock => {
+        let code = self.code.take().unwrap_or_default();
+        let code = code.trim_end_matches('\n');
+        if !code.is_empty() {
+          self.blocks.push(Block::CodeBlock(code.to_string()));
+        }
+      }
+      TagEnd::List(_) => {
+        self.flush();
+        self.lists.pop();
+      }
+      TagEnd::Emphasis => self.emphasis = self.emphasis.saturating_sub(1),
+      TagEnd::Strong => self.strong = self.strong.saturating_sub(1),
+      TagEnd::Image => {
+        let alt = self.alt.take().unwrap_or_default();
+        // Images without alt text still show where they were
+        if alt.is_empty() {
+          self.push("image", false);
+        }
+        self.push("]", false);
+        if self.image_first {
+          self.lone_image = Some(alt.trim().to_string());
+        }
+      }
+      _ => {}
+    }
+  }
+
+  fn push(&mut self, text: &str, code: bool) {
+    match &mut self.alt {
+      Some(alt) => alt.push_str(text),
+      None if !text.trim().is_empty() => self.lone_image = None,
+      None => {}
+    }
+    let style =
+      SpanStyle { strong: self.strong > 0, emphasis: self.emphasis > 0, code };
+    match self.spans.last_mut() {
+      Some(last) if last.style == style => last.text.push_str(text),
+      _ => self.spans.push(Span { text: text.to_string(), style }),
+    }
+  }
+
+  // Turn the inline text read so far into a paragraph, list item, quote or
+  // image
+  fn flush(&mut self) {
+    let spans = std::mem::take(&mut self.spans);
+    let lone_image = self.lone_image.take();
+    if is_blank(&spans) {
+      return;
+    }
+    let spans = trim(spans);
+    let block = if let Some(alt) = lone_image
+      && self.lists.is_empty()
+      && self.quotes == 0
+    {
+      Block::Image { alt }
+    } else if !self.lists.is_empty() {
+      Block::ListItem {
+        depth: self.lists.len() - 1,
+        marker: self.marker.take().unwrap_or_default(),
+        spans,
+      }
+    } else if self.quotes > 0 {
+      Block::Quote(spans)
+    } else {
+      Block::Paragraph(spans)
+    };
+    self.blocks.push(block);
+  }
+}
+
+pub fn is_blank(spans: &[Span]) -> bool {
+  spans.iter().all(|span| span.text.trim().is_empty())
+}
+
+pub fn trim(mut spans: Vec<Span>) -> Vec<Span> {
+  if let Some(first) = spans.first_mut() {
+    first.text = first.text.trim_start().to_string();
+  }
+  if let Some(last) = spans.last_mut() {
+    last.text = last.text.trim_end().to_string();
+  }
+  spans.retain(|span| !span.text.is_empty());
+  spans
+}
+
+/// Group blocks into parts starting at the top level headings
+pub fn split_parts(blocks: Vec<Block>) -> Vec<Part> {
+  let mut parts: Vec<Part> = Vec::new();
+  let mut current = Part {
+    title: None,
+    level: 0,
+    page: None,
+    text: String::new(),
+    blocks: Vec::new(),
+  };
+  for block in blocks {
+    if let Block::Heading { level, .. } = &block
+      && *level < PART_LEVELS
+    {
+      let next = Part {
+        title: Some(block.text()),
+        level: *level,
+        page: None,
+        text: String::new(),
+        blocks: Vec::new(),
+      };
+      let previous = std::mem::replace(&mut current, next);
+      if !previous.blocks.is_empty() {
+        parts.push(previous);
+      }
+    }
+    current.blocks.push(block);
+  }
+  if !current.blocks.is_empty() {
+    parts.push(current);
+  }
+
+  // Documents that start at "##" still have top level parts
+  let top = parts
+    .iter()
+    .filter(|part| part.title.is_some())
+    .map(|part| part.level)
+    .min()
+    .unwrap_or(0);
+  for part in &mut parts {
+    part.level = part.level.saturating_sub(top);
+    part.text = part
+      .blocks
+      .iter()
+      .map(Block::text)
+      .filter(|text| !text.is_empty())
+      .collect::<Vec<_>>()
+      .join("\n\n");
+  }
+  parts
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn spans(block: &Block) -> Vec<(&str, SpanStyle)> {
+    match block {
+      Block::Heading { spans, .. }
+      | Block::Paragraph(spans)
+      | Block::Quote(spans)
+      | Block::ListItem { spans, .. } => {
+        spans.iter().map(|span| (span.text.as_str(), span.style)).collect()
+      }
+      _ => Vec::new(),
+    }
+  }
+
+  #[test]
+  fn test_markdown_blocks() {
+    let text = "# Title\n\nSome *emphasis*, **bold** and `code`.\n\n## Usage\n\n- one\n- two\n  1. nested\n  2. again\n\n```sh\ncargo run\n  indented\n```\n\n> quoted\n\n| a | b |\n|---|---|\n| 1 | 2 |\n";
+    let content = markdown_content(text);
+
+    let titles: Vec<_> = content
+      .parts
+      .iter()
+      .map(|part| (part.title.as_deref(), part.level))
+      .collect();
+    assert_eq!(titles, [(Some("Title"), 0), (Some("Usage"), 1)]);
+
+    let first = &content.parts[0].blocks;
+    assert_eq!(
+      first[0],
+      Block::Heading { level: 0, spans: vec![Span::plain("Title")] }
+    );
+    let strong = SpanStyle { strong: true, ..Default::default() };
+    let emphasis = SpanStyle { emphasis: true, ..Default::default() };
+    let code = SpanStyle { code: true, ..Default::default() };
+    assert_eq!(
+      spans(&first[1]),
+      [
+        ("Some ", SpanStyle::default()),
+        ("emphasis", emphasis),
+        (", ", SpanStyle::default()),
+        ("bold", strong),
+        (" and ", SpanStyle::default()),
+        ("code", code),
+        (".", SpanStyle::default()),
+      ]
+    );
+
+    assert_eq!(
+      content.parts[1].text,
+      "Usage\n\n• one\n\n• two\n\n  1. nested\n\n  2. again\n\ncargo run\n  indented\n\nquoted\n\na | b\n\n1 | 2"
+    );
+    assert!(matches!(content.parts[1].blocks[6], Block::Quote(_)));
+    let cell = |text: &str| vec![Span::plain(text)];
+    assert_eq!(
+      content.parts[1].blocks[7],
+      Block::Table {
+        header: true,
+        rows: vec![vec![cell("a"), cell("b")], vec![cell("1"), cell("2")]],
+      }
+    );
+  }
+
+  #[test]
+  fn test_images() {
+    let text = "![Floor plan](plan.png)\n\nSee ![the logo](logo.svg) here.\n\n- ![](icon.png)";
+    let blocks = markdown_blocks(text);
+    assert_eq!(blocks[0], Block::Image { alt: "Floor plan".to_string() });
+    assert_eq!(
+      blocks[1],
+      Block::Paragraph(vec![Span::plain("See [the logo] here.")])
+    );
+    assert_eq!(blocks[2].text(), "• [image]");
+  }
+
+  #[test]
+  fn test_looks_like_markdown() {
+    assert!(looks_like_markdown("# hygg\n\nA **fast** reader."));
+    assert!(!looks_like_markdown("# hygg\n\nA reader."));
+    assert!(looks_like_markdown("Run:\n\n```\nhygg\n```"));
+    assert!(looks_like_markdown("- see [docs](docs/)\n- more"));
+    assert!(!looks_like_markdown("#hashtag and\n- a dash list\n- item"));
+    assert!(!looks_like_markdown("Plain text.\n\nWith paragraphs."));
+    assert!(!looks_like_markdown(
+      "#!/bin/sh\n\n# Build it\n\nmake `nproc` && echo `date`\n"
+    ));
+    assert!(!looks_like_markdown(
+      "# Settings\n# - name: the host\nhost: example.com\nports:\n  - 80\n"
+    ));
+    assert!(!looks_like_markdown(
+      "# Helpers\nimport os\n\n# - paths\ndef run(*args, **kwargs):\n"
+    ));
+  }
+}
@@ -0,0 +1,209 @@
+use document_converter::{Block, Content, Metadata, Span, SpanStyle};
+use serde_json::Value;
+use std::error::Error;
+
+use crate::markdown;
+
+/// Output types that are left out, with a placeholder in their place
+const IMAGE_TYPES: &[&str] =
+  &["image/png", "image/jpeg", "image/gif", "image/svg+xml"];
+
+/// Read a Jupyter notebook into parts that start at the top level headings
+/// of its markdown cells
+///
+/// Markdown cells are read as Markdown, code cells are shown as they are
+/// after an `In [n]:` label, followed by their text outputs. Images in the
+/// outputs are shown as `[image]`.
+pub fn notebook_content(json: &str) -> Result<Content, Box<dyn Error>> {
+  let notebook: Value = serde_json::from_str(json)
+    .map_err(|e| format!("Invalid Jupyter notebook: {e}"))?;
+  let version = notebook["nbformat"].as_u64().unwrap_or_default();
+  if version < 4 {
+    return Err(
+      format!(
+        "Jupyter notebook format {version} is not supported, save it as \
+         format 4"
+      )
+      .into(),
+    );
+  }
+  let cells =
+    notebook["cells"].as_array().ok_or("Invalid Jupyter notebook: no cells")?;
+
+  let mut blocks = Vec::new();
+  for cell in cells {
+    let source = multiline(&cell["source"]);
+    match cell["cell_type"].as_str() {
+      Some("markdown") => blocks.extend(markdown::markdown_blocks(&source)),
+      Some("code") => code_cell(cell, &source, &mut blocks),
+      // Raw cells are passed through by Jupyter as they are
+      _ => push_code(&source, &mut blocks),
+    }
+  }
+
+  let metadata = &notebook["metadata"];
+  Ok(Content {
+    parts: markdown::split_parts(blocks),
+    metadata: Metadata {
+      title: metadata["title"].as_str().map(str::to_string),
+      authors: metadata["authors"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|author| author["name"].as_str().map(str::to_string))
+        .collect(),
+      ..Default::default()
+    },
+    ..Default::default()
+  })
+}
+
+fn code_cell(cell: &Value, source: &str, blocks: &mut Vec<Block>) {
+  // Cells that haven't run have no count
+  let count = cell["execution_count"]
+    .as_u64()
+    .map_or_else(|| " ".to_string(), |count| count.to_string());
+  blocks.push(label(&format!("In [{count}]:")));
+  push_code(source, blocks);
+
+  for output in cell["outputs"].as_array().into_iter().flatten() {
+    let data = &output["data"];
+    match output["output_type"].as_str() {
+      Some("stream") => push_code(&multiline(&output["text"]), blocks),
+      Some("execute_result" | "display_data")
+        if IMAGE_TYPES.iter().any(|kind| !data[kind].is_null()) =>
+      {
+        blocks.push(Block::Image { alt: String::new() });
+      }
+      Some(kind @ ("execute_result" | "display_data")) => {
+        let text = multiline(&data["text/plain"]);
+        if kind == "execute_result" && !text.trim().is_empty() {
+          blocks.push(label(&format!("Out[{count}]:")));
+        }
+        push_code(&text, blocks);
+      }
+      Some("error") => {
+        let traceback = output["traceback"]
+          .as_array()
+          .into_iter()
+          .flatten()
+          .filter_map(Value::as_str)
+          .collect::<Vec<_>>()
+          .join("\n");
+        let text = if traceback.is_empty() {
+          format!(
+            "{}: {}",
+            output["ename"].as_str().unwrap_or("Error"),
+            output["evalue"].as_str().unwrap_or_default()
+          )
+        } else {
+          strip_ansi(&traceback)
+        };
+        push_code(&text, blocks);
+      }
+      _ => {}
+    }
+  }
+}
+
+fn push_code(text: &str, blocks: &mut Vec<Block>) {
+  let text = text.trim_end();
+  if !text.trim().is_empty() {
+    blocks.push(Block::CodeBlock(text.to_string()));
+  }
+}
+
+fn label(text: &str) -> Block {
+  let style = SpanStyle { strong: true, ..Default::default() };
+  Block::Paragraph(vec![Span { text: text.to_string(), style }])
+}
+
+// Sources and outputs are a string or a list of lines
+fn multiline(value: &Value) -> String {
+  match value {
+    Value::String(text) => text.clone(),
+    Value::Array(lines) => lines.iter().filter_map(Value::as_str).collect(),
+    _ => String::new(),
+  }
+}
+
+// Tracebacks are colored with escape sequences
+fn strip_ansi(text: &str) -> String {
+  let mut stripped = String::with_capacity(text.len());
+  let mut chars = text.chars();
+  while let Some(c) = chars.next() {
+    if c != '\x1b' {
+      stripped.push(c);
+    } else if chars.next() == Some('[') {
+      // Parameters up to the final letter of the sequence
+      for c in chars.by_ref() {
+        if c.is_ascii_alphabetic() {
+          break;
+        }
+      }
+    }
+  }
+  stripped
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const NOTEBOOK: &str = r###"{
+    "nbformat": 4,
+    "nbformat_minor": 5,
+    "metadata": {"title": "Analysis", "authors": [{"name": "Jane Doe"}]},
+    "cells": [
+      {"cell_type": "markdown", "source": ["# Analysis\n", "\n", "Some **bold** prose."]},
+      {"cell_type": "code", "execution_count": 1, "source": "for i in range(2):\n    print(i)",
+       "outputs": [{"output_type": "stream", "name": "stdout", "text": ["0\n", "1\n"]}]},
+      {"cell_type": "code", "execution_count": 2, "source": ["x = 40\n", "x + 2"],
+       "outputs": [{"output_type": "execute_result", "execution_count": 2,
+                    "data": {"text/plain": ["42"]}}]},
+      {"cell_type": "code", "execution_count": 3, "source": "plot()",
+       "outputs": [{"output_type": "display_data",
+                    "data": {"image/png": "iVBORw0KGgo=", "text/plain": ["<Figure>"]}}]},
+      {"cell_type": "markdown", "source": "## Errors"},
+      {"cell_type": "code", "execution_count": null, "source": "1 / 0",
+       "outputs": [{"output_type": "error", "ename": "ZeroDivisionError", "evalue": "division by zero",
+                    "traceback": ["\u001b[0;31mZeroDivisionError\u001b[0m: division by zero"]}]}
+    ]
+  }"###;
+
+  #[test]
+  fn test_notebook_cells() {
+    let content = notebook_content(NOTEBOOK).unwrap();
+    let titles: Vec<_> = content
+      .parts
+      .iter()
+      .map(|part| (part.title.as_deref(), part.level))
+      .collect();
+    assert_eq!(titles, [(Some("Analysis"), 0), (Some("Errors"), 1)]);
+    assert_eq!(
+      content.parts[0].text,
+      "Analysis\n\nSome bold prose.\n\nIn [1]:\n\nfor i in range(2):\n    print(i)\n\n0\n1\n\nIn [2]:\n\nx = 40\nx + 2\n\nOut[2]:\n\n42\n\nIn [3]:\n\nplot()\n\n[image]"
+    );
+    assert_eq!(
+      content.parts[1].text,
+      "Errors\n\nIn [ ]:\n\n1 / 0\n\nZeroDivisionError: division by zero"
+    );
+    assert!(matches!(content.parts[0].blocks[3], Block::CodeBlock(_)));
+    assert_eq!(
+      content.parts[0].blocks.last(),
+      Some(&Block::Image { alt: String::new() })
+    );
+    assert_eq!(content.metadata.title.as_deref(), Some("Analysis"));
+    assert_eq!(content.metadata.authors, ["Jane Doe"]);
+  }
+
+  #[test]
+  fn test_old_or_invalid_notebooks() {
+    let error = notebook_content(r#"{"nbformat": 3, "worksheets": []}"#);
+    assert_eq!(
+      error.unwrap_err().to_string(),
+      "Jupyter notebook format 3 is not supported, save it as format 4"
+    );
+    assert!(notebook_content("not json").is_err());
+  }
+}
@@ -0,0 +1,108 @@
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::validate_file_path;
+
+/// When to run OCR on a PDF
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OcrMode {
+  /// OCR the pages that have images but no text, if ocrmypdf is installed
+  Auto,
+  /// OCR every page, replacing any text the PDF has
+  #[value(alias = "true")]
+  Always,
+  /// Only read the text the PDF has
+  #[default]
+  #[value(alias = "false")]
+  Never,
+}
+
+/// A path in the system temp dir, the file is removed when this is dropped
+///
+/// Dropping also happens while unwinding, so the file doesn't outlive a
+/// failed conversion or a panic.
+pub struct TempFile {
+  path: PathBuf,
+}
+
+impl TempFile {
+  pub fn new(extension: &str) -> Self {
+    let mut name = format!("hygg-{}", uuid::Uuid::new_v4());
+    if !extension.is_empty() {
+      name = format!("{name}.{extension}");
+    }
+    Self { path: std::env::temp_dir().join(name) }
+  }
+
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+}
+
+impl Drop for TempFile {
+  fn drop(&mut self) {
+    let _ = std::fs::remove_file(&self.path);
+  }
+}
+
+/// Run ocrmypdf on `input` and return the PDF it writes
+///
+/// Only the 1-based `pages` are OCRed when given, the others are copied
+/// as they are. `lang` selects the tesseract languages, e.g. `eng+deu`.
+pub fn ocr_pdf(
+  input: &str,
+  pages: Option<&[u32]>,
+  lang: Option<&str>,
+) -> Result<TempFile, Box<dyn Error>> {
+  validate_file_path(input)?;
+  let output = TempFile::new("pdf");
+
+  let mut cmd = Command::new("ocrmypdf");
+  cmd.arg("--force-ocr");
+  if let Some(pages) = pages {
+    let pages: Vec<String> = pages.iter().map(u32::to_string).collect();
+    cmd.arg("--pages").arg(pages.join(","));
+  }
+  if let Some(lang) = lang {
+    cmd.arg("--language").arg(lang);
+  }
+  cmd
+    .arg("--") // End of options marker
+    .arg(input)
+    .arg(output.path())
+    .stdin(Stdio::null())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped());
+
+  let result =
+    cmd.output().map_err(|e| format!("Unable to run ocrmypdf: {e}"))?;
+  if !result.status.success() {
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    let reason = stderr.lines().rfind(|line| !line.trim().is_empty());
+    return Err(
+      match reason {
+        Some(reason) => format!("OCR processing failed: {}", reason.trim()),
+        None => "OCR processing failed".to_string(),
+      }
+      .into(),
+    );
+  }
+  Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_temp_file_is_removed_on_drop() {
+    let file = TempFile::new("pdf");
+    let path = file.path().to_path_buf();
+    assert!(path.starts_with(std::env::temp_dir()));
+    std::fs::write(&path, b"scan").unwrap();
+
+    drop(file);
+    assert!(!path.exists());
+  }
+}
@@ -0,0 +1,99 @@
+use encoding_rs::{Encoding, UTF_8, UTF_16BE, UTF_16LE, WINDOWS_1252};
+
+/// How many leading bytes the UTF-16 heuristic looks at
+const SAMPLE_LEN: usize = 4096;
+
+/// Parse an `--encoding` value, e.g. `latin1`, `windows-1252` or `utf-16le`
+pub fn parse_encoding(label: &str) -> Result<&'static Encoding, String> {
+  Encoding::for_label(label.trim().as_bytes())
+    .ok_or_else(|| format!("unknown encoding '{label}'"))
+}
+
+/// Guess the encoding of `bytes`
+///
+/// A BOM wins, then UTF-16 without a BOM is recognized by its NUL bytes,
+/// then valid UTF-8. Anything else is read as Windows-1252, which is a
+/// superset of the printable range of Latin-1.
+pub fn detect_encoding(bytes: &[u8]) -> &'static Encoding {
+  if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+    return encoding;
+  }
+
+  if let Some(encoding) = detect_utf16(bytes) {
+    return encoding;
+  }
+
+  if std::str::from_utf8(bytes).is_ok() {
+    return UTF_8;
+  }
+
+  WINDOWS_1252
+}
+
+// Mostly ASCII UTF-16 text has a NUL in every other byte
+fn detect_utf16(bytes: &[u8]) -> Option<&'static Encoding> {
+  let sample = &bytes[..bytes.len().min(SAMPLE_LEN) & !1];
+  if sample.is_empty() {
+    return None;
+  }
+
+  let pairs = sample.len() / 2;
+  let even_nuls = sample.iter().step_by(2).filter(|&&b| b == 0).count();
+  let odd_nuls = sample.iter().skip(1).step_by(2).filter(|&&b| b == 0).count();
+
+  if odd_nuls * 10 >= pairs * 3 && even_nuls * 10 < pairs {
+    Some(UTF_16LE)
+  } else if even_nuls * 10 >= pairs * 3 && odd_nuls * 10 < pairs {
+    Some(UTF_16BE)
+  } else {
+    None
+  }
+}
+
+/// Decode `bytes` to UTF-8, with `encoding` overriding detection
+///
+/// Undecodable sequences are replaced rather than failing the whole file.
+pub fn decode_text(
+  bytes: &[u8],
+  encoding: Option<&'static Encoding>,
+) -> (String, &'static Encoding) {
+  let encoding = encoding.unwrap_or_else(|| detect_encoding(bytes));
+  let (text, _) = encoding.decode_with_bom_removal(bytes);
+  (text.into_owned(), encoding)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_detect_encoding() {
+    assert_eq!(detect_encoding("héllo".as_bytes()), UTF_8);
+    assert_eq!(detect_encoding(b"\xef\xbb\xbfhello"), UTF_8);
+    assert_eq!(detect_encoding(b"\xff\xfeh\0i\0"), UTF_16LE);
+    assert_eq!(detect_encoding(b"h\0e\0l\0l\0o\0"), UTF_16LE);
+    assert_eq!(detect_encoding(b"\0h\0e\0l\0l\0o"), UTF_16BE);
+    assert_eq!(detect_encoding(b"caf\xe9"), WINDOWS_1252);
+  }
+
+  #[test]
+  fn test_decode_text() {
+    let (text, encoding) = decode_text(b"caf\xe9 \x93quoted\x94", None);
+    assert_eq!(text, "café \u{201c}quoted\u{201d}");
+    assert_eq!(encoding, WINDOWS_1252);
+
+    let (text, _) = decode_text(b"\xff\xfeh\0i\0", None);
+    assert_eq!(text, "hi");
+
+    let (text, encoding) =
+      decode_text("héllo".as_bytes(), Some(parse_encoding("latin1").unwrap()));
+    assert_eq!(text, "hÃ©llo");
+    assert_eq!(encoding, WINDOWS_1252);
+  }
+
+  #[test]
+  fn test_parse_encoding_rejects_unknown_labels() {
+    assert_eq!(parse_encoding("UTF-16LE").unwrap(), UTF_16LE);
+    assert!(parse_encoding("klingon").is_err());
+  }
+}
@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
 #[test]
@@ -14,6 +14,7 @@ fn test_pdf_processing() {
   }
 
   let output = Command::new(env!("CARGO_BIN_EXE_hygg"))
+    .arg("--no-cache")
     .arg("--col")
     .arg("80")
     .arg(test_file.to_str().unwrap())
@@ -63,6 +64,7 @@ fn test_epub_processing() {
   // For the full hygg test, we'll spawn it and kill it after a short time
   // This verifies it doesn't panic on startup
   let mut child = Command::new(env!("CARGO_BIN_EXE_hygg"))
+    .arg("--no-cache")
     .arg("--col")
     .arg("80")
     .arg(test_file.to_str().unwrap())
@@ -79,13 +81,11 @@ fn test_epub_processing() {
 
   // Check if it panicked (would have exited with error)
   match child.try_wait() {
-    Ok(Some(status)) => {
-      if !status.success() {
-        let stderr = child.wait_with_output().unwrap().stderr;
-        let stderr_str = String::from_utf8_lossy(&stderr);
-        if stderr_str.contains("panic") {
-          panic!("hygg panicked: {}", stderr_str);
-        }
+    Ok(Some(status)) if !status.success() => {
+      let stderr = child.wait_with_output().unwrap().stderr;
+      let stderr_str = String::from_utf8_lossy(&stderr);
+      if stderr_str.contains("panic") {
+        panic!("hygg panicked: {}", stderr_str);
       }
     }
     _ => {
@@ -115,6 +115,7 @@ fn test_odt_processing_with_pandoc() {
   }
 
   let output = Command::new(env!("CARGO_BIN_EXE_hygg"))
+    .arg("--no-cache")
     .arg("--col")
     .arg("80")
     .arg(test_file.to_str().unwrap())
@@ -166,6 +167,7 @@ fn test_docx_processing_with_pandoc() {
 
   // Test full hygg processing (spawn and kill due to TUI)
   let mut child = Command::new(env!("CARGO_BIN_EXE_hygg"))
+    .arg("--no-cache")
     .arg("--col")
     .arg("80")
     .arg(test_file.to_str().unwrap())
@@ -179,13 +181,11 @@ fn test_docx_processing_with_pandoc() {
 
   // Check if it panicked
   match child.try_wait() {
-    Ok(Some(status)) => {
-      if !status.success() {
-        let stderr = child.wait_with_output().unwrap().stderr;
-        let stderr_str = String::from_utf8_lossy(&stderr);
-        if stderr_str.contains("panic") {
-          panic!("hygg panicked on DOCX: {}", stderr_str);
-        }
+    Ok(Some(status)) if !status.success() => {
+      let stderr = child.wait_with_output().unwrap().stderr;
+      let stderr_str = String::from_utf8_lossy(&stderr);
+      if stderr_str.contains("panic") {
+        panic!("hygg panicked on DOCX: {}", stderr_str);
       }
     }
     _ => {
@@ -211,6 +211,7 @@ fn test_txt_processing() {
 
   // Spawn the process with piped stdout/stderr to ensure non-interactive mode
   let mut child = Command::new(env!("CARGO_BIN_EXE_hygg"))
+    .arg("--no-cache")
     .arg("--col")
     .arg("80")
     .arg(test_file.to_str().unwrap())
@@ -295,3 +296,298 @@ fn test_stdin_processing() {
     Err(e) => panic!("Failed to check hygg status: {}", e),
   }
 }
+
+#[test]
+fn test_scanned_pdfs_are_only_ocred_when_asked() {
+  let test_file = Path::new(env!("CARGO_MANIFEST_DIR"))
+    .parent()
+    .unwrap()
+    .join("test-data/pdf/ocr-0.pdf");
+
+  // Without ocrmypdf on the PATH, as on a machine that doesn't have it
+  let read = |args: &[&str]| {
+    let output = Command::new(env!("CARGO_BIN_EXE_hygg"))
+      .env("PATH", "/nonexistent")
+      .arg("--no-cache")
+      .args(args)
+      .arg(&test_file)
+      .output()
+      .expect("Failed to execute hygg");
+    String::from_utf8_lossy(&output.stderr).into_owned()
+  };
+
+  let stderr = read(&[]);
+  assert!(stderr.contains("1 page(s) have no text, --ocr=auto"), "{stderr}");
+  let stderr = read(&["--ocr=auto"]);
+  assert!(stderr.contains("1 page(s) need OCR, install ocrmypdf"), "{stderr}");
+}
+
+#[test]
+fn test_list_converters() {
+  let output = Command::new(env!("CARGO_BIN_EXE_hygg"))
+    .arg("--list-converters")
+    .arg("book.epub")
+    .output()
+    .expect("Failed to execute hygg");
+
+  assert!(output.status.success(), "hygg should exit successfully");
+
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  assert!(stdout.contains("pdf - PDF documents"), "Should list converters");
+  assert!(
+    stdout.contains("1. epub (handles .epub files)"),
+    "Should explain why the epub converter is picked"
+  );
+  assert!(!stdout.contains("2."), "Extension match should skip fallbacks");
+}
+
+#[test]
+fn test_converters_are_picked_by_content_and_extension() {
+  let dir =
+    std::env::temp_dir().join(format!("hygg-routing-{}", std::process::id()));
+  std::fs::create_dir_all(&dir).expect("Failed to create test dir");
+  let page = dir.join("page.html");
+  std::fs::write(&page, "<html><body><p>Saved page</p></body></html>")
+    .expect("Failed to write test file");
+  let compressed = dir.join("logs");
+  let data = zstd::encode_all(&b"Logs and RFC dumps\n"[..], 1).unwrap();
+  std::fs::write(&compressed, data).expect("Failed to write test file");
+
+  let repo = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+  // The file, the converters listed for it and ones that mustn't be
+  let cases: [(PathBuf, &[&str], &[&str]); 9] = [
+    (
+      repo.join("test-data/docx/test-standard.docx"),
+      &[
+        "1. docx (content is Word document)",
+        "2. pandoc (content is Word document)",
+      ],
+      &[],
+    ),
+    (
+      repo.join("test-data/odf/test.odt"),
+      &[
+        "1. odt (content is OpenDocument text)",
+        "2. pandoc (content is OpenDocument text)",
+      ],
+      &[],
+    ),
+    (repo.join("README.md"), &["1. markdown (handles .md files)"], &[]),
+    (
+      page.clone(),
+      &["1. html (handles .html files)", "2. pandoc (handles .html files)"],
+      &[],
+    ),
+    (
+      repo.join("test-data/fb2/test.fb2"),
+      &[
+        "1. fb2 (content is FictionBook e-book)",
+        "2. pandoc (handles .fb2 files)",
+      ],
+      &[],
+    ),
+    (
+      repo.join("test-data/mobi/test.azw3"),
+      &["1. mobi (content is MOBI e-book)"],
+      &["2. pandoc"],
+    ),
+    (
+      PathBuf::from("analysis.ipynb"),
+      &["1. ipynb (handles .ipynb files)"],
+      &[],
+    ),
+    (
+      repo.join("test-data/rtf/test.rtf"),
+      &["1. rtf (content is RTF document)", "2. pandoc (handles .rtf files)"],
+      &[],
+    ),
+    (
+      compressed.clone(),
+      &["1. compressed (content is zstd compressed data)"],
+      &[],
+    ),
+  ];
+
+  for (file, listed, unlisted) in cases {
+    let output = Command::new(env!("CARGO_BIN_EXE_hygg"))
+      .arg("--list-converters")
+      .arg(&file)
+      .output()
+      .expect("Failed to execute hygg");
+    assert!(output.status.success(), "hygg should exit successfully");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for line in listed {
+      assert!(stdout.contains(line), "{}: {line}\n{stdout}", file.display());
+    }
+    for line in unlisted {
+      assert!(!stdout.contains(line), "{}: {line}\n{stdout}", file.display());
+    }
+  }
+  let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_zipped_fb2_books_are_read() {
+  let dir =
+    std::env::temp_dir().join(format!("hygg-fb2-zip-{}", std::process::id()));
+  std::fs::create_dir_all(&dir).expect("Failed to create test dir");
+  let book = std::fs::read(
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+      .parent()
+      .unwrap()
+      .join("test-data/fb2/test.fb2"),
+  )
+  .expect("Failed to read test file");
+  let zipped = dir.join("book.fb2.zip");
+  let mut zip = zip::ZipWriter::new(
+    std::fs::File::create(&zipped).expect("Failed to write test file"),
+  );
+  zip.start_file("book.fb2", zip::write::SimpleFileOptions::default()).unwrap();
+  std::io::Write::write_all(&mut zip, &book).unwrap();
+  zip.finish().unwrap();
+
+  // Without the tutorial the first screen of the document is printed
+  let output = Command::new(env!("CARGO_BIN_EXE_hygg"))
+    .env("ENABLE_TUTORIAL", "false")
+    .args(["--no-cache", "--col", "80"])
+    .arg(&zipped)
+    .output()
+    .expect("Failed to execute hygg");
+  let _ = std::fs::remove_dir_all(&dir);
+
+  assert!(output.status.success(), "hygg should exit successfully");
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  assert!(stdout.contains("The Lighthouse"), "{stdout}");
+}
+
+#[test]
+fn test_native_readers_accept_any_file_name() {
+  let dir =
+    std::env::temp_dir().join(format!("hygg-names-{}", std::process::id()));
+  std::fs::create_dir_all(&dir).expect("Failed to create test dir");
+  // Only paths passed to pandoc or ocrmypdf need to avoid shell characters
+  let file = dir.join("R&D (draft).txt");
+  std::fs::write(&file, "Research notes\n").expect("Failed to write test file");
+
+  let output = Command::new(env!("CARGO_BIN_EXE_hygg"))
+    .env("ENABLE_TUTORIAL", "false")
+    .args(["--no-cache", "--col", "80"])
+    .arg(&file)
+    .output()
+    .expect("Failed to execute hygg");
+  let _ = std::fs::remove_dir_all(&dir);
+
+  assert!(output.status.success(), "hygg should exit successfully");
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  assert!(stdout.contains("Research notes"), "{stdout}");
+}
+
+#[test]
+fn test_man_pages_are_read_natively() {
+  use std::io::Write;
+
+  let dir =
+    std::env::temp_dir().join(format!("hygg-man-{}", std::process::id()));
+  std::fs::create_dir_all(&dir).expect("Failed to create test dir");
+  let page = dir.join("greet.1.gz");
+  let mut gzip =
+    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+  gzip.write_all(b".TH GREET 1\n.SH NAME\ngreet \\- say hello\n").unwrap();
+  std::fs::write(&page, gzip.finish().unwrap())
+    .expect("Failed to write test file");
+  // Rotated logs are named like pages but aren't roff
+  let log = dir.join("app.log.1");
+  std::fs::write(&log, ".started\nServer listening\n")
+    .expect("Failed to write test file");
+
+  let read = |path: &Path| {
+    // Without the tutorial the first screen of the document is printed
+    let output = Command::new(env!("CARGO_BIN_EXE_hygg"))
+      .env("ENABLE_TUTORIAL", "false")
+      .args(["--no-cache", "--col", "80"])
+      .arg(path)
+      .output()
+      .expect("Failed to execute hygg");
+    assert!(output.status.success(), "hygg should exit successfully");
+    String::from_utf8_lossy(&output.stdout).into_owned()
+  };
+  let page = read(&page);
+  let log = read(&log);
+  let _ = std::fs::remove_dir_all(&dir);
+
+  assert!(page.contains("greet - say hello"), "{page}");
+  assert!(!page.contains(".TH"), "{page}");
+  assert!(log.contains(".started"), "{log}");
+  assert!(log.contains("Server listening"), "{log}");
+}
+
+#[test]
+fn test_list_converters_sniffs_misnamed_file() {
+  let test_file = Path::new(env!("CARGO_MANIFEST_DIR"))
+    .parent()
+    .unwrap()
+    .join("test-data/epub/test-standard.epub");
+
+  if !test_file.exists() {
+    eprintln!("EPUB test file not found, skipping test");
+    return;
+  }
+
+  let misnamed = std::env::temp_dir()
+    .join(format!("hygg-misnamed-{}.pdf", std::process::id()));
+  std::fs::copy(&test_file, &misnamed).expect("Failed to copy test file");
+
+  let output = Command::new(env!("CARGO_BIN_EXE_hygg"))
+    .arg("--list-converters")
+    .arg(misnamed.to_str().unwrap())
+    .output()
+    .expect("Failed to execute hygg");
+  let _ = std::fs::remove_file(&misnamed);
+
+  assert!(output.status.success(), "hygg should exit successfully");
+
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  assert!(
+    stdout.contains("1. epub (content is EPUB e-book)"),
+    "Content should win over the .pdf extension"
+  );
+  assert!(
+    stdout.contains("2. pdf (handles .pdf files)"),
+    "Extension match should still be tried"
+  );
+}
+
+#[test]
+fn test_cache_stats_and_clear() {
+  let cache_dir =
+    std::env::temp_dir().join(format!("hygg-cache-{}", std::process::id()));
+  let _ = std::fs::remove_dir_all(&cache_dir);
+  let test_file = Path::new(env!("CARGO_MANIFEST_DIR"))
+    .parent()
+    .unwrap()
+    .join("test-data/sample.txt");
+
+  let run = |args: &[&str]| {
+    let output = Command::new(env!("CARGO_BIN_EXE_hygg"))
+      .env("HYGG_CACHE_DIR", &cache_dir)
+      .env("HYGG_CACHE_SIZE", "64")
+      .env("ENABLE_TUTORIAL", "false")
+      .args(args)
+      .stdin(Stdio::null())
+      .output()
+      .expect("Failed to execute hygg");
+    assert!(output.status.success(), "hygg should exit successfully");
+    String::from_utf8_lossy(&output.stdout).into_owned()
+  };
+
+  // Reading a document caches it
+  run(&[test_file.to_str().unwrap()]);
+  let stats = run(&["cache", "stats"]);
+  assert!(stats.contains("Documents: 1"), "{stats}");
+  assert!(stats.contains("of 64.0 MiB"), "{stats}");
+  assert!(run(&["cache", "clear"]).contains("Removed 1 cached document(s)"));
+  assert!(run(&["cache", "stats"]).contains("Documents: 0"));
+
+  let _ = std::fs::remove_dir_all(&cache_dir);
+}
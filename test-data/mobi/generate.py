@@ -0,0 +1,231 @@
+#!/usr/bin/env python3
+"""Write the MOBI and AZW3 fixtures of this directory.
+
+test.mobi is a MOBI 6 book with PalmDOC compressed text, EXTH metadata,
+a cover image and trailing entries on its text records. test.azw3 is a KF8
+book with HUFF/CDIC compressed text. Run from this directory.
+"""
+
+import struct
+
+RECORD_SIZE = 4096
+
+# A 1x1 PNG
+COVER = bytes.fromhex(
+    "89504e470d0a1a0a0000000d4948445200000001000000010806000000"
+    "1f15c4890000000d49444154789c6360000002000154a24f5d00000000"
+    "49454e44ae426082"
+)
+
+PARAGRAPH = (
+    "<p>The keeper climbed the stairs every night and lit the lamp, "
+    "then sat by the window and watched the sea until morning.</p>"
+)
+
+
+def mobi_html():
+    head = "<html><head><guide></guide></head><body>"
+    first = "<h1>Chapter 1</h1>" + PARAGRAPH * 30
+    # Pad with spaces so that the "é" is split across two text records
+    split_at = len((head + first + "<p>Caf").encode()) + 1
+    first += " " * (-split_at % RECORD_SIZE) + "<p>Café au lait — at dawn.</p>"
+    second = "<h1>Chapter 2</h1><p>The storm came.</p>"
+    return f"{head}{first}<mbp:pagebreak/>{second}</body></html>"
+
+
+def azw3_html():
+    files = [
+        "<h1>Chapter 1</h1><p>The keeper climbed the stairs.</p>",
+        "<h1>Chapter 2</h1><p>The storm came, and the light held.</p>",
+    ]
+    return "".join(
+        '<?xml version="1.0" encoding="utf-8"?>'
+        '<html xmlns="http://www.w3.org/1999/xhtml"><head><title>t</title>'
+        f'</head><body aid="0">{body}</body></html>'
+        for body in files
+    )
+
+
+def palmdoc_compress(data):
+    out = bytearray()
+    i = 0
+    while i < len(data):
+        best_len, best_dist = 0, 0
+        for dist in range(1, min(i, 2047) + 1):
+            length = 0
+            while (
+                length < 10
+                and i + length < len(data)
+                and data[i + length - dist] == data[i + length]
+            ):
+                length += 1
+            if length > best_len:
+                best_len, best_dist = length, dist
+        if best_len >= 3:
+            pair = (best_dist << 3) | (best_len - 3)
+            out += bytes([0x80 | (pair >> 8), pair & 0xFF])
+            i += best_len
+            continue
+        byte = data[i]
+        if byte == 0x20 and i + 1 < len(data) and 0x40 <= data[i + 1] <= 0x7F:
+            out.append(data[i + 1] ^ 0x80)
+            i += 2
+        elif byte == 0 or 0x09 <= byte <= 0x7F:
+            out.append(byte)
+            i += 1
+        else:
+            run = bytearray()
+            while (
+                i < len(data)
+                and len(run) < 8
+                and not (data[i] == 0 or 0x09 <= data[i] <= 0x7F)
+            ):
+                run.append(data[i])
+                i += 1
+            out.append(len(run))
+            out += run
+    return bytes(out)
+
+
+def huff_compress(data):
+    # Every byte is a phrase of its own, coded as the inverse of its value
+    return bytes(255 - byte for byte in data)
+
+
+def huff_records():
+    header = b"HUFF" + struct.pack(">LLL", 0x18, 0x18, 0x18 + 1024) + bytes(8)
+    # Code length 8, terminal, max code 255
+    dict1 = struct.pack(">256L", *([(255 << 8) | 0x80 | 8] * 256))
+    dict2 = bytes(256)
+    huff = header + dict1 + dict2
+
+    offsets = []
+    phrases = bytearray()
+    for byte in range(256):
+        offsets.append(256 * 2 + len(phrases))
+        phrases += struct.pack(">H", 0x8000 | 1) + bytes([byte])
+    cdic = b"CDIC" + struct.pack(">LLL", 0x10, 256, 8)
+    cdic += struct.pack(">256H", *offsets) + phrases
+    return [huff, cdic]
+
+
+def text_records(text, compress, multibyte):
+    records = []
+    for start in range(0, len(text), RECORD_SIZE):
+        record = compress(text[start : start + RECORD_SIZE])
+        if multibyte:
+            # The rest of a character that continues in the next record
+            rest = text[start + RECORD_SIZE : start + RECORD_SIZE + 3]
+            count = 0
+            while count < len(rest) and rest[count] & 0xC0 == 0x80:
+                count += 1
+            record += rest[:count] + bytes([count])
+        # A trailing entry of three bytes, its size ending the record
+        record += b"\x00\x00\x83"
+        records.append(record)
+    return records
+
+
+def exth(entries):
+    body = b"".join(
+        struct.pack(">LL", kind, 8 + len(value)) + value for kind, value in entries
+    )
+    length = 12 + len(body)
+    padding = (4 - length % 4) % 4
+    return b"EXTH" + struct.pack(">LL", length, len(entries)) + body + bytes(padding)
+
+
+def record0(text_len, text_count, compression, version, extra, title, entries):
+    header_len = 0xE8 if version < 8 else 0x108
+    exth_block = exth(entries)
+    name_offset = 16 + header_len + len(exth_block)
+    first_image, huff_offset, huff_count = extra
+
+    palmdoc = struct.pack(
+        ">HHLHHHH", compression, 0, text_len, text_count, RECORD_SIZE, 0, 0
+    )
+    mobi = bytearray(header_len)
+    mobi[0:4] = b"MOBI"
+    struct.pack_into(">LLLLL", mobi, 4, header_len, 2, 65001, 0x1234, version)
+    for at in range(40, 80, 4):
+        struct.pack_into(">L", mobi, at - 16, 0xFFFFFFFF)
+    struct.pack_into(">L", mobi, 80 - 16, first_image)
+    struct.pack_into(">LL", mobi, 84 - 16, name_offset, len(title))
+    struct.pack_into(">L", mobi, 92 - 16, 9)
+    struct.pack_into(">L", mobi, 104 - 16, version)
+    struct.pack_into(">LLL", mobi, 108 - 16, first_image, huff_offset, huff_count)
+    struct.pack_into(">L", mobi, 128 - 16, 0x40)
+    struct.pack_into(">L", mobi, 168 - 16, 0xFFFFFFFF)
+    # Multibyte overlap and one more trailing entry
+    flags = 0b11 if compression == 2 else 0b10
+    struct.pack_into(">H", mobi, 0xF2 - 16, flags)
+
+    name = title + bytes(2 + (4 - (len(title) + 2) % 4) % 4)
+    return palmdoc + bytes(mobi) + exth_block + name
+
+
+def palmdb(name, records):
+    header = bytearray(78)
+    header[0 : len(name)] = name
+    struct.pack_into(">HH", header, 32, 0, 0)
+    header[60:68] = b"BOOKMOBI"
+    struct.pack_into(">H", header, 76, len(records))
+    offset = 78 + 8 * len(records) + 2
+    infos = bytearray()
+    for index, record in enumerate(records):
+        infos += struct.pack(">LL", offset, index * 2)
+        offset += len(record)
+    return bytes(header) + bytes(infos) + b"\x00\x00" + b"".join(records)
+
+
+def write_mobi():
+    text = mobi_html().encode()
+    records = text_records(text, palmdoc_compress, multibyte=True)
+    first_image = len(records) + 1
+    entries = [
+        (100, b"Jane Doe"),
+        (100, b"Richard Roe"),
+        (101, b"Hygg Press"),
+        (103, b"A keeper and a storm."),
+        (104, b"978-0-306-40615-7"),
+        (105, b"Fiction"),
+        (106, b"2022-05-01"),
+        (524, b"en"),
+        (201, struct.pack(">L", 0)),
+    ]
+    header = record0(
+        len(text),
+        len(records),
+        2,
+        6,
+        (first_image, 0, 0),
+        b"The Lighthouse",
+        entries,
+    )
+    book = palmdb(b"The_Lighthouse", [header] + records + [COVER, b"\xe9\x8e\r\n"])
+    with open("test.mobi", "wb") as file:
+        file.write(book)
+
+
+def write_azw3():
+    text = azw3_html().encode()
+    records = text_records(text, huff_compress, multibyte=False)
+    huff_offset = len(records) + 1
+    entries = [(100, b"Jane Doe"), (503, b"The Lighthouse (KF8)"), (524, b"en")]
+    header = record0(
+        len(text),
+        len(records),
+        17480,
+        8,
+        (0xFFFFFFFF, huff_offset, 2),
+        b"The Lighthouse",
+        entries,
+    )
+    book = palmdb(b"The_Lighthouse", [header] + records + huff_records())
+    with open("test.azw3", "wb") as file:
+        file.write(book)
+
+
+if __name__ == "__main__":
+    write_mobi()
+    write_azw3()